@@ -0,0 +1,64 @@
+//! Compares acquiring machines from a [`MachinePool`] with constructing new machines.
+//!
+//! >> cargo bench --bench pool
+
+use adar::prelude::*;
+use std::{
+    collections::HashMap,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+const ITERATIONS: u32 = 100_000;
+const HEADERS: u32 = 64;
+
+#[StateEnum(context = HashMap<u32, u32>, initial = Receiving)]
+enum Session {
+    Receiving,
+    Closed,
+}
+
+impl Machine for Session {}
+
+impl State for Receiving {
+    fn on_update(
+        &mut self,
+        _args: Option<&mut Self::Args>,
+        context: &mut Self::Context,
+    ) -> Option<Self::States> {
+        for header in 0..black_box(HEADERS) {
+            context.insert(header, header);
+        }
+        Some(Closed.into())
+    }
+}
+
+impl State for Closed {}
+
+fn measure(name: &str, mut f: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{name:<8} {:>10.2?} total {:>8.2?} / machine",
+        elapsed,
+        Duration::from_secs_f64(elapsed.as_secs_f64() / ITERATIONS as f64)
+    );
+}
+
+fn main() {
+    measure("new", || {
+        let mut machine = StateMachine::<Session>::start();
+        machine.update();
+        black_box(&machine);
+    });
+
+    let pool = MachinePool::<Session>::new(HashMap::new, HashMap::clear);
+    measure("pooled", || {
+        let mut machine = pool.acquire();
+        machine.update();
+        black_box(&machine);
+    });
+}
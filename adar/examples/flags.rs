@@ -14,8 +14,8 @@ fn main() {
     let mut b = MyFlag::F1 | MyFlag::F2 | MyFlag::F3;
     b.reset(MyFlag::F1);
 
-    println!("a: {:?}, {:03b}", a, a.into_raw()); // Prints: (F1|F2), 011
-    println!("b: {:?}, {:03b}", b, b.into_raw()); // Prints: (F2|F3), 110
+    println!("a: {:?}, {:03b}", a, a); // Prints: (F1|F2), 011
+    println!("b: {:?}, {:03b}", b, b); // Prints: (F2|F3), 110
 
     println!("a.any(F1,F3): {:?}", a.any(MyFlag::F1 | MyFlag::F3)); // Prints: true
     println!("a.all(F1,F3): {:?}", a.all(MyFlag::F1 | MyFlag::F3)); // Prints: false
@@ -19,6 +19,7 @@ where
 #[StateEnum(context=for<T> Context<T> where T: Debug)]
 // Derive always have to come after the #[StateEnum] macro
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 enum States {
     // You can store variables inside the states
     CountState(u32),
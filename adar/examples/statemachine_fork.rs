@@ -16,7 +16,10 @@ enum ForkB {
 
 #[StateEnum]
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 enum MyState {
+    // Both regions are updated at each tick, the machine moves on when both are finished
+    #[regions(then = EndState)]
     StateAB {
         a: StateMachine<ForkA>,
         b: StateMachine<ForkB>,
@@ -25,19 +28,7 @@ enum MyState {
 }
 
 impl Machine for MyState {}
-impl State for StateAB {
-    fn on_update(
-        &mut self,
-        _args: Option<&mut Self::Args>,
-        _context: &mut Self::Context,
-    ) -> Option<Self::States> {
-        self.a.update();
-        self.b.update();
-
-        // Check if both branches finished
-        (self.a.is_finished() && self.b.is_finished()).then_some(EndState.into())
-    }
-}
+impl State for StateAB {}
 
 impl Machine for ForkA {}
 impl State for StateA {
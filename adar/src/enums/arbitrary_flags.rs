@@ -0,0 +1,87 @@
+//! Property-based testing support for [`Flags`] with [arbitrary](https://docs.rs/arbitrary) and
+//! [proptest](https://docs.rs/proptest). Only the bits of the declared flags are generated.
+
+use crate::prelude::{FlagTypeConstraints, Flags, ReflectEnum};
+
+#[cfg(feature = "arbitrary")]
+impl<'a, E> arbitrary::Arbitrary<'a> for Flags<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints,
+{
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut flags = Self::empty();
+        for flag in Self::full() {
+            flags.set_value(flag, u.arbitrary()?);
+        }
+        Ok(flags)
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, Some(E::count()))
+    }
+}
+
+#[cfg(feature = "proptest")]
+impl<E> proptest::arbitrary::Arbitrary for Flags<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + core::fmt::Debug + 'static,
+    E::Type: FlagTypeConstraints,
+{
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        use proptest::prelude::*;
+
+        proptest::collection::vec(any::<bool>(), E::count())
+            .prop_map(|bits| {
+                Self::full()
+                    .into_iter()
+                    .zip(bits)
+                    .filter_map(|(flag, set)| set.then_some(flag))
+                    .collect()
+            })
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+
+    #[FlagEnum]
+    #[derive(Debug)]
+    enum TestArbitrary {
+        A = 0b1,
+        B = 0b100,
+        C = 0b10000,
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let mut u = Unstructured::new(&[1, 0, 1, 1, 1, 1]);
+        let flags = Flags::<TestArbitrary>::arbitrary(&mut u).unwrap();
+        assert_eq!(flags, TestArbitrary::A | TestArbitrary::C);
+        assert_eq!(
+            Flags::<TestArbitrary>::arbitrary(&mut u).unwrap(),
+            Flags::<TestArbitrary>::full()
+        );
+        assert_eq!(
+            Flags::<TestArbitrary>::arbitrary(&mut u).unwrap(),
+            Flags::empty()
+        );
+    }
+
+    #[cfg(feature = "proptest")]
+    proptest::proptest! {
+        #[test]
+        fn test_proptest(flags: Flags<TestArbitrary>) {
+            proptest::prop_assert_eq!(Flags::try_from_raw(flags.into_raw()), Some(flags));
+            proptest::prop_assert_eq!(flags.complement().complement(), flags);
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! Zero-copy archiving of [`Flags`] and unit enums with [rkyv](https://docs.rs/rkyv).
+
+use crate::prelude::{FlagTypeConstraints, Flags, ReflectEnum};
+use core::{fmt::Display, marker::PhantomData};
+use rkyv::{
+    bytecheck::CheckBytes,
+    rancor::{Fallible, Source},
+    Archive, Archived, Deserialize, Place, Portable, Serialize,
+};
+
+/// Error reported when an archived value does not represent a valid [`Flags`] or enum value.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct InvalidArchive;
+
+impl Display for InvalidArchive {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Archived value is not valid")
+    }
+}
+
+impl core::error::Error for InvalidArchive {}
+
+/// Archived [`Flags`]. Stores the raw value, which is validated by [`rkyv::access()`] and on access.
+#[repr(transparent)]
+pub struct ArchivedFlags<E>
+where
+    E: ReflectEnum,
+    E::Type: Archive,
+{
+    raw: Archived<E::Type>,
+    phantom: PhantomData<E>,
+}
+
+impl<E> ArchivedFlags<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints + Archive,
+    Archived<E::Type>: Copy + Into<E::Type>,
+{
+    /// Gets the archived flags.
+    ///
+    /// # Returns
+    /// `None` if the archived value has bits set which do not correspond to any flag.
+    pub fn get(&self) -> Option<Flags<E>> {
+        Flags::try_from_raw(self.raw.into())
+    }
+}
+
+// SAFETY: `ArchivedFlags` is a transparent wrapper around a portable value.
+unsafe impl<E> Portable for ArchivedFlags<E>
+where
+    E: ReflectEnum,
+    E::Type: Archive,
+{
+}
+
+// SAFETY: The raw value is checked first, so it can be read to validate the flags.
+unsafe impl<E, C> CheckBytes<C> for ArchivedFlags<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints + Archive,
+    Archived<E::Type>: Copy + Into<E::Type> + CheckBytes<C>,
+    C: Fallible + ?Sized,
+    C::Error: Source,
+{
+    unsafe fn check_bytes(value: *const Self, context: &mut C) -> Result<(), C::Error> {
+        unsafe {
+            Archived::<E::Type>::check_bytes(value.cast(), context)?;
+            (*value)
+                .get()
+                .map(|_| ())
+                .ok_or_else(|| C::Error::new(InvalidArchive))
+        }
+    }
+}
+
+impl<E> Archive for Flags<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints + Archive,
+{
+    type Archived = ArchivedFlags<E>;
+    type Resolver = <E::Type as Archive>::Resolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        // SAFETY: `ArchivedFlags` is a transparent wrapper around the archived raw value.
+        let out = unsafe { out.cast_unchecked::<Archived<E::Type>>() };
+        self.into_raw().resolve(resolver, out);
+    }
+}
+
+impl<E, S> Serialize<S> for Flags<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints + Serialize<S>,
+    S: Fallible + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        self.into_raw().serialize(serializer)
+    }
+}
+
+impl<E, D> Deserialize<Flags<E>, D> for ArchivedFlags<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints + Archive,
+    Archived<E::Type>: Copy + Into<E::Type>,
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize(&self, _deserializer: &mut D) -> Result<Flags<E>, D::Error> {
+        self.get().ok_or_else(|| D::Error::new(InvalidArchive))
+    }
+}
+
+/// Conversion between unit enums and their discriminants.
+/// Implemented by [`crate::macros::ReflectEnum`] for unit enums when the `rkyv` feature is enabled.
+pub trait ArchiveUnitEnum: ReflectEnum + Sized {
+    fn to_raw(&self) -> Self::Type;
+    fn from_raw(raw: Self::Type) -> Option<Self>;
+}
+
+/// Archived unit enum. Stores the discriminant, which is validated by [`rkyv::access()`] and on access.
+#[repr(transparent)]
+pub struct ArchivedEnum<E>
+where
+    E: ReflectEnum,
+    E::Type: Archive,
+{
+    raw: Archived<E::Type>,
+    phantom: PhantomData<E>,
+}
+
+impl<E> ArchivedEnum<E>
+where
+    E: ArchiveUnitEnum,
+    E::Type: Archive,
+    Archived<E::Type>: Copy + Into<E::Type>,
+{
+    /// Gets the archived enum value.
+    ///
+    /// # Returns
+    /// `None` if the archived discriminant does not correspond to any variant.
+    pub fn get(&self) -> Option<E> {
+        E::from_raw(self.raw.into())
+    }
+
+    #[doc(hidden)]
+    pub fn resolve_enum(
+        value: &E,
+        resolver: <E::Type as Archive>::Resolver,
+        out: Place<ArchivedEnum<E>>,
+    ) {
+        // SAFETY: `ArchivedEnum` is a transparent wrapper around the archived discriminant.
+        let out = unsafe { out.cast_unchecked::<Archived<E::Type>>() };
+        value.to_raw().resolve(resolver, out);
+    }
+}
+
+// SAFETY: `ArchivedEnum` is a transparent wrapper around a portable value.
+unsafe impl<E> Portable for ArchivedEnum<E>
+where
+    E: ReflectEnum,
+    E::Type: Archive,
+{
+}
+
+// SAFETY: The raw value is checked first, so it can be read to validate the discriminant.
+unsafe impl<E, C> CheckBytes<C> for ArchivedEnum<E>
+where
+    E: ArchiveUnitEnum,
+    E::Type: Archive,
+    Archived<E::Type>: Copy + Into<E::Type> + CheckBytes<C>,
+    C: Fallible + ?Sized,
+    C::Error: Source,
+{
+    unsafe fn check_bytes(value: *const Self, context: &mut C) -> Result<(), C::Error> {
+        unsafe {
+            Archived::<E::Type>::check_bytes(value.cast(), context)?;
+            (*value)
+                .get()
+                .map(|_| ())
+                .ok_or_else(|| C::Error::new(InvalidArchive))
+        }
+    }
+}
+
+impl<E, D> Deserialize<E, D> for ArchivedEnum<E>
+where
+    E: ArchiveUnitEnum,
+    E::Type: Archive,
+    Archived<E::Type>: Copy + Into<E::Type>,
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize(&self, _deserializer: &mut D) -> Result<E, D::Error> {
+        self.get().ok_or_else(|| D::Error::new(InvalidArchive))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+    use rkyv::rancor::Error;
+
+    #[FlagEnum]
+    #[derive(Debug, PartialEq)]
+    #[repr(u8)]
+    enum TestFlags {
+        F1,
+        F2,
+        F3,
+    }
+
+    #[ReflectEnum]
+    #[derive(Debug, PartialEq)]
+    #[repr(u16)]
+    enum TestEnum {
+        A = 3,
+        B = 700,
+    }
+
+    #[test]
+    fn test_archive_flags() {
+        let flags = TestFlags::F1 | TestFlags::F3;
+        let bytes = rkyv::to_bytes::<Error>(&flags).unwrap();
+        assert_eq!(bytes.as_slice(), &[0b101]);
+
+        let archived = rkyv::access::<ArchivedFlags<TestFlags>, Error>(&bytes).unwrap();
+        assert_eq!(archived.get(), Some(flags));
+        assert_eq!(
+            rkyv::deserialize::<Flags<TestFlags>, Error>(archived).unwrap(),
+            flags
+        );
+
+        assert!(rkyv::access::<ArchivedFlags<TestFlags>, Error>(&[0b1000]).is_err());
+    }
+
+    #[test]
+    fn test_archive_enum() {
+        let bytes = rkyv::to_bytes::<Error>(&TestEnum::B).unwrap();
+        let archived = rkyv::access::<ArchivedEnum<TestEnum>, Error>(&bytes).unwrap();
+        assert_eq!(archived.get(), Some(TestEnum::B));
+        assert_eq!(
+            rkyv::deserialize::<TestEnum, Error>(archived).unwrap(),
+            TestEnum::B
+        );
+
+        let invalid = rkyv::to_bytes::<Error>(&4u16).unwrap();
+        assert!(rkyv::access::<ArchivedEnum<TestEnum>, Error>(&invalid).is_err());
+    }
+}
@@ -0,0 +1,282 @@
+//! [`AtomicFlags`] is a [`Flags`] container which can be shared across threads.
+
+use crate::prelude::{FlagTypeConstraints, Flags, ReflectEnum};
+use core::sync::atomic::Ordering;
+
+/// [`Flags`] container which can be modified through a shared reference.
+///
+/// Available for the storages which have atomic counterparts on the target.
+///
+/// The modifications are single atomic operations with [`Ordering::AcqRel`], the reads use [`Ordering::Acquire`].
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+/// use std::sync::Arc;
+///
+/// #[FlagEnum]
+/// #[derive(Debug)]
+/// enum Ready {Audio, Video, Network}
+///
+/// let ready = Arc::new(AtomicFlags::<Ready>::empty());
+/// let ready2 = ready.clone();
+/// std::thread::spawn(move || ready2.set(Ready::Audio | Ready::Video)).join().unwrap();
+/// assert!(ready.all(Ready::Audio | Ready::Video));
+/// assert_eq!(ready.reset(Ready::Audio), Ready::Audio | Ready::Video);
+/// assert_eq!(ready.load(), Ready::Video);
+/// ```
+pub struct AtomicFlags<E>(<E::Type as AtomicFlagType>::Atomic)
+where
+    E: ReflectEnum,
+    E::Type: AtomicFlagType;
+
+impl<E> AtomicFlags<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints + AtomicFlagType,
+{
+    /// Creates a new [`AtomicFlags`] with the specified flags set.
+    pub fn new(flags: impl Into<Flags<E>>) -> Self {
+        Self(E::Type::new_atomic(flags.into().into_raw()))
+    }
+
+    /// Creates a new [`AtomicFlags`] with no flags set.
+    pub fn empty() -> Self {
+        Self::new(Flags::empty())
+    }
+
+    /// Gets the flags which are currently set.
+    pub fn load(&self) -> Flags<E> {
+        Flags::__from_raw_unchecked(E::Type::load(&self.0, Ordering::Acquire))
+    }
+
+    /// Replaces the flags.
+    ///
+    /// # Returns
+    /// The previous flags.
+    pub fn store(&self, flags: impl Into<Flags<E>>) -> Flags<E> {
+        Flags::__from_raw_unchecked(E::Type::swap(
+            &self.0,
+            flags.into().into_raw(),
+            Ordering::AcqRel,
+        ))
+    }
+
+    /// Sets the specified flags.
+    ///
+    /// # Returns
+    /// The previous flags.
+    pub fn set(&self, flags: impl Into<Flags<E>>) -> Flags<E> {
+        Flags::__from_raw_unchecked(E::Type::fetch_or(
+            &self.0,
+            flags.into().into_raw(),
+            Ordering::AcqRel,
+        ))
+    }
+
+    /// Resets the specified flags.
+    ///
+    /// # Returns
+    /// The previous flags.
+    pub fn reset(&self, flags: impl Into<Flags<E>>) -> Flags<E> {
+        Flags::__from_raw_unchecked(E::Type::fetch_and(
+            &self.0,
+            !flags.into().into_raw(),
+            Ordering::AcqRel,
+        ))
+    }
+
+    /// Toggles the specified flags.
+    ///
+    /// # Returns
+    /// The previous flags.
+    pub fn toggle(&self, flags: impl Into<Flags<E>>) -> Flags<E> {
+        Flags::__from_raw_unchecked(E::Type::fetch_xor(
+            &self.0,
+            flags.into().into_raw(),
+            Ordering::AcqRel,
+        ))
+    }
+
+    /// Checks if all of the flags are set. See [`Flags::all()`].
+    pub fn all(&self, flags: impl Into<Flags<E>>) -> bool {
+        self.load().all(flags)
+    }
+
+    /// Checks if any of the flags are set. See [`Flags::any()`].
+    pub fn any(&self, flags: impl Into<Flags<E>>) -> bool {
+        self.load().any(flags)
+    }
+
+    /// Converts `self` into the flags.
+    pub fn into_inner(self) -> Flags<E> {
+        self.load()
+    }
+}
+
+impl<E> Default for AtomicFlags<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints + AtomicFlagType,
+{
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<E> From<Flags<E>> for AtomicFlags<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints + AtomicFlagType,
+{
+    fn from(flags: Flags<E>) -> Self {
+        Self::new(flags)
+    }
+}
+
+impl<E> core::fmt::Debug for AtomicFlags<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints + AtomicFlagType,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.load().fmt(f)
+    }
+}
+
+/// Storage of the [`Flags`] which has an atomic counterpart.
+#[doc(hidden)]
+pub trait AtomicFlagType: Sized {
+    type Atomic;
+
+    fn new_atomic(value: Self) -> Self::Atomic;
+    fn load(atomic: &Self::Atomic, ordering: Ordering) -> Self;
+    fn swap(atomic: &Self::Atomic, value: Self, ordering: Ordering) -> Self;
+    fn fetch_or(atomic: &Self::Atomic, value: Self, ordering: Ordering) -> Self;
+    fn fetch_and(atomic: &Self::Atomic, value: Self, ordering: Ordering) -> Self;
+    fn fetch_xor(atomic: &Self::Atomic, value: Self, ordering: Ordering) -> Self;
+}
+
+macro_rules! impl_atomic_flag_type {
+    ($($type:ty => $atomic:ident ($width:literal)),*) => {
+        $(
+            #[cfg(target_has_atomic = $width)]
+            impl AtomicFlagType for $type {
+                type Atomic = core::sync::atomic::$atomic;
+
+                #[inline(always)]
+                fn new_atomic(value: Self) -> Self::Atomic {
+                    Self::Atomic::new(value)
+                }
+
+                #[inline(always)]
+                fn load(atomic: &Self::Atomic, ordering: Ordering) -> Self {
+                    atomic.load(ordering)
+                }
+
+                #[inline(always)]
+                fn swap(atomic: &Self::Atomic, value: Self, ordering: Ordering) -> Self {
+                    atomic.swap(value, ordering)
+                }
+
+                #[inline(always)]
+                fn fetch_or(atomic: &Self::Atomic, value: Self, ordering: Ordering) -> Self {
+                    atomic.fetch_or(value, ordering)
+                }
+
+                #[inline(always)]
+                fn fetch_and(atomic: &Self::Atomic, value: Self, ordering: Ordering) -> Self {
+                    atomic.fetch_and(value, ordering)
+                }
+
+                #[inline(always)]
+                fn fetch_xor(atomic: &Self::Atomic, value: Self, ordering: Ordering) -> Self {
+                    atomic.fetch_xor(value, ordering)
+                }
+            }
+        )*
+    };
+}
+
+impl_atomic_flag_type!(
+    u8 => AtomicU8("8"),
+    u16 => AtomicU16("16"),
+    u32 => AtomicU32("32"),
+    u64 => AtomicU64("64"),
+    usize => AtomicUsize("ptr"),
+    i8 => AtomicI8("8"),
+    i16 => AtomicI16("16"),
+    i32 => AtomicI32("32"),
+    i64 => AtomicI64("64"),
+    isize => AtomicIsize("ptr")
+);
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+    use std::{sync::Arc, thread};
+
+    #[FlagEnum]
+    #[derive(Debug)]
+    enum TestAtomic {
+        F1,
+        F2,
+        F3,
+        F4,
+    }
+
+    #[FlagEnum(storage = u8)]
+    #[derive(Debug)]
+    enum TestAtomicU8 {
+        F1,
+        F2,
+    }
+
+    #[test]
+    fn test_atomic_flags() {
+        let flags = AtomicFlags::new(TestAtomic::F1);
+        assert_eq!(flags.set(TestAtomic::F2 | TestAtomic::F3), TestAtomic::F1);
+        assert_eq!(
+            flags.toggle(TestAtomic::F1 | TestAtomic::F4),
+            TestAtomic::F1 | TestAtomic::F2 | TestAtomic::F3
+        );
+        assert_eq!(
+            flags.load(),
+            TestAtomic::F2 | TestAtomic::F3 | TestAtomic::F4
+        );
+        flags.reset(TestAtomic::F3);
+        assert!(flags.all(TestAtomic::F2 | TestAtomic::F4));
+        assert!(!flags.any(TestAtomic::F1 | TestAtomic::F3));
+        assert_eq!(flags.store(()), TestAtomic::F2 | TestAtomic::F4);
+        assert_eq!(flags.into_inner(), ());
+
+        let small = AtomicFlags::<TestAtomicU8>::default();
+        assert_eq!(size_of_val(&small), size_of::<u8>());
+        small.set(TestAtomicU8::F2);
+        assert_eq!(format!("{:?}", small), "(F2)");
+    }
+
+    #[test]
+    fn test_atomic_flags_threads() {
+        let flags = Arc::new(AtomicFlags::<TestAtomic>::empty());
+        let threads = [
+            TestAtomic::F1,
+            TestAtomic::F2,
+            TestAtomic::F3,
+            TestAtomic::F4,
+        ]
+        .map(|flag| {
+            let flags = flags.clone();
+            thread::spawn(move || {
+                for _ in 0..1000 {
+                    flags.toggle(flag);
+                }
+                flags.set(flag);
+            })
+        });
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert_eq!(flags.load(), Flags::<TestAtomic>::full());
+    }
+}
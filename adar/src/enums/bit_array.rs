@@ -0,0 +1,312 @@
+//! [`BitArray`] is the storage of [`crate::enums::Flags`] for enums with more than 128 flags.
+
+use crate::enums::FlagTypeConstraints;
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String};
+use core::ops::{BitAnd, BitOr, BitXor, Not};
+
+/// Fixed size bit set backed by `[u64; N]`.
+///
+/// [`crate::macros::FlagEnum`] chooses it as the storage when the flags do not fit into a `u128`,
+/// or when `#[FlagEnum(storage = big)]` is specified. The flags keep the same API as the ones stored
+/// in an integer.
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+///
+/// #[FlagEnum(storage = big)]
+/// #[derive(Debug)]
+/// enum Permission {Read, Write, Delete}
+///
+/// let flags = Permission::Read | Permission::Delete;
+/// assert_eq!(flags.into_raw(), BitArray::<1>::from_words([0b101]));
+/// assert!(flags.all(Permission::Delete));
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct BitArray<const N: usize>([u64; N]);
+
+impl<const N: usize> BitArray<N> {
+    /// Creates a [`BitArray`] with no bits set.
+    pub const fn new() -> Self {
+        Self([0; N])
+    }
+
+    /// Creates a [`BitArray`] from words, the first word holds the lowest bits.
+    pub const fn from_words(words: [u64; N]) -> Self {
+        Self(words)
+    }
+
+    /// Creates a [`BitArray`] with only the bit at `index` set.
+    ///
+    /// # Panics
+    /// If `index` is out of range.
+    pub const fn with_bit(index: usize) -> Self {
+        let mut words = [0; N];
+        words[index / 64] = 1 << (index % 64);
+        Self(words)
+    }
+
+    /// Gets the words, the first word holds the lowest bits.
+    pub const fn words(&self) -> &[u64; N] {
+        &self.0
+    }
+
+    fn zip(self, rhs: Self, op: impl Fn(u64, u64) -> u64) -> Self {
+        Self(core::array::from_fn(|i| op(self.0[i], rhs.0[i])))
+    }
+}
+
+impl<const N: usize> Default for BitArray<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compares the words from the highest one, like the integers.
+impl<const N: usize> PartialOrd for BitArray<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compares the words from the highest one, like the integers.
+impl<const N: usize> Ord for BitArray<N> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize> BitArray<N> {
+    fn fmt_words(
+        &self,
+        f: &mut core::fmt::Formatter<'_>,
+        prefix: &str,
+        word: impl Fn(u64, usize) -> String,
+    ) -> core::fmt::Result {
+        let mut words = self.0.iter().rev().skip_while(|&&word| word == 0);
+        let digits = match words.next() {
+            Some(&highest) => words.fold(word(highest, 0), |digits, &w| digits + &word(w, 1)),
+            None => String::from("0"),
+        };
+        f.pad_integral(true, prefix, &digits)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize> core::fmt::Binary for BitArray<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.fmt_words(f, "0b", |word, width| {
+            format!("{:0w$b}", word, w = width * 64)
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize> core::fmt::LowerHex for BitArray<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.fmt_words(f, "0x", |word, width| {
+            format!("{:0w$x}", word, w = width * 16)
+        })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize> core::fmt::UpperHex for BitArray<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.fmt_words(f, "0x", |word, width| {
+            format!("{:0w$X}", word, w = width * 16)
+        })
+    }
+}
+
+impl<const N: usize> Not for BitArray<N> {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self(self.0.map(|word| !word))
+    }
+}
+
+impl<const N: usize> BitAnd for BitArray<N> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.zip(rhs, |a, b| a & b)
+    }
+}
+
+impl<const N: usize> BitOr for BitArray<N> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.zip(rhs, |a, b| a | b)
+    }
+}
+
+impl<const N: usize> BitXor for BitArray<N> {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        self.zip(rhs, |a, b| a ^ b)
+    }
+}
+
+impl<const N: usize> FlagTypeConstraints for BitArray<N> {
+    fn zero() -> Self {
+        Self::new()
+    }
+
+    fn count_ones(self) -> u32 {
+        self.0.iter().map(|word| word.count_ones()).sum()
+    }
+
+    fn trailing_zeros(self) -> u32 {
+        match self.0.iter().position(|&word| word != 0) {
+            Some(i) => i as u32 * 64 + self.0[i].trailing_zeros(),
+            None => N as u32 * 64,
+        }
+    }
+
+    fn clear_lowest_bit(mut self) -> Self {
+        if let Some(word) = self.0.iter_mut().find(|word| **word != 0) {
+            *word &= *word - 1;
+        }
+        self
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for BitArray<N> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(&self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'d, const N: usize> serde::Deserialize<'d> for BitArray<N> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'d>,
+    {
+        deserializer.deserialize_seq(WordsVisitor::<N>)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct WordsVisitor<const N: usize>;
+
+#[cfg(feature = "serde")]
+impl<'d, const N: usize> serde::de::Visitor<'d> for WordsVisitor<N> {
+    type Value = BitArray<N>;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(formatter, "an array of {} words", N)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'d>,
+    {
+        let mut words = [0; N];
+        for (i, word) in words.iter_mut().enumerate() {
+            *word = seq
+                .next_element()?
+                .ok_or_else(|| serde::de::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<serde::de::IgnoredAny>()?.is_some() {
+            return Err(serde::de::Error::invalid_length(N + 1, &self));
+        }
+        Ok(BitArray(words))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+
+    macro_rules! big_flag_enum {
+        ($($variant:ident)*) => {
+            #[FlagEnum]
+            #[derive(Debug, Eq, PartialEq)]
+            enum TestBig {
+                $($variant),*
+            }
+        };
+    }
+
+    big_flag_enum!(
+        F0 F1 F2 F3 F4 F5 F6 F7 F8 F9 F10 F11 F12 F13 F14 F15 F16 F17 F18 F19 F20 F21 F22 F23 F24 F25 F26
+        F27 F28 F29 F30 F31 F32 F33 F34 F35 F36 F37 F38 F39 F40 F41 F42 F43 F44 F45 F46 F47 F48 F49 F50
+        F51 F52 F53 F54 F55 F56 F57 F58 F59 F60 F61 F62 F63 F64 F65 F66 F67 F68 F69 F70 F71 F72 F73 F74
+        F75 F76 F77 F78 F79 F80 F81 F82 F83 F84 F85 F86 F87 F88 F89 F90 F91 F92 F93 F94 F95 F96 F97 F98
+        F99 F100 F101 F102 F103 F104 F105 F106 F107 F108 F109 F110 F111 F112 F113 F114 F115 F116 F117
+        F118 F119 F120 F121 F122 F123 F124 F125 F126 F127 F128 F129
+    );
+
+    #[test]
+    fn test_big_flags() {
+        assert_eq!(TestBig::count(), 130);
+        assert_eq!(Flags::<TestBig>::full().len(), 130);
+        assert_eq!(
+            Flags::<TestBig>::full().into_raw(),
+            BitArray::from_words([u64::MAX, u64::MAX, 0b11])
+        );
+
+        let mut flags = TestBig::F0 | TestBig::F64 | TestBig::F129;
+        assert_eq!(flags.len(), 3);
+        assert!(flags.all(TestBig::F64 | TestBig::F129));
+        assert!(!flags.any(TestBig::F1 | TestBig::F128));
+        assert_eq!(
+            flags.iter_values().collect::<Vec<_>>(),
+            [TestBig::F0, TestBig::F64, TestBig::F129]
+        );
+        assert_eq!(flags.to_string(), "F0|F64|F129");
+
+        flags.reset(TestBig::F64);
+        flags ^= TestBig::F128;
+        assert_eq!(flags, TestBig::F0 | TestBig::F128 | TestBig::F129);
+        assert_eq!((!flags).len(), 127);
+        assert!(Flags::<TestBig>::try_from_raw(BitArray::with_bit(130)).is_none());
+        assert!(Flags::from(TestBig::F64) > TestBig::F0 | TestBig::F63);
+        assert_eq!(
+            (TestBig::F2 | TestBig::F64 | TestBig::F129)
+                .positions()
+                .collect::<Vec<_>>(),
+            [2, 64, 129]
+        );
+        assert_eq!(
+            Flags::<TestBig>::try_from_raw(BitArray::with_bit(100)),
+            Some(Flags::from(TestBig::F100))
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_big_flags_fmt() {
+        assert_eq!(format!("{:b}", Flags::from(TestBig::F3)), "1000");
+        assert_eq!(
+            format!("{:#x}", TestBig::F0 | TestBig::F68),
+            "0x100000000000000001"
+        );
+        assert_eq!(format!("{:X}", Flags::<TestBig>::empty()), "0");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_big_flags_serde() {
+        let flags = TestBig::F1 | TestBig::F65;
+        let json = serde_json::to_string(&flags).unwrap();
+        assert_eq!(json, "[2,2,0]");
+        assert_eq!(
+            serde_json::from_str::<Flags<TestBig>>(&json).unwrap(),
+            flags
+        );
+        assert!(serde_json::from_str::<Flags<TestBig>>("[2,2]").is_err());
+        assert!(serde_json::from_str::<Flags<TestBig>>("[2,2,0,0]").is_err());
+    }
+}
@@ -0,0 +1,88 @@
+//! Conversions between [`Flags`] and the types generated by [bitflags](https://docs.rs/bitflags).
+
+use crate::prelude::{FlagTypeConstraints, Flags, ReflectEnum};
+
+impl<E> Flags<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints,
+{
+    /// Converts a type generated by `bitflags!` with the same bit layout. Bits which do not correspond
+    /// to any flag are retained, see [`Flags::from_raw_retain()`].
+    ///
+    /// `#[FlagEnum(bitflags = MyBitflags)]` generates the [`From`] conversions in both directions.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// bitflags::bitflags! {
+    ///     #[derive(Debug, Clone, Copy, PartialEq)]
+    ///     struct Legacy: u8 {
+    ///         const A = 0b001;
+    ///         const B = 0b010;
+    ///         const C = 0b100;
+    ///     }
+    /// }
+    ///
+    /// #[FlagEnum]
+    /// #[derive(Debug)]
+    /// enum MyFlags {A, B, C}
+    ///
+    /// let flags = Flags::<MyFlags>::from_bitflags(Legacy::A | Legacy::C);
+    /// assert_eq!(flags, MyFlags::A | MyFlags::C);
+    /// assert_eq!(flags.into_bitflags::<Legacy>(), Legacy::A | Legacy::C);
+    /// ```
+    pub fn from_bitflags<B>(flags: B) -> Self
+    where
+        B: bitflags::Flags<Bits = E::Type>,
+    {
+        Self::from_raw_retain(flags.bits())
+    }
+
+    /// Converts `self` into a type generated by `bitflags!` with the same bit layout.
+    /// See [`Flags::from_bitflags()`].
+    pub fn into_bitflags<B>(self) -> B
+    where
+        B: bitflags::Flags<Bits = E::Type>,
+    {
+        B::from_bits_retain(self.into_raw())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+
+    bitflags::bitflags! {
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        struct TestBitflags: u16 {
+            const READ = 1 << 0;
+            const WRITE = 1 << 1;
+            const EXEC = 1 << 2;
+            const READ_WRITE = Self::READ.bits() | Self::WRITE.bits();
+        }
+    }
+
+    #[FlagEnum(bitflags = TestBitflags)]
+    #[derive(Debug)]
+    #[repr(u16)]
+    enum TestInterop {
+        Read,
+        Write,
+        Exec,
+    }
+
+    #[test]
+    fn test_bitflags_interop() {
+        let flags: Flags<TestInterop> = TestBitflags::READ_WRITE.into();
+        assert_eq!(flags, TestInterop::Read | TestInterop::Write);
+        let legacy: TestBitflags = (TestInterop::Exec | TestInterop::Read).into();
+        assert_eq!(legacy, TestBitflags::EXEC | TestBitflags::READ);
+
+        let unknown = TestBitflags::from_bits_retain(0b1000_0001);
+        let flags = Flags::<TestInterop>::from_bitflags(unknown);
+        assert_eq!(flags.iter_values().count(), 1);
+        assert_eq!(flags.into_bitflags::<TestBitflags>(), unknown);
+    }
+}
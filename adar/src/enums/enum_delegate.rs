@@ -0,0 +1,162 @@
+// Note: This file contains tests for the EnumDelegate macro.
+
+#[cfg(test)]
+mod test {
+    use adar_macros::*;
+
+    #[EnumDelegate]
+    trait Shape {
+        const SIDES: usize = 0;
+
+        fn kind() -> &'static str {
+            "shape"
+        }
+        fn area(&self) -> f32;
+        fn scale(&mut self, factor: f32);
+        fn describe(&self, prefix: &str) -> String {
+            format!("{prefix}shape")
+        }
+        fn into_area(self) -> f32
+        where
+            Self: Sized;
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Square(f32);
+    impl Shape for Square {
+        fn area(&self) -> f32 {
+            self.0 * self.0
+        }
+        fn scale(&mut self, factor: f32) {
+            self.0 *= factor;
+        }
+        fn describe(&self, prefix: &str) -> String {
+            format!("{prefix}square")
+        }
+        fn into_area(self) -> f32 {
+            self.area()
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Rect {
+        w: f32,
+        h: f32,
+    }
+    impl Shape for Rect {
+        fn area(&self) -> f32 {
+            self.w * self.h
+        }
+        fn scale(&mut self, factor: f32) {
+            self.w *= factor;
+            self.h *= factor;
+        }
+        fn into_area(self) -> f32 {
+            self.area()
+        }
+    }
+
+    #[EnumDelegate(Shape)]
+    #[derive(Debug, PartialEq)]
+    enum Shapes {
+        Square(Square),
+        Rect {
+            rect: Rect,
+        },
+        #[delegate(field = 1)]
+        Labeled(&'static str, Square),
+    }
+
+    fn total_area(shapes: impl IntoIterator<Item = impl Shape>) -> f32 {
+        shapes.into_iter().map(Shape::into_area).sum()
+    }
+
+    #[test]
+    fn test_enum_delegate() {
+        let mut shapes = [
+            Shapes::Square(Square(2.0)),
+            Shapes::Rect {
+                rect: Rect { w: 2.0, h: 3.0 },
+            },
+            Shapes::Labeled("small", Square(1.0)),
+        ];
+        assert_eq!(
+            shapes.iter().map(Shape::area).collect::<Vec<_>>(),
+            [4.0, 6.0, 1.0]
+        );
+        assert_eq!(
+            shapes.iter().map(|s| s.describe("a ")).collect::<Vec<_>>(),
+            ["a square", "a shape", "a square"]
+        );
+
+        shapes.iter_mut().for_each(|s| s.scale(2.0));
+        assert_eq!(shapes[2], Shapes::Labeled("small", Square(2.0)));
+        assert_eq!(total_area(shapes), 16.0 + 24.0 + 4.0);
+        assert_eq!(<Shapes as Shape>::SIDES, 0);
+        assert_eq!(Shapes::kind(), "shape");
+    }
+
+    #[EnumDelegate]
+    trait Handler<M> {
+        fn handle(&mut self, message: M) -> usize;
+        fn name(&self) -> &str;
+    }
+
+    struct Counter(usize);
+    impl<M> Handler<M> for Counter {
+        fn handle(&mut self, _: M) -> usize {
+            self.0 += 1;
+            self.0
+        }
+        fn name(&self) -> &str {
+            "counter"
+        }
+    }
+
+    struct Summer(usize);
+    impl Handler<usize> for Summer {
+        fn handle(&mut self, message: usize) -> usize {
+            self.0 += message;
+            self.0
+        }
+        fn name(&self) -> &str {
+            "summer"
+        }
+    }
+
+    #[EnumDelegate(Handler)]
+    enum Handlers<'a, T> {
+        Counter(Counter),
+        Summer(Summer),
+        Borrowed(&'a mut T),
+    }
+
+    impl<M, T: Handler<M> + ?Sized> Handler<M> for &mut T {
+        fn handle(&mut self, message: M) -> usize {
+            (**self).handle(message)
+        }
+        fn name(&self) -> &str {
+            (**self).name()
+        }
+    }
+
+    #[test]
+    fn test_enum_delegate_generic() {
+        let mut inner = Summer(100);
+        let mut handlers: [Handlers<Summer>; 3] = [
+            Handlers::Counter(Counter(0)),
+            Handlers::Summer(Summer(0)),
+            Handlers::Borrowed(&mut inner),
+        ];
+        for handler in &mut handlers {
+            handler.handle(5usize);
+        }
+        assert_eq!(handlers[0].handle(5usize), 2);
+        assert_eq!(handlers[1].handle(5usize), 10);
+        assert_eq!(
+            handlers.iter().map(|h| h.name()).collect::<Vec<_>>(),
+            ["counter", "summer", "summer"]
+        );
+        assert_eq!(inner.0, 105);
+    }
+}
@@ -0,0 +1,70 @@
+// Note: This file contains tests for the EnumFrom macro.
+
+#[cfg(test)]
+mod test {
+    use adar_macros::*;
+
+    #[derive(Debug, PartialEq)]
+    struct A(u32);
+    #[derive(Debug, PartialEq)]
+    struct B;
+
+    trait TestTrait {
+        fn value(&self) -> u32;
+    }
+    impl TestTrait for A {
+        fn value(&self) -> u32 {
+            self.0
+        }
+    }
+    impl TestTrait for B {
+        fn value(&self) -> u32 {
+            0
+        }
+    }
+
+    #[EnumFrom]
+    #[EnumTraitDeref(TestTrait)]
+    #[derive(Debug, PartialEq)]
+    enum TestEnumFrom {
+        A(A),
+        B {
+            inner: B,
+        },
+        #[from(skip)]
+        #[deref(field = 1)]
+        Other(u8, A),
+    }
+
+    #[EnumFrom]
+    #[derive(Debug, PartialEq)]
+    enum TestGenericFrom<T> {
+        Value(Vec<T>),
+        Empty,
+    }
+
+    #[test]
+    fn test_enum_from() {
+        let a: TestEnumFrom = A(5).into();
+        assert_eq!(a, TestEnumFrom::A(A(5)));
+        assert_eq!(a.value(), 5);
+        assert_eq!(TestEnumFrom::from(B), TestEnumFrom::B { inner: B });
+        assert_eq!(A::try_from(a), Ok(A(5)));
+        assert_eq!(
+            B::try_from(TestEnumFrom::A(A(1))),
+            Err(TestEnumFrom::A(A(1)))
+        );
+        assert_eq!(B::try_from(TestEnumFrom::B { inner: B }), Ok(B));
+        assert_eq!(
+            A::try_from(TestEnumFrom::Other(1, A(2))),
+            Err(TestEnumFrom::Other(1, A(2)))
+        );
+
+        let value = TestGenericFrom::from(vec![1, 2]);
+        assert_eq!(Vec::try_from(value), Ok(vec![1, 2]));
+        assert_eq!(
+            Vec::<u8>::try_from(TestGenericFrom::Empty),
+            Err(TestGenericFrom::Empty)
+        );
+    }
+}
@@ -0,0 +1,297 @@
+//! [`EnumMap`] is a container with a value for every variant of an enum.
+
+use crate::prelude::{EnumVariant, ReflectEnum};
+use alloc::vec::Vec;
+use core::{
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+};
+
+/// Container with a value for every variant of a [`ReflectEnum`], stored densely in the order of
+/// [`ReflectEnum::variants()`] and indexed by [`ReflectEnum::index()`].
+///
+/// The keys are the unit variants, the variants with fields are not iterated. Indexing with a variant
+/// skipped by `#[reflect(skip)]` panics.
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+///
+/// #[ReflectEnum]
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Light {Red, Yellow, Green}
+///
+/// let mut durations = EnumMap::from_fn(|light| match light {
+///     Light::Red | Light::Green => 30,
+///     Light::Yellow => 3,
+/// });
+/// durations[Light::Green] += 15;
+/// assert_eq!(durations[Light::Green], 45);
+/// assert_eq!(durations.iter().map(|(_, &d)| d).sum::<u32>(), 78);
+/// ```
+pub struct EnumMap<E, V>
+where
+    E: ReflectEnum,
+{
+    values: Vec<V>,
+    _enum: PhantomData<E>,
+}
+
+impl<E, V> EnumMap<E, V>
+where
+    E: ReflectEnum + Copy + 'static,
+{
+    /// Creates a new [`EnumMap`] with the default value for every variant.
+    pub fn new() -> Self
+    where
+        V: Default,
+    {
+        Self::from_variants(|_| V::default())
+    }
+
+    /// Creates a new [`EnumMap`] with the values returned by `f` for the unit variants, and the default
+    /// value for the others.
+    pub fn from_fn(mut f: impl FnMut(E) -> V) -> Self
+    where
+        V: Default,
+    {
+        Self::from_variants(|variant| variant.value.map_or_else(V::default, &mut f))
+    }
+
+    fn from_variants(f: impl FnMut(&'static EnumVariant<E>) -> V) -> Self {
+        Self {
+            values: E::variants().iter().map(f).collect(),
+            _enum: PhantomData,
+        }
+    }
+
+    fn position(key: E) -> usize {
+        key.index().expect("Variant is skipped by #[reflect(skip)]")
+    }
+
+    /// Gets the value of the variant.
+    ///
+    /// # Panics
+    /// If the variant is skipped by `#[reflect(skip)]`.
+    pub fn get(&self, key: E) -> &V {
+        &self.values[Self::position(key)]
+    }
+
+    /// Gets the mutable value of the variant.
+    ///
+    /// # Panics
+    /// If the variant is skipped by `#[reflect(skip)]`.
+    pub fn get_mut(&mut self, key: E) -> &mut V {
+        &mut self.values[Self::position(key)]
+    }
+
+    /// Replaces the value of the variant.
+    ///
+    /// # Returns
+    /// The previous value.
+    ///
+    /// # Panics
+    /// If the variant is skipped by `#[reflect(skip)]`.
+    pub fn insert(&mut self, key: E, value: V) -> V {
+        core::mem::replace(self.get_mut(key), value)
+    }
+
+    /// Creates an iterator to iterate through the unit variants and their values.
+    pub fn iter(&self) -> impl Iterator<Item = (E, &V)> {
+        E::variants()
+            .iter()
+            .zip(&self.values)
+            .filter_map(|(variant, value)| Some((variant.value?, value)))
+    }
+
+    /// Creates an iterator to iterate through the unit variants and their mutable values.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (E, &mut V)> {
+        E::variants()
+            .iter()
+            .zip(&mut self.values)
+            .filter_map(|(variant, value)| Some((variant.value?, value)))
+    }
+
+    /// Gets the values of every variant, in the order of [`ReflectEnum::variants()`].
+    pub fn values(&self) -> &[V] {
+        &self.values
+    }
+
+    /// Gets the mutable values of every variant, in the order of [`ReflectEnum::variants()`].
+    pub fn values_mut(&mut self) -> &mut [V] {
+        &mut self.values
+    }
+
+    /// Converts `self` into the values of every variant, in the order of [`ReflectEnum::variants()`].
+    pub fn into_values(self) -> Vec<V> {
+        self.values
+    }
+}
+
+impl<E, V> Default for EnumMap<E, V>
+where
+    E: ReflectEnum + Copy + 'static,
+    V: Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E, V> Index<E> for EnumMap<E, V>
+where
+    E: ReflectEnum + Copy + 'static,
+{
+    type Output = V;
+
+    fn index(&self, key: E) -> &Self::Output {
+        self.get(key)
+    }
+}
+
+impl<E, V> IndexMut<E> for EnumMap<E, V>
+where
+    E: ReflectEnum + Copy + 'static,
+{
+    fn index_mut(&mut self, key: E) -> &mut Self::Output {
+        self.get_mut(key)
+    }
+}
+
+/// Collects the values of the variants, the missing ones are defaulted.
+impl<E, V> FromIterator<(E, V)> for EnumMap<E, V>
+where
+    E: ReflectEnum + Copy + 'static,
+    V: Default,
+{
+    fn from_iter<I: IntoIterator<Item = (E, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (key, value) in iter {
+            map.insert(key, value);
+        }
+        map
+    }
+}
+
+impl<E, V> Clone for EnumMap<E, V>
+where
+    E: ReflectEnum,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            _enum: PhantomData,
+        }
+    }
+}
+
+impl<E, V> PartialEq for EnumMap<E, V>
+where
+    E: ReflectEnum,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+    }
+}
+
+impl<E, V> Eq for EnumMap<E, V>
+where
+    E: ReflectEnum,
+    V: Eq,
+{
+}
+
+impl<E, V> core::hash::Hash for EnumMap<E, V>
+where
+    E: ReflectEnum,
+    V: core::hash::Hash,
+{
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.values.hash(state);
+    }
+}
+
+/// Formats the names of the variants and the values as a map.
+impl<E, V> core::fmt::Debug for EnumMap<E, V>
+where
+    E: ReflectEnum + 'static,
+    V: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map()
+            .entries(
+                E::variants()
+                    .iter()
+                    .map(|variant| variant.name)
+                    .zip(&self.values),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+
+    #[ReflectEnum]
+    #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+    enum TestEnum {
+        E1,
+        E2,
+        #[reflect(skip)]
+        Internal,
+        E3,
+    }
+
+    #[test]
+    fn test_enum_map() {
+        let mut map = EnumMap::<TestEnum, u32>::new();
+        assert_eq!(map.values(), [0, 0, 0]);
+        map[TestEnum::E2] = 5;
+        assert_eq!(map.insert(TestEnum::E3, 7), 0);
+        *map.get_mut(TestEnum::E1) += 1;
+        assert_eq!(*map.get(TestEnum::E3), 7);
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            [(TestEnum::E1, &1), (TestEnum::E2, &5), (TestEnum::E3, &7)]
+        );
+        for (_, value) in map.iter_mut() {
+            *value *= 2;
+        }
+        assert_eq!(map.clone().into_values(), [2, 10, 14]);
+        assert_eq!(format!("{:?}", map), "{\"E1\": 2, \"E2\": 10, \"E3\": 14}");
+
+        let collected = [(TestEnum::E2, 10), (TestEnum::E1, 2), (TestEnum::E3, 14)]
+            .into_iter()
+            .collect::<EnumMap<_, _>>();
+        assert_eq!(collected, map);
+        assert_eq!(
+            EnumMap::from_fn(|e: TestEnum| e.name()).values(),
+            ["E1", "E2", "E3"]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_enum_map_skipped() {
+        EnumMap::<TestEnum, u32>::default().get(TestEnum::Internal);
+    }
+
+    #[test]
+    fn test_enum_map_data_variants() {
+        #[ReflectEnum]
+        #[derive(Debug, Clone, Copy)]
+        #[allow(dead_code)]
+        enum TestData {
+            E1,
+            E2(u8),
+        }
+
+        let mut map = EnumMap::from_fn(|_: TestData| 1);
+        map[TestData::E2(3)] = 2;
+        assert_eq!(map.values(), [1, 2]);
+        assert_eq!(map.iter().count(), 1);
+    }
+}
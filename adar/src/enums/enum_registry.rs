@@ -0,0 +1,108 @@
+//! Global registry of the reflected enums, e.g. for dev tools which inspect or serialize every enum of a binary.
+
+use crate::prelude::{EnumVariant, ReflectEnum};
+use adar_registry::prelude::{Entry, Registry};
+use std::{any::TypeId, sync::OnceLock};
+
+/// Type-erased metadata of a [`ReflectEnum`], registered in [`enum_registry()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumInfo {
+    /// Name of the enum, as [`core::any::type_name()`].
+    pub name: &'static str,
+    /// Type of the enum.
+    pub type_id: TypeId,
+    /// Name of [`ReflectEnum::Type`].
+    pub repr: &'static str,
+    /// Reflected variants, see [`EnumVariant::erased()`].
+    pub variants: Vec<EnumVariant<()>>,
+}
+
+impl EnumInfo {
+    /// Collects the metadata of the enum.
+    pub fn of<E>() -> Self
+    where
+        E: ReflectEnum + 'static,
+    {
+        Self {
+            name: core::any::type_name::<E>(),
+            type_id: TypeId::of::<E>(),
+            repr: core::any::type_name::<E::Type>(),
+            variants: E::variants().iter().map(EnumVariant::erased).collect(),
+        }
+    }
+}
+
+/// Gets the global registry of the reflected enums, see [`register_enum()`].
+pub fn enum_registry() -> &'static Registry<EnumInfo> {
+    static REGISTRY: OnceLock<Registry<EnumInfo>> = OnceLock::new();
+    REGISTRY.get_or_init(Registry::new)
+}
+
+/// Registers the metadata of the enum in [`enum_registry()`].
+///
+/// Rust has no code running before `main()`, so the enums have to be registered explicitly, typically at startup.
+///
+/// # Returns
+/// [`Entry`] which controls the lifetime of the registration.
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+///
+/// #[ReflectEnum]
+/// #[repr(u8)]
+/// enum Color {Red, Green, Blue}
+///
+/// let _color = register_enum::<Color>();
+/// let registry = enum_registry().read();
+/// let (_, info) = registry.iter().find(|(_, info)| info.name.ends_with("Color")).unwrap();
+/// assert_eq!(info.repr, "u8");
+/// assert_eq!(info.variants.iter().map(|variant| variant.name).collect::<Vec<_>>(), ["Red", "Green", "Blue"]);
+/// ```
+#[must_use = "Entry will be immediately revoked if not used"]
+pub fn register_enum<E>() -> Entry<EnumInfo>
+where
+    E: ReflectEnum + 'static,
+{
+    enum_registry().register(EnumInfo::of::<E>())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+    use std::any::TypeId;
+
+    #[ReflectEnum]
+    #[repr(u16)]
+    #[allow(dead_code)]
+    enum TestRegistered {
+        /// Unit variant.
+        A,
+        B(u16) = 5,
+    }
+
+    fn is_registered() -> bool {
+        enum_registry()
+            .read()
+            .iter()
+            .any(|(_, info)| info.type_id == TypeId::of::<TestRegistered>())
+    }
+
+    #[test]
+    fn test_enum_registry() {
+        let info = EnumInfo::of::<TestRegistered>();
+        assert!(info.name.ends_with("TestRegistered"));
+        assert_eq!(info.repr, "u16");
+        assert_eq!(info.variants[0].value, Some(()));
+        assert_eq!(info.variants[0].doc(), Some("Unit variant."));
+        assert_eq!(info.variants[1].value, None);
+        assert_eq!(info.variants[1].discriminant(), 5);
+        assert_eq!(info.variants[1].fields()[0].type_id, TypeId::of::<u16>());
+
+        let entry = register_enum::<TestRegistered>();
+        assert_eq!(*entry.read().unwrap(), info);
+        assert!(is_registered());
+        drop(entry);
+        assert!(!is_registered());
+    }
+}
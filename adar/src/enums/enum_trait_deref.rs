@@ -5,7 +5,7 @@ mod test {
     use adar_macros::*;
     trait TestTrait {
         fn my_func(&self) -> bool;
-        fn my_mut_func(&self) -> bool;
+        fn my_mut_func(&mut self) -> bool;
     }
     struct A;
     impl TestTrait for A {
@@ -13,7 +13,7 @@ mod test {
             true
         }
 
-        fn my_mut_func(&self) -> bool {
+        fn my_mut_func(&mut self) -> bool {
             true
         }
     }
@@ -23,7 +23,7 @@ mod test {
             false
         }
 
-        fn my_mut_func(&self) -> bool {
+        fn my_mut_func(&mut self) -> bool {
             false
         }
     }
@@ -40,6 +40,140 @@ mod test {
         B(B),
     }
 
+    trait OtherTrait {
+        fn name(&self) -> &'static str;
+    }
+    impl OtherTrait for A {
+        fn name(&self) -> &'static str {
+            "A"
+        }
+    }
+    impl OtherTrait for B {
+        fn name(&self) -> &'static str {
+            "B"
+        }
+    }
+
+    #[EnumTraitDeref(TestTrait, OtherTrait + Send)]
+    enum TestEnumTraitDerefMulti {
+        A(A),
+        B(B),
+    }
+
+    #[EnumTraitDerefMut(OtherTrait, TestTrait)]
+    enum TestEnumTraitDerefMutMulti {
+        A(A),
+        B(B),
+    }
+
+    #[test]
+    fn test_enum_trait_deref_multi() {
+        let a = TestEnumTraitDerefMulti::A(A);
+        assert!(a.my_func());
+        assert!(a.as_test_trait().my_func());
+        assert_eq!(a.as_other_trait().name(), "A");
+
+        assert_eq!(TestEnumTraitDerefMulti::B(B).as_other_trait().name(), "B");
+
+        assert!(TestEnumTraitDerefMutMulti::A(A).as_test_trait().my_func());
+        let mut b = TestEnumTraitDerefMutMulti::B(B);
+        assert_eq!(b.name(), "B");
+        assert!(!b.as_test_trait_mut().my_mut_func());
+        assert_eq!(b.as_other_trait_mut().name(), "B");
+        assert!(!b.as_test_trait().my_func());
+    }
+
+    trait Handler<M> {
+        type Output;
+        fn handle(&self, msg: M) -> Self::Output;
+    }
+    struct Echo;
+    impl<M> Handler<M> for Echo {
+        type Output = Option<M>;
+        fn handle(&self, msg: M) -> Option<M> {
+            Some(msg)
+        }
+    }
+    struct Discard<M>(std::marker::PhantomData<M>);
+    impl<M> Handler<M> for Discard<M> {
+        type Output = Option<M>;
+        fn handle(&self, _msg: M) -> Option<M> {
+            None
+        }
+    }
+    struct Prefix<'a>(&'a str);
+    impl<'a> Handler<&'a str> for Prefix<'a> {
+        type Output = Option<&'a str>;
+        fn handle(&self, msg: &'a str) -> Option<&'a str> {
+            msg.strip_prefix(self.0)
+        }
+    }
+
+    #[EnumTraitDerefMut(Handler<Msg, Output = Option<Msg>>)]
+    enum TestPipeline<Msg>
+    where
+        Msg: Clone,
+    {
+        Echo(Echo),
+        Discard(Discard<Msg>),
+    }
+
+    #[EnumTraitDeref(Handler<&'a str, Output = Option<&'a str>>)]
+    enum TestBorrowedPipeline<'a> {
+        Echo(Echo),
+        Prefix(Prefix<'a>),
+    }
+
+    #[test]
+    fn test_enum_trait_deref_generic() {
+        let echo = TestPipeline::<u32>::Echo(Echo);
+        assert_eq!(echo.handle(5), Some(5));
+        let mut discard = TestPipeline::Discard(Discard(std::marker::PhantomData));
+        assert_eq!(discard.as_handler_mut().handle(5u32), None);
+
+        let prefix = String::from("a:");
+        let pipeline = [
+            TestBorrowedPipeline::Echo(Echo),
+            TestBorrowedPipeline::Prefix(Prefix(&prefix)),
+        ];
+        assert_eq!(
+            pipeline
+                .iter()
+                .map(|handler| handler.handle("a:b"))
+                .collect::<Vec<_>>(),
+            [Some("a:b"), Some("b")]
+        );
+    }
+
+    #[EnumTraitDerefMut(TestTrait)]
+    enum TestEnumTraitDerefField {
+        Named {
+            handler: A,
+        },
+        #[deref(field = inner)]
+        NamedMulti {
+            id: u32,
+            inner: B,
+        },
+        #[deref(field = 1)]
+        Unnamed(u32, A),
+    }
+
+    #[test]
+    fn test_enum_trait_deref_field() {
+        assert!(TestEnumTraitDerefField::Named { handler: A }.my_func());
+        let mut multi = TestEnumTraitDerefField::NamedMulti { id: 1, inner: B };
+        assert!(!multi.my_mut_func());
+        if let TestEnumTraitDerefField::NamedMulti { id, .. } = multi {
+            assert_eq!(id, 1);
+        }
+        let unnamed = TestEnumTraitDerefField::Unnamed(2, A);
+        assert!(unnamed.my_func());
+        if let TestEnumTraitDerefField::Unnamed(id, _) = unnamed {
+            assert_eq!(id, 2);
+        }
+    }
+
     #[test]
     fn test_enum_trait_deref() {
         assert!(TestEnumTraitDeref::A(A).my_func());
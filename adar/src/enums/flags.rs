@@ -1,8 +1,12 @@
 //! [`Flags`] is a type-safe and verbose bitwise flag container.
 
 use crate::prelude::{EnumVariant, ReflectEnum};
-use num_traits::{One, PrimInt, Zero};
-use std::ops::{BitAnd, BitOr, BitXor, Not, Sub};
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+use core::marker::PhantomData;
+use core::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Sub, SubAssign,
+};
 
 /// Type-safe and verbose bitwise flag container.
 /// The associated enum must be annotated with [`crate::macros::FlagEnum`] derive macro.
@@ -13,7 +17,7 @@ where
 
 impl<E> Flags<E>
 where
-    E: ReflectEnum + Into<E::Type>,
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
     E::Type: FlagTypeConstraints,
 {
     /// Creates a new [`Flags`] with no flags set.
@@ -72,11 +76,7 @@ where
     /// [`Flags`] with all flags set.
     #[inline(always)]
     pub fn full() -> Self {
-        Self(
-            ((1 << E::count()) - 1)
-                .try_into()
-                .unwrap_or(E::Type::zero()),
-        )
+        Self(E::all_bits())
     }
 
     /// Sets the specified flags.
@@ -144,6 +144,70 @@ where
         self.0 = self.0 ^ flags.into().0;
     }
 
+    /// Sets the specified flags if `value` is `true`, resets them otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// enum MyFlags {A, B, C}
+    ///
+    /// let mut flag = Flags::from(MyFlags::A);
+    /// flag.set_value(MyFlags::B | MyFlags::C, true);
+    /// assert_eq!(flag, MyFlags::A | MyFlags::B | MyFlags::C);
+    /// flag.set_value(MyFlags::A, false);
+    /// assert_eq!(flag, MyFlags::B | MyFlags::C);
+    /// ```
+    #[inline(always)]
+    pub fn set_value(&mut self, flags: impl Into<Flags<E>>, value: bool) {
+        if value {
+            self.set(flags);
+        } else {
+            self.reset(flags);
+        }
+    }
+
+    /// Resets all flags.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// enum MyFlags {A, B, C}
+    ///
+    /// let mut flag = MyFlags::A | MyFlags::C;
+    /// flag.clear();
+    /// assert!(flag.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        *self = Self::empty();
+    }
+
+    /// Keeps only the flags for which the predicate returns `true`.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// #[derive(PartialEq)]
+    /// enum MyFlags {A, B, C, D}
+    ///
+    /// let mut flag = MyFlags::A | MyFlags::B | MyFlags::D;
+    /// flag.retain(|flag| flag != MyFlags::B);
+    /// assert_eq!(flag, MyFlags::A | MyFlags::D);
+    /// ```
+    pub fn retain(&mut self, mut predicate: impl FnMut(E) -> bool) {
+        for flag in self.iter_values() {
+            if !predicate(flag) {
+                self.reset(flag);
+            }
+        }
+    }
+
     /// Checks if all of the flags are set.
     ///
     /// # Example
@@ -189,6 +253,71 @@ where
         self.0 & flags.into().0 != E::Type::zero()
     }
 
+    /// Checks if every flag of `self` is set in the specified flags.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// enum MyFlags {A, B, C}
+    ///
+    /// let flags = Flags::from(MyFlags::A);
+    /// assert!(flags.is_subset(MyFlags::A | MyFlags::B));
+    /// assert!(flags.is_subset(MyFlags::A));
+    /// assert!(!flags.is_subset(MyFlags::B | MyFlags::C));
+    /// ```
+    ///
+    /// # Returns
+    /// `true` if `self` is a subset of the specified flags.
+    #[inline(always)]
+    pub fn is_subset(&self, flags: impl Into<Flags<E>>) -> bool {
+        flags.into().all(*self)
+    }
+
+    /// Checks if every specified flag is set in `self`. Same as [`Flags::all()`].
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// enum MyFlags {A, B, C}
+    ///
+    /// let flags = MyFlags::A | MyFlags::B;
+    /// assert!(flags.is_superset(MyFlags::A));
+    /// assert!(flags.is_superset(Flags::empty()));
+    /// assert!(!flags.is_superset(MyFlags::B | MyFlags::C));
+    /// ```
+    ///
+    /// # Returns
+    /// `true` if `self` is a superset of the specified flags.
+    #[inline(always)]
+    pub fn is_superset(&self, flags: impl Into<Flags<E>>) -> bool {
+        self.all(flags)
+    }
+
+    /// Checks if `self` and the specified flags have no flags in common.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// enum MyFlags {A, B, C}
+    ///
+    /// let flags = MyFlags::A | MyFlags::B;
+    /// assert!(flags.is_disjoint(MyFlags::C));
+    /// assert!(!flags.is_disjoint(MyFlags::B | MyFlags::C));
+    /// ```
+    ///
+    /// # Returns
+    /// `true` if none of the specified flags are set in `self`.
+    #[inline(always)]
+    pub fn is_disjoint(&self, flags: impl Into<Flags<E>>) -> bool {
+        !self.any(flags)
+    }
+
     /// Creates a new [`Flags`] where both the flags from `self` and the specified flags are set.
     ///
     /// # Example
@@ -230,7 +359,70 @@ where
         Self(self.0 & flags.into().0)
     }
 
-    /// Counts the number of flags set in `self`.
+    /// Creates a new [`Flags`] where only the flags present in `self` but not in the specified flags are set.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// #[derive(Debug)]
+    /// enum MyFlags {A, B, C, D}
+    ///
+    /// let flags = (MyFlags::A | MyFlags::B).difference(MyFlags::B | MyFlags::D);
+    /// assert_eq!(flags, MyFlags::A)
+    /// ```
+    ///
+    /// # Returns
+    /// [`Flags`] with the difference of the flags set.
+    #[inline(always)]
+    pub fn difference(&self, flags: impl Into<Flags<E>>) -> Flags<E> {
+        Self(self.0 & !flags.into().0)
+    }
+
+    /// Creates a new [`Flags`] where only the flags present in exactly one of `self` and the specified flags are set.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// enum MyFlags {A, B, C, D}
+    ///
+    /// let flags = (MyFlags::A | MyFlags::B).symmetric_difference(MyFlags::B | MyFlags::D);
+    /// assert_eq!(flags, MyFlags::A | MyFlags::D)
+    /// ```
+    ///
+    /// # Returns
+    /// [`Flags`] with the symmetric difference of the flags set.
+    #[inline(always)]
+    pub fn symmetric_difference(&self, flags: impl Into<Flags<E>>) -> Flags<E> {
+        Self(self.0 ^ flags.into().0)
+    }
+
+    /// Creates a new [`Flags`] where only the flags not present in `self` are set.
+    /// Bits outside of [`Flags::full()`] stay unset.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// enum MyFlags {A, B, C, D}
+    ///
+    /// let flags = (MyFlags::A | MyFlags::B).complement();
+    /// assert_eq!(flags, MyFlags::C | MyFlags::D);
+    /// assert_eq!(flags.into_raw(), 0b1100);
+    /// ```
+    ///
+    /// # Returns
+    /// [`Flags`] with the complement of the flags set.
+    #[inline(always)]
+    pub fn complement(&self) -> Flags<E> {
+        Self(!self.0 & Self::full().0)
+    }
+
+    /// Counts the number of flags set in `self`. Unknown bits kept by [`Flags::from_raw_retain()`] are not counted.
     ///
     /// # Example
     /// ```
@@ -242,15 +434,66 @@ where
     /// assert_eq!((MyFlags::A | MyFlags::B).len(), 2);
     /// assert_eq!(Flags::<MyFlags>::empty().len(), 0);
     /// assert_eq!(Flags::<MyFlags>::full().len(), 4);
+    /// assert_eq!(Flags::<MyFlags>::from_raw_retain(0b10001).len(), 1);
     /// ```
     ///
     /// # Returns
     /// Number of flags set.
-    pub fn len(self) -> u32 {
-        self.0.count_ones()
+    pub fn len(&self) -> u32 {
+        (self.0 & Self::full().0).count_ones()
+    }
+
+    /// Checks if exactly one flag is set, ignoring the unknown bits kept by [`Flags::from_raw_retain()`].
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// enum MyFlags {A, B, C}
+    ///
+    /// assert!(Flags::from(MyFlags::B).is_single());
+    /// assert!(!(MyFlags::A | MyFlags::B).is_single());
+    /// assert!(!Flags::<MyFlags>::empty().is_single());
+    /// ```
+    ///
+    /// # Returns
+    /// `true` if exactly one flag is set.
+    #[inline(always)]
+    pub fn is_single(&self) -> bool {
+        self.len() == 1
     }
 
-    /// Creates an iterator to iterate through the set flags.
+    /// Gets the flag if exactly one flag is set, ignoring the unknown bits kept by [`Flags::from_raw_retain()`].
+    /// See also [`crate::enums::Single`].
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// #[derive(Debug, PartialEq)]
+    /// enum MyFlags {A, B, C}
+    ///
+    /// assert_eq!(Flags::from(MyFlags::B).as_single(), Some(MyFlags::B));
+    /// assert_eq!((MyFlags::A | MyFlags::B).as_single(), None);
+    /// assert_eq!(Flags::<MyFlags>::empty().as_single(), None);
+    /// ```
+    ///
+    /// # Returns
+    /// `Some` - The flag if exactly one flag is set \
+    /// `None` - Otherwise
+    pub fn as_single(&self) -> Option<E> {
+        if self.is_single() {
+            self.iter_values().next()
+        } else {
+            None
+        }
+    }
+
+    /// Creates an iterator to iterate through the set flags, in ascending order of their bits.
+    /// Only the set bits are visited, the lookup of their variants is constant time when the bits
+    /// follow the declaration order, like the implicit ones do.
     ///
     /// # Example
     /// ```
@@ -270,11 +513,124 @@ where
     /// An iterator.
     pub fn iter<'a>(&'a self) -> FlagsIterator<'a, E> {
         FlagsIterator::<E> {
-            iter: E::variants().iter() as std::slice::Iter<'static, EnumVariant<E>>,
-            flags: self,
+            raw: self.0,
+            flags: PhantomData,
+        }
+    }
+
+    /// Creates an iterator to iterate through the values of the set flags, see [`Flags::iter()`].
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// #[derive(Debug, Eq, PartialEq)]
+    /// enum MyFlags {A, B, C, D}
+    ///
+    /// let flags = MyFlags::B | MyFlags::D;
+    /// assert_eq!(flags.iter_values().collect::<Vec<_>>(), [MyFlags::B, MyFlags::D]);
+    /// for flag in flags {
+    ///     assert!(flags.any(flag));
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    /// An iterator.
+    pub fn iter_values(&self) -> FlagsValues<E> {
+        FlagsValues {
+            iter: FlagsIterator {
+                raw: self.0,
+                flags: PhantomData,
+            },
         }
     }
 
+    /// Creates an iterator to iterate through the bit indices of the set flags, in ascending order.
+    /// Unlike [`Flags::iter()`] it does not look up the variants.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// enum Register {Enable = 0b1, Interrupt = 0b100, Reset = 1 << 7}
+    ///
+    /// let flags = Register::Enable | Register::Reset;
+    /// assert_eq!(flags.positions().collect::<Vec<_>>(), [0, 7]);
+    /// ```
+    ///
+    /// # Returns
+    /// An iterator.
+    pub fn positions(&self) -> FlagsPositions<E> {
+        FlagsPositions { raw: self.0 }
+    }
+
+    /// Translates the flags to the flags of another enum. The flags mapped to `None` are dropped.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// enum V1 {Read, Write, Legacy}
+    ///
+    /// #[FlagEnum]
+    /// #[derive(Debug)]
+    /// enum V2 {Read, Write}
+    ///
+    /// let flags = (V1::Read | V1::Legacy).map(|flag| match flag {
+    ///     V1::Read => Some(V2::Read),
+    ///     V1::Write => Some(V2::Write),
+    ///     V1::Legacy => None,
+    /// });
+    /// assert_eq!(flags, V2::Read);
+    /// ```
+    ///
+    /// # Returns
+    /// [`Flags`] of the mapped flags.
+    pub fn map<F>(&self, mut f: impl FnMut(E) -> Option<F>) -> Flags<F>
+    where
+        F: ReflectEnum + Into<F::Type> + Copy + 'static,
+        F::Type: FlagTypeConstraints,
+    {
+        self.iter_values().filter_map(&mut f).collect()
+    }
+
+    /// Translates the flags to the flags of another enum with the same names, e.g. to the next version
+    /// of the same enum.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// #[derive(Debug, PartialEq)]
+    /// enum V1 {Read, Write, Legacy}
+    ///
+    /// #[FlagEnum]
+    /// #[derive(Debug)]
+    /// enum V2 {Execute, Read, Write}
+    ///
+    /// assert_eq!((V1::Read | V1::Write).try_cast(), Ok(V2::Read | V2::Write));
+    /// assert_eq!((V1::Read | V1::Legacy).try_cast::<V2>(), Err(V1::Legacy));
+    /// ```
+    ///
+    /// # Returns
+    /// `Ok` - [`Flags`] if every flag has a counterpart \
+    /// `Err` - The first flag without a counterpart otherwise
+    pub fn try_cast<F>(&self) -> Result<Flags<F>, E>
+    where
+        F: ReflectEnum + Into<F::Type> + Copy + 'static,
+        F::Type: FlagTypeConstraints,
+    {
+        let mut flags = Flags::empty();
+        for flag in self.iter_values() {
+            flags.set(F::from_name(flag.name()).ok_or(flag)?);
+        }
+        Ok(flags)
+    }
+
     /// Checks if no flags are set.
     ///
     /// # Example
@@ -318,6 +674,104 @@ where
         }
     }
 
+    /// Creates [`Flags`] from a raw value. Bits which do not correspond to any flag are dropped.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// enum MyFlags {A, B, C, D}
+    ///
+    /// let flags = Flags::<MyFlags>::from_raw_truncate(0b11010);
+    /// assert_eq!(flags, MyFlags::B | MyFlags::D);
+    /// assert_eq!(flags.into_raw(), 0b1010);
+    /// ```
+    ///
+    /// # Returns
+    /// [`Flags`] with the known flags of the raw value set.
+    pub fn from_raw_truncate(raw: E::Type) -> Self {
+        Self(raw & Self::full().0)
+    }
+
+    /// Creates [`Flags`] from a raw value, keeping the bits which do not correspond to any flag, e.g. flags
+    /// written by a newer version. The unknown bits are preserved by [`Flags::into_raw()`] and the bitwise
+    /// operations, but they are not iterated. [`Flags::complement()`] drops them.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// enum MyFlags {A, B, C, D}
+    ///
+    /// let mut flags = Flags::<MyFlags>::from_raw_retain(0b11010);
+    /// flags.set(MyFlags::A);
+    /// assert_eq!(flags.iter_values().count(), 3);
+    /// assert_eq!(flags.into_raw(), 0b11011);
+    /// ```
+    ///
+    /// # Returns
+    /// [`Flags`] with the raw value.
+    #[inline(always)]
+    pub fn from_raw_retain(raw: E::Type) -> Self {
+        Self(raw)
+    }
+
+    /// Creates a [`std::fmt::Display`] implementation which joins the names of the set flags with `separator`.
+    /// [`Flags`] itself is displayed with `|` as the separator.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// enum MyFlags {A, B, C}
+    ///
+    /// let flags = MyFlags::A | MyFlags::C;
+    /// assert_eq!(flags.to_string(), "A|C");
+    /// assert_eq!(flags.display_with(", ").to_string(), "A, C");
+    /// ```
+    pub fn display_with<'a>(&'a self, separator: &'a str) -> FlagsDisplay<'a, E> {
+        FlagsDisplay {
+            flags: self,
+            separator,
+        }
+    }
+
+    /// Parses the names of flags separated by `separator`. Whitespace around the names is ignored.
+    /// [`Flags`] itself is parsed with `|` as the separator.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// #[derive(Debug)]
+    /// enum MyFlags {A, B, C}
+    ///
+    /// assert_eq!("A|C".parse::<Flags<MyFlags>>(), Ok(MyFlags::A | MyFlags::C));
+    /// assert_eq!(Flags::<MyFlags>::parse_with("B, C", ","), Ok(MyFlags::B | MyFlags::C));
+    /// assert_eq!(Flags::<MyFlags>::parse_with("", ","), Ok(Flags::empty()));
+    /// assert_eq!(Flags::<MyFlags>::parse_with("A,D", ","), Err(UnknownFlag("D".into())));
+    /// ```
+    ///
+    /// # Returns
+    /// `Ok` - [`Flags`] if every name is a flag \
+    /// `Err` - The first unknown name otherwise
+    #[cfg(feature = "alloc")]
+    pub fn parse_with(s: &str, separator: &str) -> Result<Self, UnknownFlag> {
+        let mut flags = Self::empty();
+        for name in s.split(separator).map(str::trim) {
+            if name.is_empty() {
+                continue;
+            }
+            let flag = E::from_name(name).ok_or_else(|| UnknownFlag(name.to_string()))?;
+            flags.set(flag);
+        }
+        Ok(flags)
+    }
+
     /// Converts `self` into a raw value.
     ///
     /// # Example
@@ -338,45 +792,177 @@ where
     }
 }
 
-impl<E, T> PartialEq<T> for Flags<E>
+impl<E> Flags<E>
+where
+    E: ReflectEnum,
+{
+    /// Used by [`crate::macros::FlagEnum`] to define the composite flags as constants.
+    #[doc(hidden)]
+    pub const fn __from_raw_unchecked(raw: E::Type) -> Self {
+        Self(raw)
+    }
+}
+
+impl<E, T> PartialEq<T> for Flags<E>
+where
+    E: ReflectEnum,
+    E::Type: FlagTypeConstraints,
+    T: Into<Self> + Copy,
+{
+    #[inline(always)]
+    fn eq(&self, other: &T) -> bool {
+        self.0 == (*other).into().0
+    }
+}
+
+impl<E> Eq for Flags<E>
+where
+    E: ReflectEnum + Copy,
+    E::Type: FlagTypeConstraints + Eq,
+{
+}
+
+impl<E> core::hash::Hash for Flags<E>
+where
+    E: ReflectEnum,
+    E::Type: core::hash::Hash,
+{
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+/// Compares the raw values.
+impl<E> PartialOrd for Flags<E>
+where
+    E: ReflectEnum + Copy,
+    E::Type: FlagTypeConstraints + Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compares the raw values.
+impl<E> Ord for Flags<E>
+where
+    E: ReflectEnum + Copy,
+    E::Type: FlagTypeConstraints + Ord,
+{
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl<E> core::fmt::Debug for Flags<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut first = true;
+        write!(f, "(")?;
+        for flag in self.iter() {
+            if !first {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", flag.name)?;
+            first = false;
+        }
+        write!(f, ")")?;
+        Ok(())
+    }
+}
+
+impl<E> core::fmt::Display for Flags<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.display_with("|").fmt(f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<E> core::str::FromStr for Flags<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints,
+{
+    type Err = UnknownFlag;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, "|")
+    }
+}
+
+/// Displays the names of the set flags, created by [`Flags::display_with()`].
+pub struct FlagsDisplay<'a, E>
 where
     E: ReflectEnum,
-    E::Type: FlagTypeConstraints,
-    T: Into<Self> + Copy,
 {
-    #[inline(always)]
-    fn eq(&self, other: &T) -> bool {
-        self.0 == (*other).into().0
-    }
+    flags: &'a Flags<E>,
+    separator: &'a str,
 }
 
-impl<E> std::fmt::Debug for Flags<E>
+impl<E> core::fmt::Display for FlagsDisplay<'_, E>
 where
     E: ReflectEnum + Into<E::Type> + Copy + 'static,
     E::Type: FlagTypeConstraints,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut first = true;
-        write!(f, "(")?;
-        for flag in self.iter() {
-            if !first {
-                write!(f, ",")?;
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, flag) in self.flags.iter().enumerate() {
+            if i != 0 {
+                write!(f, "{}", self.separator)?;
             }
             write!(f, "{}", flag.name)?;
-            first = false;
         }
-        write!(f, ")")?;
         Ok(())
     }
 }
 
+/// Error reported when parsing [`Flags`] from a name which is not a flag.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UnknownFlag(pub String);
+
+#[cfg(feature = "alloc")]
+impl core::fmt::Display for UnknownFlag {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Unknown flag: {}", self.0)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::error::Error for UnknownFlag {}
+
+macro_rules! impl_flags_fmt {
+    ($($fmt:ident),*) => {
+        $(
+            /// Formats the raw value.
+            impl<E> core::fmt::$fmt for Flags<E>
+            where
+                E: ReflectEnum,
+                E::Type: core::fmt::$fmt,
+            {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    core::fmt::$fmt::fmt(&self.0, f)
+                }
+            }
+        )*
+    };
+}
+
+impl_flags_fmt!(Binary, LowerHex, UpperHex);
+
 /// Iterates set flags in a [`Flags`] container.
 pub struct FlagsIterator<'a, E>
 where
     E: ReflectEnum + 'static,
 {
-    iter: std::slice::Iter<'static, EnumVariant<E>>,
-    flags: &'a Flags<E>,
+    raw: E::Type,
+    flags: PhantomData<&'a Flags<E>>,
 }
 
 impl<'a, E> Iterator for FlagsIterator<'a, E>
@@ -387,24 +973,152 @@ where
     type Item = &'a EnumVariant<E>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.iter
-            .by_ref()
-            .find(|&flag| self.flags.any(flag.value.unwrap()))
+        let variants = E::variants();
+        let is_bit = |variant: &EnumVariant<E>, bit| variant.value.is_some_and(|v| v.into() == bit);
+        while self.raw != E::Type::zero() {
+            let position = self.raw.trailing_zeros() as usize;
+            let rest = self.raw.clear_lowest_bit();
+            let bit = self.raw ^ rest;
+            self.raw = rest;
+            // Retained unknown bits have no variant and are skipped.
+            let variant = match variants.get(position) {
+                Some(variant) if is_bit(variant, bit) => Some(variant),
+                _ => variants.iter().find(|variant| is_bit(variant, bit)),
+            };
+            if variant.is_some() {
+                return variant;
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.raw.count_ones() as usize))
     }
 }
 
-impl<E> BitOr<E> for Flags<E>
+macro_rules! impl_flags_op {
+    ($op:ident, $method:ident, $assign_op:ident, $assign_method:ident, |$lhs:ident, $rhs:ident| $expr:expr) => {
+        impl<E, T> $op<T> for Flags<E>
+        where
+            E: ReflectEnum + Into<E::Type>,
+            E::Type: FlagTypeConstraints,
+            T: Into<Flags<E>>,
+        {
+            type Output = Self;
+
+            #[inline(always)]
+            fn $method(self, rhs: T) -> Self::Output {
+                let ($lhs, $rhs) = (self.0, rhs.into().0);
+                Self($expr)
+            }
+        }
+
+        impl<E, T> $assign_op<T> for Flags<E>
+        where
+            E: ReflectEnum + Into<E::Type>,
+            E::Type: FlagTypeConstraints,
+            T: Into<Flags<E>>,
+        {
+            #[inline(always)]
+            fn $assign_method(&mut self, rhs: T) {
+                let ($lhs, $rhs) = (self.0, rhs.into().0);
+                self.0 = $expr;
+            }
+        }
+    };
+}
+
+impl_flags_op!(BitOr, bitor, BitOrAssign, bitor_assign, |a, b| a | b);
+impl_flags_op!(BitAnd, bitand, BitAndAssign, bitand_assign, |a, b| a & b);
+impl_flags_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b| a ^ b);
+impl_flags_op!(Sub, sub, SubAssign, sub_assign, |a, b| a & !b);
+
+/// Same as [`Flags::complement()`].
+impl<E> Not for Flags<E>
 where
-    E: ReflectEnum + Into<E::Type>,
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
     E::Type: FlagTypeConstraints,
 {
     type Output = Self;
 
     #[inline(always)]
-    fn bitor(self, rhs: E) -> Self::Output {
-        let mut res = self;
-        res.set(rhs);
-        res
+    fn not(self) -> Self::Output {
+        self.complement()
+    }
+}
+
+/// Iterates the values of set flags in a [`Flags`] container.
+pub struct FlagsValues<E>
+where
+    E: ReflectEnum + 'static,
+{
+    iter: FlagsIterator<'static, E>,
+}
+
+impl<E> Iterator for FlagsValues<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy,
+    E::Type: FlagTypeConstraints,
+{
+    type Item = E;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.find_map(|flag| flag.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Iterates the bit indices of the set flags in a [`Flags`] container.
+pub struct FlagsPositions<E>
+where
+    E: ReflectEnum,
+{
+    raw: E::Type,
+}
+
+impl<E> Iterator for FlagsPositions<E>
+where
+    E: ReflectEnum,
+    E::Type: FlagTypeConstraints,
+{
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.raw == E::Type::zero() {
+            return None;
+        }
+        let position = self.raw.trailing_zeros();
+        self.raw = self.raw.clear_lowest_bit();
+        Some(position)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.raw.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl<E> ExactSizeIterator for FlagsPositions<E>
+where
+    E: ReflectEnum,
+    E::Type: FlagTypeConstraints,
+{
+}
+
+impl<E> IntoIterator for Flags<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints,
+{
+    type Item = E;
+    type IntoIter = FlagsValues<E>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_values()
     }
 }
 
@@ -421,7 +1135,7 @@ where
 
 impl<E> FromIterator<E> for Flags<E>
 where
-    E: ReflectEnum + Into<E::Type>,
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
     E::Type: FlagTypeConstraints,
 {
     fn from_iter<I: IntoIterator<Item = E>>(iter: I) -> Self {
@@ -433,6 +1147,30 @@ where
     }
 }
 
+impl<E> Extend<E> for Flags<E>
+where
+    E: ReflectEnum + Into<E::Type>,
+    E::Type: FlagTypeConstraints,
+{
+    fn extend<I: IntoIterator<Item = E>>(&mut self, iter: I) {
+        for flag in iter {
+            *self |= flag;
+        }
+    }
+}
+
+impl<E> Extend<Flags<E>> for Flags<E>
+where
+    E: ReflectEnum + Into<E::Type>,
+    E::Type: FlagTypeConstraints,
+{
+    fn extend<I: IntoIterator<Item = Flags<E>>>(&mut self, iter: I) {
+        for flags in iter {
+            *self |= flags;
+        }
+    }
+}
+
 impl<E> From<E> for Flags<E>
 where
     E: ReflectEnum + Into<E::Type>,
@@ -446,7 +1184,7 @@ where
 
 impl<E> From<()> for Flags<E>
 where
-    E: ReflectEnum + Into<E::Type>,
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
     E::Type: FlagTypeConstraints,
 {
     #[inline(always)]
@@ -472,7 +1210,7 @@ where
 #[cfg(feature = "serde")]
 impl<'d, E> serde::Deserialize<'d> for Flags<E>
 where
-    E: ReflectEnum + Into<E::Type>,
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
     E::Type: FlagTypeConstraints + serde::Deserialize<'d>,
 {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -484,37 +1222,59 @@ where
     }
 }
 
+/// Storage of the [`Flags`]. Implemented for the primitive integers and [`crate::enums::BitArray`].
 #[doc(hidden)]
 pub trait FlagTypeConstraints:
     Copy
-    + Zero
-    + One
     + PartialEq
     + Not<Output = Self>
-    + Sub<Output = Self>
     + BitAnd<Output = Self>
     + BitOr<Output = Self>
     + BitXor<Output = Self>
-    + TryFrom<usize>
-    + PrimInt
 {
+    /// Value with no bits set.
+    fn zero() -> Self;
+
+    /// Number of set bits.
+    fn count_ones(self) -> u32;
+
+    /// Number of zero bits below the lowest set bit.
+    fn trailing_zeros(self) -> u32;
+
+    /// Value with the lowest set bit cleared.
+    fn clear_lowest_bit(self) -> Self;
 }
 
-impl<T> FlagTypeConstraints for T where
-    T: Copy
-        + Zero
-        + One
-        + PartialEq
-        + Not<Output = T>
-        + Sub<Output = T>
-        + BitAnd<Output = T>
-        + BitOr<Output = T>
-        + BitXor<Output = T>
-        + TryFrom<usize>
-        + PrimInt
-{
+macro_rules! impl_flag_type_constraints {
+    ($($type:ty),*) => {
+        $(
+            impl FlagTypeConstraints for $type {
+                #[inline(always)]
+                fn zero() -> Self {
+                    0
+                }
+
+                #[inline(always)]
+                fn count_ones(self) -> u32 {
+                    <$type>::count_ones(self)
+                }
+
+                #[inline(always)]
+                fn trailing_zeros(self) -> u32 {
+                    <$type>::trailing_zeros(self)
+                }
+
+                #[inline(always)]
+                fn clear_lowest_bit(self) -> Self {
+                    self & self.wrapping_sub(1)
+                }
+            }
+        )*
+    };
 }
 
+impl_flag_type_constraints!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
 #[cfg(test)]
 mod test {
     use crate as adar;
@@ -559,10 +1319,97 @@ mod test {
 
     #[FlagEnum]
     #[repr(u64)]
+    #[allow(dead_code)]
     enum TestFlagsForced {
         F,
     }
 
+    #[derive(Debug)]
+    #[FlagEnum(storage = u64)]
+    #[repr(u32)]
+    enum TestWideStorage {
+        F1,
+        F2,
+    }
+
+    #[derive(Debug)]
+    #[FlagEnum(storage = u8)]
+    #[repr(u32)]
+    enum TestPackedStorage {
+        F1,
+        F2,
+    }
+
+    #[FlagEnum]
+    #[derive(Debug)]
+    #[repr(u8)]
+    #[group(
+        /// Everything but execution
+        NoExec = ReadWrite | Hidden,
+        Visible = Read | Write | Exec
+    )]
+    enum TestExplicit {
+        Read = 0b001,
+        Write = 0b010,
+        /// Both read and write
+        ReadWrite = Read | Write,
+        Exec = 1 << 3,
+        Hidden,
+        All = 0b1011 | Hidden,
+        Executable = Exec,
+    }
+
+    #[test]
+    fn test_flag_explicit_bits() {
+        assert_eq!(TestExplicit::Read as u8, 0b001);
+        assert_eq!(TestExplicit::Exec as u8, 0b1000);
+        assert_eq!(TestExplicit::Hidden as u8, 0b100);
+        assert_eq!(TestExplicit::count(), 4);
+
+        assert_eq!(
+            TestExplicit::ReadWrite,
+            TestExplicit::Read | TestExplicit::Write
+        );
+        assert_eq!(TestExplicit::All, Flags::<TestExplicit>::full());
+        assert_eq!(TestExplicit::NoExec, !TestExplicit::Exec);
+        assert_eq!(TestExplicit::Executable, TestExplicit::Exec);
+        assert_eq!(TestExplicit::Visible, !TestExplicit::Hidden);
+        assert_eq!(Flags::<TestExplicit>::full().into_raw(), 0b1111);
+        assert_eq!(
+            format!("{:?}", TestExplicit::ReadWrite),
+            "(Read,Write)".to_string()
+        );
+        assert!(Flags::<TestExplicit>::try_from_raw(0b10000).is_none());
+        assert_eq!(
+            (!TestExplicit::ReadWrite).into_raw(),
+            (TestExplicit::Hidden | TestExplicit::Exec).into_raw()
+        );
+    }
+
+    #[test]
+    fn test_flag_auto_repr() {
+        assert_eq!(size_of::<TestSmallU8>(), size_of::<u8>());
+        assert_eq!(size_of::<Flags<TestU8>>(), size_of::<u8>());
+        assert_eq!(size_of::<Flags<TestU16>>(), size_of::<u16>());
+        assert_eq!(size_of::<Flags<TestFlagsForced>>(), size_of::<u64>());
+        assert_eq!(size_of::<Flags<TestExplicit>>(), size_of::<u8>());
+        assert_eq!(size_of::<Flags<TestEmpty>>(), size_of::<u32>());
+    }
+
+    #[test]
+    fn test_flag_storage() {
+        assert_eq!(size_of::<TestWideStorage>(), size_of::<u32>());
+        assert_eq!(size_of::<Flags<TestWideStorage>>(), size_of::<u64>());
+        assert_eq!(size_of::<TestPackedStorage>(), size_of::<u32>());
+        assert_eq!(size_of::<Flags<TestPackedStorage>>(), size_of::<u8>());
+
+        let raw: u64 = (TestWideStorage::F1 | TestWideStorage::F2).into_raw();
+        assert_eq!(raw, 0b11);
+        let raw: u8 = Flags::from(TestPackedStorage::F2).into_raw();
+        assert_eq!(raw, 0b10);
+        assert_eq!(TestPackedStorage::F2 as u32, 0b10);
+    }
+
     #[test]
     fn test_flag_default() {
         let flags = Flags::<TestU8>::default();
@@ -720,6 +1567,320 @@ mod test {
         assert_eq!(flags.intersect(flags), flags);
     }
 
+    #[test]
+    fn test_flag_difference() {
+        let a = TestU16::F1 | TestU16::F3 | TestU16::F9;
+        let b = TestU16::F3 | TestU16::F7;
+        assert_eq!(a.difference(b), TestU16::F1 | TestU16::F9);
+        assert_eq!(b.difference(a), TestU16::F7);
+        assert_eq!(a.difference(a), ());
+        assert_eq!(
+            a.symmetric_difference(b),
+            TestU16::F1 | TestU16::F7 | TestU16::F9
+        );
+        assert_eq!(a.symmetric_difference(b), b.symmetric_difference(a));
+        assert_eq!(a.symmetric_difference(a), ());
+    }
+
+    #[test]
+    fn test_flag_complement() {
+        let flags = Flags::from(TestSmallU8::F1).complement();
+        assert_eq!(flags.into_raw(), 0b10);
+        assert_eq!(
+            Flags::<TestSmallU8>::try_from_raw(flags.into_raw()).unwrap(),
+            TestSmallU8::F2
+        );
+        assert_eq!(Flags::<TestSmallU8>::full().complement(), ());
+        assert_eq!(
+            Flags::<TestSmallU8>::empty().complement(),
+            Flags::<TestSmallU8>::full()
+        );
+        assert_eq!(
+            !Flags::from(TestU16::F1),
+            Flags::from(TestU16::F1).complement()
+        );
+        assert_eq!(
+            Flags::from(TestU16::F4).complement().complement(),
+            TestU16::F4
+        );
+    }
+
+    #[test]
+    fn test_flag_subset() {
+        let small = TestU16::F2 | TestU16::F9;
+        let large = TestU16::F1 | TestU16::F2 | TestU16::F9;
+        assert!(small.is_subset(large));
+        assert!(!large.is_subset(small));
+        assert!(large.is_superset(small));
+        assert!(!small.is_superset(large));
+        assert!(small.is_subset(small) && small.is_superset(small));
+        assert!(Flags::<TestU16>::empty().is_subset(small));
+        assert!(small.is_disjoint(TestU16::F1 | TestU16::F3));
+        assert!(!small.is_disjoint(large));
+        assert!(Flags::<TestU16>::empty().is_disjoint(Flags::<TestU16>::empty()));
+    }
+
+    #[test]
+    fn test_flag_mutation() {
+        let mut flags = Flags::from(TestU16::F1);
+        flags.set_value(TestU16::F5 | TestU16::F9, true);
+        assert_eq!(flags, TestU16::F1 | TestU16::F5 | TestU16::F9);
+        flags.set_value(TestU16::F1, false);
+        assert_eq!(flags, TestU16::F5 | TestU16::F9);
+        flags.set_value(TestU16::F2, false);
+        assert_eq!(flags, TestU16::F5 | TestU16::F9);
+
+        flags.set(TestU16::F2 | TestU16::F4);
+        flags.retain(|flag| matches!(flag, TestU16::F2 | TestU16::F9));
+        assert_eq!(flags, TestU16::F2 | TestU16::F9);
+        flags.retain(|_| true);
+        assert_eq!(flags, TestU16::F2 | TestU16::F9);
+
+        flags.clear();
+        assert!(flags.is_empty());
+        flags.retain(|_| unreachable!());
+    }
+
+    #[test]
+    fn test_flag_hash_ord() {
+        use std::collections::{BTreeSet, HashMap};
+
+        let mut map = HashMap::new();
+        map.insert(TestU16::F1 | TestU16::F2, "a");
+        map.insert(Flags::from(TestU16::F9), "b");
+        assert_eq!(map[&(TestU16::F2 | TestU16::F1)], "a");
+        assert_eq!(map.get(&Flags::from(TestU16::F9)), Some(&"b"));
+        assert_eq!(map.get(&Flags::empty()), None);
+
+        let set = BTreeSet::from([
+            Flags::from(TestU16::F9),
+            TestU16::F1 | TestU16::F2,
+            Flags::empty(),
+            Flags::from(TestU16::F3),
+        ]);
+        assert_eq!(
+            set.into_iter().collect::<Vec<_>>(),
+            [
+                Flags::empty(),
+                TestU16::F1 | TestU16::F2,
+                Flags::from(TestU16::F3),
+                Flags::from(TestU16::F9)
+            ]
+        );
+        assert!(Flags::from(TestU16::F9) > TestU16::F1 | TestU16::F8);
+    }
+
+    #[test]
+    fn test_flag_from_raw_lossy() {
+        let raw = 0b1111_0000_0000_0101;
+        let truncated = Flags::<TestU16>::from_raw_truncate(raw);
+        assert_eq!(truncated, TestU16::F1 | TestU16::F3);
+        assert_eq!(
+            Flags::<TestU16>::try_from_raw(truncated.into_raw()),
+            Some(truncated)
+        );
+
+        let retained = Flags::<TestU16>::from_raw_retain(raw);
+        assert_eq!(retained.into_raw(), raw);
+        assert!(retained.all(TestU16::F1 | TestU16::F3));
+        assert_eq!(
+            retained.iter_values().collect::<Vec<_>>(),
+            [TestU16::F1, TestU16::F3]
+        );
+        assert_eq!((retained - TestU16::F1).into_raw(), 0b1111_0000_0000_0100);
+        assert_eq!(retained.complement(), !truncated);
+        assert!(Flags::<TestU16>::try_from_raw(raw).is_none());
+    }
+
+    #[test]
+    fn test_flag_single() {
+        assert!(Flags::from(TestU16::F9).is_single());
+        assert_eq!(Flags::from(TestU16::F9).as_single(), Some(TestU16::F9));
+        assert!(!(TestU16::F1 | TestU16::F9).is_single());
+        assert_eq!((TestU16::F1 | TestU16::F9).as_single(), None);
+        assert_eq!(Flags::<TestU16>::empty().as_single(), None);
+
+        // Unknown bits are not counted
+        let unknown = Flags::<TestU16>::from_raw_retain(0b1000_0000_0000_0000);
+        assert_eq!(unknown.len(), 0);
+        assert!(!unknown.is_single());
+        assert_eq!(unknown.as_single(), None);
+        let retained = unknown | TestU16::F9;
+        assert_eq!(retained.len(), 1);
+        assert!(retained.is_single());
+        assert_eq!(retained.as_single(), Some(TestU16::F9));
+        assert_eq!(Flags::<TestU16>::from_raw_retain(0xFFFF).len(), 9);
+    }
+
+    #[test]
+    fn test_flag_extend() {
+        let mut flags = Flags::from(TestU16::F1);
+        flags.extend([TestU16::F2, TestU16::F9]);
+        assert_eq!(flags, TestU16::F1 | TestU16::F2 | TestU16::F9);
+        flags.extend([TestU16::F3 | TestU16::F4, Flags::from(TestU16::F1)]);
+        assert_eq!(
+            flags,
+            TestU16::F1 | TestU16::F2 | TestU16::F3 | TestU16::F4 | TestU16::F9
+        );
+        flags.extend(Vec::<TestU16>::new());
+        assert_eq!(flags.len(), 5);
+    }
+
+    #[test]
+    fn test_flag_positions() {
+        let flags = TestU16::F1 | TestU16::F8 | TestU16::F9;
+        assert_eq!(flags.positions().collect::<Vec<_>>(), [0, 7, 8]);
+        assert_eq!(flags.positions().len(), 3);
+        assert_eq!(Flags::<TestU16>::empty().positions().next(), None);
+        assert_eq!(
+            (TestExplicit::Exec | TestExplicit::Read)
+                .positions()
+                .collect::<Vec<_>>(),
+            [0, 3]
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_flag_rename_all() {
+        #[FlagEnum(rename_all = "kebab-case")]
+        #[derive(Debug)]
+        enum TestRenamed {
+            ReadOnly,
+            WriteOnly,
+        }
+
+        let flags = TestRenamed::ReadOnly | TestRenamed::WriteOnly;
+        assert_eq!(flags.to_string(), "read-only|write-only");
+        assert_eq!(
+            "write-only".parse(),
+            Ok(Flags::from(TestRenamed::WriteOnly))
+        );
+    }
+
+    #[test]
+    fn test_flag_map() {
+        let flags = TestU16::F1 | TestU16::F2 | TestU16::F9;
+        let mapped = flags.map(|flag| match flag {
+            TestU16::F1 => Some(TestU8::F8),
+            TestU16::F2 => Some(TestU8::F8),
+            _ => None,
+        });
+        assert_eq!(mapped, TestU8::F8);
+        assert_eq!(flags.map(|_| None::<TestU8>), Flags::<TestU8>::empty());
+
+        assert_eq!(
+            (TestU16::F1 | TestU16::F8).try_cast(),
+            Ok(TestU8::F1 | TestU8::F8)
+        );
+        assert_eq!(flags.try_cast::<TestU8>(), Err(TestU16::F9));
+        assert_eq!(
+            Flags::<TestU16>::empty().try_cast::<TestU8>(),
+            Ok(Flags::empty())
+        );
+    }
+
+    #[test]
+    fn test_flag_operators() {
+        let a = TestU16::F1 | TestU16::F2 | TestU16::F3;
+        let b = TestU16::F3 | TestU16::F9;
+        assert_eq!(a | b, TestU16::F1 | TestU16::F2 | TestU16::F3 | TestU16::F9);
+        assert_eq!(a & b, TestU16::F3);
+        assert_eq!(a ^ b, TestU16::F1 | TestU16::F2 | TestU16::F9);
+        assert_eq!(a - b, TestU16::F1 | TestU16::F2);
+        assert_eq!(a & !b, a - b);
+        assert_eq!(!Flags::<TestU16>::empty(), Flags::<TestU16>::full());
+        assert_eq!((!a).into_raw(), 0b1_1111_1000);
+
+        assert_eq!(TestU16::F1 & TestU16::F2, ());
+        assert_eq!(TestU16::F1 ^ a, TestU16::F2 | TestU16::F3);
+        assert_eq!(TestU16::F3 - b, ());
+        assert_eq!(!TestU8::F1, Flags::<TestU8>::full() - TestU8::F1);
+
+        let mut flags = a;
+        flags |= TestU16::F9;
+        assert_eq!(flags, a | TestU16::F9);
+        flags &= b;
+        assert_eq!(flags, b);
+        flags ^= TestU16::F1 | TestU16::F3;
+        assert_eq!(flags, TestU16::F1 | TestU16::F9);
+        flags -= TestU16::F9;
+        assert_eq!(flags, TestU16::F1);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_flag_display_parse() {
+        let flags = TestU16::F1 | TestU16::F3 | TestU16::F9;
+        assert_eq!(flags.to_string(), "F1|F3|F9");
+        assert_eq!(flags.to_string().parse::<Flags<TestU16>>(), Ok(flags));
+        assert_eq!(Flags::<TestU16>::empty().to_string(), "");
+        assert_eq!("".parse::<Flags<TestU16>>(), Ok(Flags::empty()));
+        assert_eq!(
+            " F2 | F1 ".parse::<Flags<TestU16>>(),
+            Ok(TestU16::F1 | TestU16::F2)
+        );
+
+        let text = flags.display_with(" + ").to_string();
+        assert_eq!(text, "F1 + F3 + F9");
+        assert_eq!(Flags::<TestU16>::parse_with(&text, "+"), Ok(flags));
+        assert_eq!(
+            "F1|f2".parse::<Flags<TestU16>>(),
+            Err(UnknownFlag("f2".to_string()))
+        );
+        assert_eq!(
+            UnknownFlag("f2".to_string()).to_string(),
+            "Unknown flag: f2"
+        );
+    }
+
+    #[test]
+    fn test_flag_iter_values() {
+        let flags = TestU16::F2 | TestU16::F5 | TestU16::F9;
+        assert_eq!(
+            flags.iter_values().collect::<Vec<_>>(),
+            [TestU16::F2, TestU16::F5, TestU16::F9]
+        );
+        let mut count = 0;
+        for flag in flags {
+            assert!(flags.all(flag));
+            count += 1;
+        }
+        assert_eq!(count, 3);
+        assert_eq!(Flags::<TestU16>::empty().into_iter().next(), None);
+        assert_eq!(flags.into_iter().collect::<Flags<_>>(), flags);
+    }
+
+    #[test]
+    fn test_flag_iter_bit_order() {
+        let names = |flags: Flags<TestExplicit>| {
+            flags
+                .iter()
+                .map(|flag| flag.name)
+                .collect::<Vec<_>>()
+                .join("|")
+        };
+        assert_eq!(names(Flags::full()), "Read|Write|Hidden|Exec");
+        assert_eq!(
+            names(TestExplicit::Exec | TestExplicit::Write),
+            "Write|Exec"
+        );
+        assert_eq!(names(Flags::from_raw_retain(0b1111_0001)), "Read");
+        assert_eq!(
+            Flags::<TestExplicit>::full().iter().size_hint(),
+            (0, Some(4))
+        );
+    }
+
+    #[test]
+    fn test_flag_fmt_raw() {
+        let flags = TestU16::F1 | TestU16::F3 | TestU16::F9;
+        assert_eq!(format!("{:b}", flags), "100000101");
+        assert_eq!(format!("{:#014b}", flags), "0b000100000101");
+        assert_eq!(format!("{:x}", flags), "105");
+        assert_eq!(format!("{:#06X}", TestU16::F5 | TestU16::F7), "0x0050");
+    }
+
     #[test]
     fn test_flag_debug() {
         let flags = TestU16::F1 | TestU16::F3 | TestU16::F5 | TestU16::F7;
@@ -0,0 +1,161 @@
+//! Serializes [`Flags`] as a map of booleans with an entry for every flag (e.g. `{"A": true, "B": false}`),
+//! instead of the raw value. Use it with `#[serde(with = "adar::enums::flags_as_map")]`.
+//!
+//! Missing flags are deserialized as unset, unknown flags are rejected.
+//!
+//! # Example
+//! ```
+//! use adar::prelude::*;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[FlagEnum]
+//! #[derive(Debug)]
+//! enum Permission {
+//!     Read,
+//!     Write,
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct User {
+//!     #[serde(with = "adar::enums::flags_as_map")]
+//!     permissions: Flags<Permission>,
+//! }
+//!
+//! let user = User { permissions: Permission::Read.into() };
+//! let json = serde_json::to_string(&user).unwrap();
+//! assert_eq!(json, r#"{"permissions":{"Read":true,"Write":false}}"#);
+//!
+//! let user: User = serde_json::from_str(r#"{"permissions":{"Write":true}}"#).unwrap();
+//! assert_eq!(user.permissions, Permission::Write);
+//! ```
+
+use crate::prelude::{FlagTypeConstraints, Flags, ReflectEnum};
+use core::{fmt, marker::PhantomData};
+use serde::{
+    de::{DeserializeSeed, Error, MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserializer, Serializer,
+};
+
+pub fn serialize<E, S>(flags: &Flags<E>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints,
+    S: Serializer,
+{
+    let mut map = serializer.serialize_map(Some(E::count()))?;
+    for variant in E::variants() {
+        if let Some(value) = variant.value {
+            map.serialize_entry(variant.name, &flags.any(value))?;
+        }
+    }
+    map.end()
+}
+
+pub fn deserialize<'de, E, D>(deserializer: D) -> Result<Flags<E>, D::Error>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_map(FlagsVisitor(PhantomData))
+}
+
+struct FlagsVisitor<E>(PhantomData<E>);
+
+impl<'de, E> Visitor<'de> for FlagsVisitor<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints,
+{
+    type Value = Flags<E>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of flag names to booleans")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut flags = Flags::empty();
+        while let Some(value) = map.next_key_seed(FlagName::<E>(PhantomData))? {
+            if map.next_value()? {
+                flags.set(value);
+            }
+        }
+        Ok(flags)
+    }
+}
+
+/// Looks up the flag by the name of the key, so the name does not need to be allocated.
+struct FlagName<E>(PhantomData<E>);
+
+impl<'de, E> DeserializeSeed<'de> for FlagName<E>
+where
+    E: ReflectEnum + Copy + 'static,
+{
+    type Value = E;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<E> Visitor<'_> for FlagName<E>
+where
+    E: ReflectEnum + Copy + 'static,
+{
+    type Value = E;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a flag name")
+    }
+
+    fn visit_str<Er>(self, name: &str) -> Result<Self::Value, Er>
+    where
+        Er: Error,
+    {
+        E::from_name(name).ok_or_else(|| Er::custom(format_args!("Unknown flag: {}", name)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug)]
+    #[FlagEnum]
+    enum TestFlags {
+        A,
+        B,
+        C,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct TestStruct {
+        #[serde(with = "adar::enums::flags_as_map")]
+        flags: Flags<TestFlags>,
+    }
+
+    #[test]
+    fn test_flags_as_map() {
+        let value = TestStruct {
+            flags: TestFlags::A | TestFlags::C,
+        };
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, r#"{"flags":{"A":true,"B":false,"C":true}}"#);
+        let value: TestStruct = serde_json::from_str(&json).unwrap();
+        assert_eq!(value.flags, TestFlags::A | TestFlags::C);
+
+        let value: TestStruct = serde_json::from_str(r#"{"flags":{"B":true,"C":false}}"#).unwrap();
+        assert_eq!(value.flags, TestFlags::B);
+        let value: TestStruct = serde_json::from_str(r#"{"flags":{"\u0041":true}}"#).unwrap();
+        assert_eq!(value.flags, TestFlags::A);
+        assert!(serde_json::from_str::<TestStruct>(r#"{"flags":{"D":true}}"#).is_err());
+    }
+}
@@ -1,8 +1,40 @@
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+mod arbitrary_flags;
+#[cfg(feature = "rkyv")]
+mod archive;
+mod atomic_flags;
+mod bit_array;
+#[cfg(feature = "bitflags")]
+mod bitflags_interop;
+mod enum_delegate;
+mod enum_from;
+#[cfg(feature = "alloc")]
+mod enum_map;
+#[cfg(feature = "registry")]
+mod enum_registry;
 mod enum_trait_deref;
 mod flags;
+#[cfg(feature = "serde")]
+pub mod flags_as_map;
 mod reflect;
+#[cfg(feature = "schema")]
+pub mod schema;
+mod single;
 
+#[cfg(feature = "rkyv")]
+pub use archive::*;
+pub use atomic_flags::*;
+pub use bit_array::*;
+#[allow(unused_imports)]
+pub use enum_delegate::*;
+#[allow(unused_imports)]
+pub use enum_from::*;
+#[cfg(feature = "alloc")]
+pub use enum_map::*;
+#[cfg(feature = "registry")]
+pub use enum_registry::*;
 #[allow(unused_imports)]
 pub use enum_trait_deref::*;
 pub use flags::*;
 pub use reflect::*;
+pub use single::*;
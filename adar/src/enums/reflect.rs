@@ -1,12 +1,222 @@
-#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+use crate::prelude::FlagTypeConstraints;
+use core::any::TypeId;
+
+#[derive(Debug, Copy, Clone)]
 pub struct EnumVariant<T> {
     pub name: &'static str,
     pub value: Option<T>,
+    discriminant: i128,
+    fields: &'static [FieldInfo],
+    doc: Option<&'static str>,
+    metadata: &'static [(&'static str, &'static str)],
+}
+
+/// Compares the name and the value only, so [`EnumVariant::new()`] equals the reflected variant.
+impl<T: PartialEq> PartialEq for EnumVariant<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.value == other.value
+    }
 }
 
+impl<T: Eq> Eq for EnumVariant<T> {}
+
 impl<T> EnumVariant<T> {
     pub const fn new(name: &'static str, value: Option<T>) -> Self {
-        Self { name, value }
+        Self {
+            name,
+            value,
+            discriminant: 0,
+            fields: &[],
+            doc: None,
+            metadata: &[],
+        }
+    }
+
+    /// Sets the discriminant of the variant.
+    pub const fn with_discriminant(mut self, discriminant: i128) -> Self {
+        self.discriminant = discriminant;
+        self
+    }
+
+    /// Gets the discriminant of the variant, including the variants with fields. Explicit discriminants
+    /// (e.g. `A = 1 << 4`) are evaluated in the `#[repr]` of the enum, the implicit ones follow the previous
+    /// variant.
+    ///
+    /// The discriminants are widened to `i128`, the ones of `u128` enums above `i128::MAX` can be
+    /// converted back with `as u128`.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[ReflectEnum]
+    /// #[repr(u8)]
+    /// enum Packet {
+    ///     Ping = 1 << 4,
+    ///     Pong,
+    ///     Data(Vec<u8>) = 0x80,
+    /// }
+    ///
+    /// let discriminants = Packet::variants().iter().map(|variant| variant.discriminant());
+    /// assert_eq!(discriminants.collect::<Vec<_>>(), [16, 17, 128]);
+    /// ```
+    pub const fn discriminant(&self) -> i128 {
+        self.discriminant
+    }
+
+    /// Copies the metadata of the variant without the value, which is `Some(())` for unit variants.
+    pub const fn erased(&self) -> EnumVariant<()> {
+        EnumVariant {
+            name: self.name,
+            value: match self.value {
+                Some(_) => Some(()),
+                None => None,
+            },
+            discriminant: self.discriminant,
+            fields: self.fields,
+            doc: self.doc,
+            metadata: self.metadata,
+        }
+    }
+
+    /// Sets the fields of the variant.
+    pub const fn with_fields(mut self, fields: &'static [FieldInfo]) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    /// Gets the fields of the variant, in declaration order. Unit variants have no fields.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    /// use std::any::TypeId;
+    ///
+    /// #[ReflectEnum]
+    /// enum Shape {
+    ///     Point,
+    ///     Circle(f32),
+    ///     Rect { width: u32, height: u32 },
+    /// }
+    ///
+    /// let [point, circle, rect] = Shape::variants() else { unreachable!() };
+    /// assert!(point.fields().is_empty());
+    /// assert_eq!(circle.fields(), [FieldInfo::new("0", TypeId::of::<f32>())]);
+    /// assert_eq!(rect.fields().iter().map(|field| field.name).collect::<Vec<_>>(), ["width", "height"]);
+    /// ```
+    pub const fn fields(&self) -> &'static [FieldInfo] {
+        self.fields
+    }
+
+    /// Sets the doc comment of the variant.
+    pub const fn with_doc(mut self, doc: &'static str) -> Self {
+        self.doc = Some(doc);
+        self
+    }
+
+    /// Gets the doc comment of the variant, without the leading space of the lines.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[ReflectEnum]
+    /// enum Access {
+    ///     /// Can only be read.
+    ///     #[reflect(label = "Read only", color = "red")]
+    ///     ReadOnly,
+    ///     ReadWrite,
+    /// }
+    ///
+    /// let [read_only, read_write] = Access::variants() else { unreachable!() };
+    /// assert_eq!(read_only.doc(), Some("Can only be read."));
+    /// assert_eq!(read_only.meta("label"), Some("Read only"));
+    /// assert_eq!(read_only.metadata(), [("label", "Read only"), ("color", "red")]);
+    /// assert_eq!(read_write.doc(), None);
+    /// assert_eq!(read_write.meta("label"), None);
+    /// ```
+    pub const fn doc(&self) -> Option<&'static str> {
+        self.doc
+    }
+
+    /// Sets the custom metadata of the variant.
+    pub const fn with_metadata(
+        mut self,
+        metadata: &'static [(&'static str, &'static str)],
+    ) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Gets the custom metadata declared with `#[reflect(key = "value")]`, in declaration order.
+    /// See [`EnumVariant::doc()`].
+    pub const fn metadata(&self) -> &'static [(&'static str, &'static str)] {
+        self.metadata
+    }
+
+    /// Gets the value of a custom metadata, see [`EnumVariant::metadata()`].
+    pub fn meta(&self, key: &str) -> Option<&'static str> {
+        self.metadata
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|&(_, value)| value)
+    }
+}
+
+/// Reflected field of an [`EnumVariant`] or a [`ReflectStruct`](crate::structs::ReflectStruct).
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct FieldInfo {
+    /// Name of the field, or its position for tuple variants and structs (e.g. `"0"`).
+    pub name: &'static str,
+    /// Type of the field.
+    pub type_id: TypeId,
+    doc: Option<&'static str>,
+    metadata: &'static [(&'static str, &'static str)],
+}
+
+impl FieldInfo {
+    pub const fn new(name: &'static str, type_id: TypeId) -> Self {
+        Self {
+            name,
+            type_id,
+            doc: None,
+            metadata: &[],
+        }
+    }
+
+    /// Sets the doc comment of the field.
+    pub const fn with_doc(mut self, doc: &'static str) -> Self {
+        self.doc = Some(doc);
+        self
+    }
+
+    /// Gets the doc comment of the field, without the leading space of the lines.
+    /// Only reflected by [`ReflectStruct`](crate::structs::ReflectStruct).
+    pub const fn doc(&self) -> Option<&'static str> {
+        self.doc
+    }
+
+    /// Sets the custom metadata of the field.
+    pub const fn with_metadata(
+        mut self,
+        metadata: &'static [(&'static str, &'static str)],
+    ) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Gets the custom metadata declared with `#[reflect(key = "value")]`, in declaration order.
+    /// Only reflected by [`ReflectStruct`](crate::structs::ReflectStruct).
+    pub const fn metadata(&self) -> &'static [(&'static str, &'static str)] {
+        self.metadata
+    }
+
+    /// Gets the value of a custom metadata, see [`FieldInfo::metadata()`].
+    pub fn meta(&self, key: &str) -> Option<&'static str> {
+        self.metadata
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|&(_, value)| value)
     }
 }
 
@@ -14,7 +224,312 @@ pub trait ReflectEnum: Sized {
     type Type;
     fn variants() -> &'static [EnumVariant<Self>];
     fn count() -> usize;
+
     fn name(&self) -> &'static str;
+
+    /// Gets the unit variant with the specified name. The default implementation searches
+    /// [`ReflectEnum::variants()`], the macro generates a `match` instead.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[ReflectEnum]
+    /// #[derive(Debug, Clone, PartialEq)]
+    /// enum Level {Low, High, Custom(u32)}
+    ///
+    /// assert_eq!(Level::from_name("High"), Some(Level::High));
+    /// assert_eq!(Level::from_name("Custom"), None);
+    /// assert_eq!(Level::from_name("high"), None);
+    /// ```
+    fn from_name(name: &str) -> Option<Self>
+    where
+        Self: Clone + 'static,
+    {
+        Self::variants()
+            .iter()
+            .find(|variant| variant.name == name)
+            .and_then(|variant| variant.value.clone())
+    }
+
+    /// Gets the position of the variant in [`ReflectEnum::variants()`]. The default implementation searches
+    /// the variants by [`ReflectEnum::name()`], the macro generates a `match` instead.
+    ///
+    /// # Returns
+    /// The position, or [`None`] for variants skipped with `#[reflect(skip)]`.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[ReflectEnum]
+    /// #[derive(Debug, PartialEq)]
+    /// enum Tab {General, Advanced(u32), About}
+    ///
+    /// assert_eq!(Tab::About.index(), Some(2));
+    /// assert_eq!(Tab::from_index(Tab::Advanced(5).index().unwrap()).unwrap().name, "Advanced");
+    /// assert_eq!(Tab::from_index(3), None);
+    /// ```
+    fn index(&self) -> Option<usize>
+    where
+        Self: 'static,
+    {
+        let name = self.name();
+        Self::variants()
+            .iter()
+            .position(|variant| variant.name == name)
+    }
+
+    /// Gets the variant at the position in [`ReflectEnum::variants()`], see [`ReflectEnum::index()`].
+    fn from_index(index: usize) -> Option<&'static EnumVariant<Self>> {
+        Self::variants().get(index)
+    }
+
+    /// Creates an iterator to iterate through the values of the unit variants.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[ReflectEnum]
+    /// #[derive(Debug, Clone, Copy, PartialEq)]
+    /// enum Mode {Off, Eco, Custom(u8), Boost}
+    ///
+    /// assert_eq!(Mode::values().collect::<Vec<_>>(), [Mode::Off, Mode::Eco, Mode::Boost]);
+    /// assert_eq!(Mode::Eco.next_variant(), Mode::Boost);
+    /// assert_eq!(Mode::Boost.next_variant(), Mode::Off);
+    /// assert_eq!(Mode::Off.prev_variant(), Mode::Boost);
+    /// assert_eq!(Mode::Custom(5).prev_variant(), Mode::Eco);
+    /// ```
+    fn values() -> impl DoubleEndedIterator<Item = Self>
+    where
+        Self: Copy + 'static,
+    {
+        Self::variants().iter().filter_map(|variant| variant.value)
+    }
+
+    /// Gets the values of the unit variants combined, the raw value of [`crate::enums::Flags::full()`]. The default
+    /// implementation combines [`ReflectEnum::values()`], the macro generates a constant instead.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// enum Access {Read, Write, Execute}
+    ///
+    /// assert_eq!(Access::all_bits(), 0b111);
+    /// ```
+    fn all_bits() -> Self::Type
+    where
+        Self: Into<Self::Type> + Copy + 'static,
+        Self::Type: FlagTypeConstraints,
+    {
+        Self::values().fold(Self::Type::zero(), |bits, value| bits | value.into())
+    }
+
+    /// Gets the next unit variant, wrapping around after the last one. See [`ReflectEnum::values()`].
+    ///
+    /// # Returns
+    /// The next unit variant, or `self` if there are no other unit variants.
+    fn next_variant(&self) -> Self
+    where
+        Self: Copy + 'static,
+    {
+        let variants = Self::variants();
+        let start = self.index().map_or(variants.len(), |index| index + 1);
+        variants[start..]
+            .iter()
+            .chain(&variants[..start])
+            .find_map(|variant| variant.value)
+            .unwrap_or(*self)
+    }
+
+    /// Gets the previous unit variant, wrapping around before the first one. See [`ReflectEnum::values()`].
+    ///
+    /// # Returns
+    /// The previous unit variant, or `self` if there are no other unit variants.
+    fn prev_variant(&self) -> Self
+    where
+        Self: Copy + 'static,
+    {
+        let variants = Self::variants();
+        let end = self.index().unwrap_or(variants.len());
+        variants[..end]
+            .iter()
+            .rev()
+            .chain(variants[end..].iter().rev())
+            .find_map(|variant| variant.value)
+            .unwrap_or(*self)
+    }
+}
+
+/// Reflected variants as constants, usable in const contexts (e.g. `[u8; MyEnum::COUNT]`).
+/// Implemented by the `#[ReflectEnum]` macro along with [`ReflectEnum`], whose
+/// [`ReflectEnum::variants()`] and [`ReflectEnum::count()`] return the same values.
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+///
+/// #[ReflectEnum]
+/// enum Axis {X, Y, Z}
+///
+/// const LABELS: [&str; Axis::COUNT] = [Axis::VARIANTS[0].name, Axis::VARIANTS[1].name, Axis::VARIANTS[2].name];
+/// assert_eq!(LABELS, ["X", "Y", "Z"]);
+/// ```
+pub trait ReflectEnumConsts: ReflectEnum + 'static {
+    /// Number of the reflected variants. See [`ReflectEnum::count()`].
+    const COUNT: usize;
+
+    /// Reflected variants. See [`ReflectEnum::variants()`].
+    const VARIANTS: &'static [EnumVariant<Self>];
+}
+
+/// Error reported when converting a value which is not a discriminant of the enum.
+///
+/// `#[ReflectEnum]` generates [`TryFrom`] from [`ReflectEnum::Type`] for enums with only unit variants.
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+///
+/// #[ReflectEnum]
+/// #[repr(u8)]
+/// #[derive(Debug, PartialEq)]
+/// enum Command {Start = 1, Stop = 2}
+///
+/// assert_eq!(Command::try_from(2), Ok(Command::Stop));
+/// assert_eq!(Command::try_from(3), Err(UnknownDiscriminant(3)));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct UnknownDiscriminant<T>(pub T);
+
+impl<T: core::fmt::Display> core::fmt::Display for UnknownDiscriminant<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Unknown discriminant: {}", self.0)
+    }
+}
+
+impl<T: core::fmt::Debug + core::fmt::Display> core::error::Error for UnknownDiscriminant<T> {}
+
+/// Error reported when parsing a name which is not a unit variant of the enum.
+///
+/// `#[ReflectEnum(display)]` implements [`Display`](core::fmt::Display) and [`FromStr`](core::str::FromStr)
+/// with the reflected names, see [`ReflectEnum::name()`] and [`ReflectEnum::from_name()`].
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+///
+/// #[ReflectEnum(display, rename_all = "snake_case")]
+/// #[derive(Debug, PartialEq)]
+/// enum LogLevel {Warning, Error}
+///
+/// assert_eq!(LogLevel::Warning.to_string(), "warning");
+/// assert_eq!(format!("[{:>7}]", LogLevel::Error), "[  error]");
+/// assert_eq!("error".parse(), Ok(LogLevel::Error));
+/// assert_eq!("Error".parse::<LogLevel>(), Err(UnknownVariant));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct UnknownVariant;
+
+impl core::fmt::Display for UnknownVariant {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Unknown variant")
+    }
+}
+
+impl core::error::Error for UnknownVariant {}
+
+/// Implements the rkyv traits for unit enums. Invoked by [`crate::macros::ReflectEnum`].
+#[cfg(feature = "rkyv")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __archive_unit_enum {
+    ($enum:ident, $repr:ty, [$($variant:ident),+]) => {
+        impl $crate::enums::ArchiveUnitEnum for $enum {
+            fn to_raw(&self) -> $repr {
+                match self {
+                    $(Self::$variant => Self::$variant as $repr),+
+                }
+            }
+
+            fn from_raw(raw: $repr) -> Option<Self> {
+                $(
+                    if raw == Self::$variant as $repr {
+                        return Some(Self::$variant);
+                    }
+                )+
+                None
+            }
+        }
+
+        impl $crate::rkyv::Archive for $enum {
+            type Archived = $crate::enums::ArchivedEnum<$enum>;
+            type Resolver = <$repr as $crate::rkyv::Archive>::Resolver;
+
+            fn resolve(&self, resolver: Self::Resolver, out: $crate::rkyv::Place<Self::Archived>) {
+                $crate::enums::ArchivedEnum::resolve_enum(self, resolver, out);
+            }
+        }
+
+        impl<S> $crate::rkyv::Serialize<S> for $enum
+        where
+            S: $crate::rkyv::rancor::Fallible + ?Sized,
+        {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                $crate::rkyv::Serialize::<S>::serialize(
+                    &$crate::enums::ArchiveUnitEnum::to_raw(self),
+                    serializer,
+                )
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "rkyv"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __archive_unit_enum {
+    ($($tokens:tt)*) => {};
+}
+
+/// Implements [`clap::ValueEnum`] for unit enums with the reflected names, and the doc comments as help.
+/// Invoked by [`crate::macros::ReflectEnum`].
+#[cfg(feature = "clap")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __clap_value_enum {
+    ($enum:ident, [$($variant:ident),*]) => {
+        // The higher-ranked bound skips the enums which are not `Clone` instead of failing to compile
+        impl $crate::clap::ValueEnum for $enum
+        where
+            for<'a> $enum: Clone,
+        {
+            fn value_variants<'a>() -> &'a [Self] {
+                &[$(Self::$variant),*]
+            }
+
+            fn to_possible_value(&self) -> Option<$crate::clap::builder::PossibleValue> {
+                let variant = <Self as $crate::enums::ReflectEnum>::from_index(
+                    $crate::enums::ReflectEnum::index(self)?,
+                )?;
+                let value = $crate::clap::builder::PossibleValue::new(variant.name);
+                Some(match variant.doc() {
+                    Some(doc) => value.help(doc),
+                    None => value,
+                })
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "clap"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __clap_value_enum {
+    ($($tokens:tt)*) => {};
 }
 
 #[cfg(test)]
@@ -23,7 +538,7 @@ mod test {
     use std::any::TypeId;
 
     #[ReflectEnum]
-    #[derive(Debug, Eq, PartialEq)]
+    #[derive(Debug, Clone, Eq, PartialEq)]
     enum MixedTestEnum {
         Elem1,
         Elem2(u32),
@@ -55,6 +570,33 @@ mod test {
         assert_eq!(MixedTestEnum::count(), 3);
     }
 
+    #[test]
+    fn test_enum_fields() {
+        #[ReflectEnum]
+        #[derive(Debug)]
+        #[allow(dead_code)]
+        enum TestEnum {
+            Leaf(&'static str, Option<u8>),
+            Node { children: Vec<Self> },
+        }
+
+        let [leaf, node] = TestEnum::variants() else {
+            unreachable!()
+        };
+        assert_eq!(
+            leaf.fields(),
+            [
+                FieldInfo::new("0", TypeId::of::<&str>()),
+                FieldInfo::new("1", TypeId::of::<Option<u8>>())
+            ]
+        );
+        assert_eq!(
+            node.fields(),
+            [FieldInfo::new("children", TypeId::of::<Vec<TestEnum>>())]
+        );
+        assert!(MixedTestEnum::variants()[0].fields().is_empty());
+    }
+
     #[test]
     fn test_enum_name() {
         assert_eq!(MixedTestEnum::Elem1.name(), "Elem1");
@@ -62,6 +604,392 @@ mod test {
         assert_eq!(MixedTestEnum::Elem3 { a: 0, b: 0 }.name(), "Elem3");
     }
 
+    #[test]
+    fn test_enum_from_name() {
+        assert_eq!(
+            MixedTestEnum::from_name("Elem1"),
+            Some(MixedTestEnum::Elem1)
+        );
+        assert_eq!(MixedTestEnum::from_name("Elem2"), None);
+        assert_eq!(MixedTestEnum::from_name("Elem4"), None);
+        assert_eq!(MixedTestEnum::from_name(""), None);
+    }
+
+    #[test]
+    fn test_enum_storage() {
+        #[ReflectEnum(storage = u8)]
+        #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+        #[repr(u32)]
+        enum TestEnum {
+            Low,
+            Mid = 128,
+            High = 255,
+        }
+
+        let raw: u8 = TestEnum::High.into();
+        assert_eq!(raw, 255);
+        assert_eq!(TestEnum::try_from(128u8), Ok(TestEnum::Mid));
+        assert_eq!(TestEnum::try_from(1u8), Err(UnknownDiscriminant(1)));
+        assert_eq!(TestEnum::Low.index(), Some(0));
+    }
+
+    #[test]
+    fn test_manual_impl() {
+        #[derive(Debug, Clone, PartialEq)]
+        enum Manual {
+            A,
+            B(u8),
+            Hidden,
+        }
+
+        impl ReflectEnum for Manual {
+            type Type = u8;
+
+            fn variants() -> &'static [EnumVariant<Self>] {
+                const VARIANTS: &[EnumVariant<Manual>] = &[
+                    EnumVariant::new("A", Some(Manual::A)),
+                    EnumVariant::new("B", None),
+                ];
+                VARIANTS
+            }
+
+            fn count() -> usize {
+                2
+            }
+
+            fn name(&self) -> &'static str {
+                match self {
+                    Manual::A => "A",
+                    Manual::B(_) => "B",
+                    Manual::Hidden => "Hidden",
+                }
+            }
+        }
+
+        assert_eq!(Manual::A.index(), Some(0));
+        assert_eq!(Manual::B(1).index(), Some(1));
+        assert_eq!(Manual::Hidden.index(), None);
+        assert_eq!(Manual::from_name("A"), Some(Manual::A));
+        assert_eq!(Manual::from_name("B"), None);
+        assert_eq!(Manual::from_name("Hidden"), None);
+    }
+
+    #[test]
+    fn test_enum_doc_metadata() {
+        #[ReflectEnum]
+        #[derive(Debug)]
+        #[allow(dead_code)]
+        enum TestEnum {
+            ///  Indented first line.
+            /// Second line.
+            #[reflect(label = "Documented")]
+            E1,
+            #[reflect(label = "With data", icon = "\u{1F4BE}")]
+            E2(u32),
+            E3,
+        }
+
+        let [e1, e2, e3] = TestEnum::variants() else {
+            unreachable!()
+        };
+        assert_eq!(e1.doc(), Some("Indented first line.\nSecond line."));
+        assert_eq!(e1.metadata(), [("label", "Documented")]);
+        assert_eq!(e2.doc(), None);
+        assert_eq!(e2.meta("icon"), Some("\u{1F4BE}"));
+        assert_eq!(e2.meta("label"), Some("With data"));
+        assert_eq!(e3.doc(), None);
+        assert!(e3.metadata().is_empty());
+    }
+
+    #[test]
+    fn test_enum_skip_rename() {
+        #[ReflectEnum]
+        #[derive(Debug, Clone, Eq, PartialEq)]
+        #[repr(u8)]
+        enum TestEnum {
+            #[reflect(rename = "read-only")]
+            ReadOnly,
+            #[reflect(skip)]
+            Internal,
+            #[reflect(rename = "read-write", label = "Read & Write")]
+            ReadWrite,
+        }
+
+        let names = TestEnum::variants()
+            .iter()
+            .map(|variant| variant.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["read-only", "read-write"]);
+        assert_eq!(TestEnum::count(), 2);
+        assert_eq!(TestEnum::variants()[1].meta("label"), Some("Read & Write"));
+        assert_eq!(TestEnum::ReadWrite.name(), "read-write");
+        assert_eq!(TestEnum::Internal.name(), "Internal");
+        assert_eq!(TestEnum::from_name("read-only"), Some(TestEnum::ReadOnly));
+        assert_eq!(TestEnum::from_name("ReadOnly"), None);
+        assert_eq!(TestEnum::from_name("Internal"), None);
+        assert_eq!(TestEnum::ReadWrite.index(), Some(1));
+        assert_eq!(TestEnum::Internal.index(), None);
+        assert_eq!(TestEnum::try_from(2), Ok(TestEnum::ReadWrite));
+        assert_eq!(TestEnum::try_from(1), Err(UnknownDiscriminant(1)));
+    }
+
+    #[test]
+    fn test_enum_all_bits() {
+        #[ReflectEnum]
+        #[derive(Clone, Copy)]
+        #[repr(u8)]
+        enum TestEnum {
+            Read = 0b001,
+            #[reflect(skip)]
+            #[allow(dead_code)]
+            Internal = 0b010,
+            Write = 0b100,
+        }
+
+        assert_eq!(TestEnum::all_bits(), 0b101);
+    }
+
+    #[test]
+    fn test_enum_rename_all() {
+        macro_rules! rename_all {
+            ($rule:literal, $names:expr) => {{
+                #[ReflectEnum(rename_all = $rule)]
+                #[derive(Debug, Clone, Eq, PartialEq)]
+                #[allow(dead_code)]
+                enum TestEnum {
+                    ReadOnly,
+                    Http2Server(u8),
+                    #[reflect(rename = "custom")]
+                    Renamed,
+                }
+
+                let names = TestEnum::variants()
+                    .iter()
+                    .map(|variant| variant.name)
+                    .collect::<Vec<_>>();
+                assert_eq!(names, $names, $rule);
+                assert_eq!(TestEnum::from_name($names[0]), Some(TestEnum::ReadOnly));
+                assert_eq!(TestEnum::Http2Server(0).name(), $names[1]);
+            }};
+        }
+
+        rename_all!("lowercase", ["readonly", "http2server", "custom"]);
+        rename_all!("UPPERCASE", ["READONLY", "HTTP2SERVER", "custom"]);
+        rename_all!("PascalCase", ["ReadOnly", "Http2Server", "custom"]);
+        rename_all!("camelCase", ["readOnly", "http2Server", "custom"]);
+        rename_all!("snake_case", ["read_only", "http2_server", "custom"]);
+        rename_all!(
+            "SCREAMING_SNAKE_CASE",
+            ["READ_ONLY", "HTTP2_SERVER", "custom"]
+        );
+        rename_all!("kebab-case", ["read-only", "http2-server", "custom"]);
+        rename_all!(
+            "SCREAMING-KEBAB-CASE",
+            ["READ-ONLY", "HTTP2-SERVER", "custom"]
+        );
+    }
+
+    #[test]
+    fn test_enum_display() {
+        #[ReflectEnum(display)]
+        #[derive(Debug, Eq, PartialEq)]
+        enum TestEnum {
+            #[reflect(rename = "first")]
+            E1,
+            E2(u32),
+        }
+
+        assert_eq!(TestEnum::E1.to_string(), "first");
+        assert_eq!(TestEnum::E2(3).to_string(), "E2");
+        assert_eq!(format!("{:-<7}", TestEnum::E1), "first--");
+        assert_eq!("first".parse(), Ok(TestEnum::E1));
+        assert_eq!("E2".parse::<TestEnum>(), Err(UnknownVariant));
+        assert_eq!(UnknownVariant.to_string(), "Unknown variant");
+    }
+
+    #[test]
+    fn test_enum_values() {
+        #[ReflectEnum]
+        #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+        enum TestEnum {
+            E1,
+            #[reflect(skip)]
+            Internal,
+            E2,
+            E3,
+        }
+
+        assert_eq!(
+            TestEnum::values().rev().collect::<Vec<_>>(),
+            [TestEnum::E3, TestEnum::E2, TestEnum::E1]
+        );
+        assert_eq!(TestEnum::E1.next_variant(), TestEnum::E2);
+        assert_eq!(TestEnum::E3.next_variant(), TestEnum::E1);
+        assert_eq!(TestEnum::E2.prev_variant(), TestEnum::E1);
+        assert_eq!(TestEnum::E1.prev_variant(), TestEnum::E3);
+        assert_eq!(TestEnum::Internal.next_variant(), TestEnum::E1);
+        assert_eq!(TestEnum::Internal.prev_variant(), TestEnum::E3);
+
+        #[ReflectEnum]
+        #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+        enum TestSingle {
+            E1,
+            E2(u8),
+        }
+
+        assert_eq!(TestSingle::E1.next_variant(), TestSingle::E1);
+        assert_eq!(TestSingle::E2(1).next_variant(), TestSingle::E1);
+        assert_eq!(TestSingle::E2(1).prev_variant(), TestSingle::E1);
+
+        #[ReflectEnum]
+        #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+        enum TestData {
+            E1(u8),
+        }
+
+        assert_eq!(TestData::E1(1).next_variant(), TestData::E1(1));
+        assert_eq!(TestData::values().next(), None);
+    }
+
+    #[cfg(feature = "clap")]
+    #[test]
+    fn test_enum_clap() {
+        use clap::{builder::PossibleValue, Arg, Command, ValueEnum};
+
+        #[ReflectEnum(rename_all = "kebab-case")]
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        enum Format {
+            /// Human readable output.
+            PlainText,
+            Json,
+            #[reflect(skip)]
+            Internal,
+        }
+
+        assert_eq!(Format::value_variants(), [Format::PlainText, Format::Json]);
+        assert_eq!(
+            Format::PlainText.to_possible_value(),
+            Some(PossibleValue::new("plain-text").help("Human readable output."))
+        );
+        assert_eq!(Format::Internal.to_possible_value(), None);
+        assert_eq!(Format::from_str("JSON", true), Ok(Format::Json));
+
+        let command = Command::new("test").arg(
+            Arg::new("format")
+                .long("format")
+                .value_parser(clap::value_parser!(Format)),
+        );
+        let matches = command
+            .clone()
+            .try_get_matches_from(["test", "--format", "plain-text"])
+            .unwrap();
+        assert_eq!(
+            matches.get_one::<Format>("format"),
+            Some(&Format::PlainText)
+        );
+        assert!(command
+            .try_get_matches_from(["test", "--format", "internal"])
+            .is_err());
+    }
+
+    #[test]
+    fn test_enum_discriminant() {
+        const BASE: u16 = 0x100;
+
+        #[ReflectEnum]
+        #[repr(C, u16)]
+        #[allow(dead_code)]
+        enum TestRepr {
+            A,
+            B(u8) = BASE | 1 << 4,
+            #[reflect(skip)]
+            C,
+            D {
+                x: u8,
+            },
+        }
+
+        #[ReflectEnum]
+        #[allow(dead_code)]
+        enum TestNegative {
+            A = -2,
+            B,
+            C,
+        }
+
+        fn discriminants<E: ReflectEnum + 'static>() -> Vec<i128> {
+            E::variants()
+                .iter()
+                .map(|variant| variant.discriminant())
+                .collect()
+        }
+        assert_eq!(discriminants::<TestRepr>(), [0, 0x110, 0x112]);
+        assert_eq!(discriminants::<TestNegative>(), [-2, -1, 0]);
+        assert_eq!(
+            TestNegative::variants()[0].discriminant(),
+            TestNegative::A as i128
+        );
+        assert_eq!(discriminants::<MixedTestEnum>(), [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_enum_consts() {
+        const NAMES: [&str; MixedTestEnum::COUNT] = {
+            let mut names = [""; MixedTestEnum::COUNT];
+            let mut i = 0;
+            while i < MixedTestEnum::COUNT {
+                names[i] = MixedTestEnum::VARIANTS[i].name;
+                i += 1;
+            }
+            names
+        };
+        assert_eq!(NAMES, ["Elem1", "Elem2", "Elem3"]);
+        assert_eq!(MixedTestEnum::COUNT, MixedTestEnum::count());
+        assert_eq!(
+            MixedTestEnum::VARIANTS[2].value,
+            MixedTestEnum::variants()[2].value
+        );
+    }
+
+    #[test]
+    fn test_enum_index() {
+        assert_eq!(MixedTestEnum::Elem1.index(), Some(0));
+        assert_eq!(MixedTestEnum::Elem2(0).index(), Some(1));
+        assert_eq!(MixedTestEnum::Elem3 { a: 0, b: 0 }.index(), Some(2));
+        assert_eq!(
+            MixedTestEnum::from_index(0),
+            Some(&EnumVariant::new("Elem1", Some(MixedTestEnum::Elem1)))
+        );
+        assert_eq!(MixedTestEnum::from_index(2).unwrap().name, "Elem3");
+        assert_eq!(MixedTestEnum::from_index(3), None);
+    }
+
+    #[test]
+    fn test_enum_try_from() {
+        #[ReflectEnum]
+        #[derive(Debug, Eq, PartialEq)]
+        #[repr(i16)]
+        enum TestEnum {
+            E1 = -5,
+            E2 = 7,
+            E3,
+        }
+
+        assert_eq!(TestEnum::try_from(-5), Ok(TestEnum::E1));
+        assert_eq!(TestEnum::try_from(8), Ok(TestEnum::E3));
+        assert_eq!(TestEnum::try_from(0), Err(UnknownDiscriminant(0)));
+        assert_eq!(
+            UnknownDiscriminant(0i16).to_string(),
+            "Unknown discriminant: 0"
+        );
+
+        #[ReflectEnum]
+        #[derive(Debug)]
+        enum TestEmpty {}
+
+        assert!(TestEmpty::try_from(0).is_err());
+    }
+
     #[test]
     fn test_enum_repr() {
         #[ReflectEnum]
@@ -0,0 +1,166 @@
+//! Exports the unit variants of a [`ReflectEnum`] as a JSON Schema or a TypeScript union type, e.g. to keep
+//! the front-end in sync with the enums of an API.
+//!
+//! The unit variants are described by their reflected names, which is how serde represents them by default.
+//! Variants with fields and the variants skipped by `#[reflect(skip)]` are not exported.
+//!
+//! # Example
+//! ```
+//! use adar::prelude::*;
+//!
+//! #[ReflectEnum(rename_all = "lowercase")]
+//! enum Status {
+//!     Active,
+//!     /// Can be restored within 30 days.
+//!     Deleted,
+//! }
+//!
+//! assert_eq!(schema::typescript::<Status>(), r#""active" | "deleted""#);
+//! assert_eq!(
+//!     schema::json_schema::<Status>(),
+//!     r#"{"oneOf":[{"const":"active"},{"const":"deleted","description":"Can be restored within 30 days."}]}"#
+//! );
+//! ```
+
+use crate::prelude::ReflectEnum;
+use alloc::string::String;
+use core::fmt::Write;
+
+/// Creates a JSON Schema of the names of the unit variants.
+///
+/// The schema is `{"type":"string","enum":[...]}`, or a `oneOf` of `const` values when any of the variants
+/// has a doc comment, which is exported as `description`.
+pub fn json_schema<E>() -> String
+where
+    E: ReflectEnum + 'static,
+{
+    let mut schema = String::new();
+    let mut variants = E::variants()
+        .iter()
+        .filter(|variant| variant.value.is_some());
+    if variants.clone().any(|variant| variant.doc().is_some()) {
+        schema.push_str(r#"{"oneOf":["#);
+        for (i, variant) in variants.enumerate() {
+            if i != 0 {
+                schema.push(',');
+            }
+            schema.push_str(r#"{"const":"#);
+            push_string_literal(&mut schema, variant.name);
+            if let Some(doc) = variant.doc() {
+                schema.push_str(r#","description":"#);
+                push_string_literal(&mut schema, doc);
+            }
+            schema.push('}');
+        }
+    } else {
+        schema.push_str(r#"{"type":"string","enum":["#);
+        if let Some(first) = variants.next() {
+            push_string_literal(&mut schema, first.name);
+        }
+        for variant in variants {
+            schema.push(',');
+            push_string_literal(&mut schema, variant.name);
+        }
+    }
+    schema.push_str("]}");
+    schema
+}
+
+/// Creates a TypeScript union type of the names of the unit variants (e.g. `"A" | "B"`), or `never`
+/// if there are none.
+pub fn typescript<E>() -> String
+where
+    E: ReflectEnum + 'static,
+{
+    let mut union = String::new();
+    for variant in E::variants()
+        .iter()
+        .filter(|variant| variant.value.is_some())
+    {
+        if !union.is_empty() {
+            union.push_str(" | ");
+        }
+        push_string_literal(&mut union, variant.name);
+    }
+    if union.is_empty() {
+        union.push_str("never");
+    }
+    union
+}
+
+/// Appends a quoted and escaped string literal, which is valid both in JSON and TypeScript.
+fn push_string_literal(output: &mut String, value: &str) {
+    output.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(output, "\\u{:04x}", c as u32);
+            }
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{self as adar, prelude::*};
+
+    #[ReflectEnum]
+    #[allow(dead_code)]
+    enum TestPlain {
+        A,
+        B(u8),
+        #[reflect(rename = "c\"quoted\"")]
+        C,
+        #[reflect(skip)]
+        D,
+    }
+
+    #[ReflectEnum]
+    #[allow(dead_code)]
+    enum TestDoc {
+        /// First line.
+        /// Second "line".
+        A,
+        B,
+    }
+
+    #[ReflectEnum]
+    #[allow(dead_code)]
+    enum TestEmpty {
+        A(u8),
+    }
+
+    #[test]
+    fn test_json_schema() {
+        assert_eq!(
+            json_schema::<TestPlain>(),
+            r#"{"type":"string","enum":["A","c\"quoted\""]}"#
+        );
+        assert_eq!(
+            json_schema::<TestDoc>(),
+            r#"{"oneOf":[{"const":"A","description":"First line.\nSecond \"line\"."},{"const":"B"}]}"#
+        );
+        assert_eq!(json_schema::<TestEmpty>(), r#"{"type":"string","enum":[]}"#);
+
+        let schema: serde_json::Value = serde_json::from_str(&json_schema::<TestDoc>()).unwrap();
+        assert_eq!(
+            schema["oneOf"][0]["description"],
+            "First line.\nSecond \"line\"."
+        );
+    }
+
+    #[test]
+    fn test_typescript() {
+        assert_eq!(typescript::<TestPlain>(), r#""A" | "c\"quoted\"""#);
+        assert_eq!(typescript::<TestDoc>(), r#""A" | "B""#);
+        assert_eq!(typescript::<TestEmpty>(), "never");
+    }
+}
@@ -0,0 +1,179 @@
+//! [`Single`] is a flag container which holds exactly one flag.
+
+use crate::prelude::{FlagTypeConstraints, Flags, ReflectEnum};
+
+/// Flag container which guarantees that exactly one flag is set.
+/// The associated enum must be annotated with [`crate::macros::FlagEnum`] derive macro.
+///
+/// [`Single`] is backed by the enum value itself, so `Option<Single<E>>` has the same size as `E`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct Single<E>(E);
+
+impl<E> Single<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints,
+{
+    /// Creates a new [`Single`] from a flag.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// #[derive(Debug, Eq, PartialEq)]
+    /// enum MyFlags {A, B, C}
+    ///
+    /// assert_eq!(Single::new(MyFlags::B).get(), MyFlags::B);
+    /// ```
+    ///
+    /// # Returns
+    /// [`Single`] holding the specified flag.
+    #[inline(always)]
+    pub fn new(value: E) -> Self {
+        Self(value)
+    }
+
+    /// Gets the flag held by `self`.
+    ///
+    /// # Returns
+    /// The flag.
+    #[inline(always)]
+    pub fn get(self) -> E {
+        self.0
+    }
+
+    /// Converts `self` into [`Flags`].
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// #[derive(Debug)]
+    /// enum MyFlags {A, B, C}
+    ///
+    /// assert_eq!(Single::new(MyFlags::C).into_flags(), MyFlags::C);
+    /// ```
+    ///
+    /// # Returns
+    /// [`Flags`] with only the held flag set.
+    #[inline(always)]
+    pub fn into_flags(self) -> Flags<E> {
+        Flags::single(self.0)
+    }
+}
+
+impl<E> From<E> for Single<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints,
+{
+    #[inline(always)]
+    fn from(value: E) -> Self {
+        Self(value)
+    }
+}
+
+impl<E> From<Single<E>> for Flags<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints,
+{
+    #[inline(always)]
+    fn from(value: Single<E>) -> Self {
+        value.into_flags()
+    }
+}
+
+impl<E> TryFrom<Flags<E>> for Single<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints,
+{
+    type Error = Flags<E>;
+
+    /// Tries to create a [`Single`] from [`Flags`]. Only the known flags are counted, the unknown bits kept by
+    /// [`Flags::from_raw_retain()`] are ignored and dropped, like in [`Flags::as_single()`].
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[FlagEnum]
+    /// #[derive(Debug, Eq, PartialEq)]
+    /// enum MyFlags {A, B, C}
+    ///
+    /// assert_eq!(Single::try_from(Flags::from(MyFlags::A)), Ok(Single::new(MyFlags::A)));
+    /// assert!(Single::try_from(MyFlags::A | MyFlags::B).is_err());
+    /// assert!(Single::try_from(Flags::<MyFlags>::empty()).is_err());
+    /// ```
+    ///
+    /// # Returns
+    /// `Ok` - [`Single`] if exactly one flag is set \
+    /// `Err` - The original [`Flags`] otherwise
+    fn try_from(flags: Flags<E>) -> Result<Self, Self::Error> {
+        flags.as_single().map(Self).ok_or(flags)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate as adar;
+    use crate::prelude::*;
+
+    #[derive(Debug, Eq, PartialEq)]
+    #[FlagEnum]
+    enum TestFlags {
+        F1,
+        F2,
+        F3,
+    }
+
+    #[derive(Debug, Eq, PartialEq)]
+    #[FlagEnum]
+    #[repr(u8)]
+    enum TestFlagsU8 {
+        F1,
+    }
+
+    #[test]
+    fn test_single_try_from() {
+        assert_eq!(
+            Single::try_from(Flags::from(TestFlags::F2)),
+            Ok(Single::new(TestFlags::F2))
+        );
+        assert_eq!(
+            Single::try_from(TestFlags::F1 | TestFlags::F3),
+            Err(TestFlags::F1 | TestFlags::F3)
+        );
+        assert!(Single::try_from(Flags::<TestFlags>::empty()).is_err());
+
+        let unknown = Flags::<TestFlags>::from_raw_retain(0b1000);
+        assert_eq!(Single::try_from(unknown), Err(unknown));
+        assert_eq!(
+            Single::try_from(unknown | TestFlags::F3),
+            Ok(Single::new(TestFlags::F3))
+        );
+        let mixed = Flags::<TestFlags>::from_raw_retain(0b1011);
+        assert_eq!(Single::try_from(mixed), Err(mixed));
+    }
+
+    #[test]
+    fn test_single_into_flags() {
+        let flags: Flags<TestFlags> = Single::new(TestFlags::F3).into();
+        assert_eq!(flags, TestFlags::F3);
+        assert_eq!(flags.len(), 1);
+    }
+
+    #[test]
+    fn test_single_niche() {
+        assert_eq!(size_of::<Single<TestFlags>>(), size_of::<TestFlags>());
+        assert_eq!(
+            size_of::<Option<Single<TestFlags>>>(),
+            size_of::<TestFlags>()
+        );
+        assert_eq!(size_of::<Option<Single<TestFlagsU8>>>(), 1);
+    }
+}
@@ -0,0 +1,244 @@
+use crate::prelude::{
+    FlagTypeConstraints, Flags, InspectError, InspectKind, InspectNode, InspectOption, ReflectEnum,
+};
+
+/// Converts values into an [`InspectNode`] tree and applies modified trees back.
+/// Implemented for [`ReflectEnum`] enums, [`Flags`] and tuples of inspectable values.
+pub trait Inspect {
+    /// Creates the tree model of `self`.
+    fn inspect(&self) -> InspectNode;
+
+    /// Applies the selected options of the node to `self`.
+    /// On error `self` might be partially modified (e.g. the first elements of a tuple).
+    fn apply(&mut self, node: &InspectNode) -> Result<(), InspectError>;
+}
+
+fn type_name<T>() -> String {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name).to_string()
+}
+
+fn find_variant<E>(name: &'static str) -> Result<E, InspectError>
+where
+    E: ReflectEnum + Copy + 'static,
+{
+    let variant = E::variants()
+        .iter()
+        .find(|variant| variant.name == name)
+        .ok_or(InspectError::UnknownOption(name))?;
+    variant.value.ok_or(InspectError::NotEditable(name))
+}
+
+impl<E> Inspect for E
+where
+    E: ReflectEnum + Copy + 'static,
+{
+    fn inspect(&self) -> InspectNode {
+        let current = self.name();
+        InspectNode {
+            name: type_name::<E>(),
+            kind: InspectKind::Enum,
+            options: E::variants()
+                .iter()
+                .map(|variant| InspectOption {
+                    name: variant.name,
+                    selected: variant.name == current,
+                    editable: variant.value.is_some(),
+                })
+                .collect(),
+            children: vec![],
+        }
+    }
+
+    fn apply(&mut self, node: &InspectNode) -> Result<(), InspectError> {
+        if node.kind != InspectKind::Enum {
+            return Err(InspectError::Mismatch);
+        }
+        let mut selected = node.selected();
+        let (Some(name), None) = (selected.next(), selected.next()) else {
+            return Err(InspectError::InvalidSelection);
+        };
+        if name != self.name() {
+            *self = find_variant(name)?;
+        }
+        Ok(())
+    }
+}
+
+impl<E> Inspect for Flags<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints,
+{
+    fn inspect(&self) -> InspectNode {
+        InspectNode {
+            name: type_name::<E>(),
+            kind: InspectKind::Flags,
+            options: E::variants()
+                .iter()
+                .filter_map(|variant| {
+                    variant.value.map(|value| InspectOption {
+                        name: variant.name,
+                        selected: self.any(value),
+                        editable: true,
+                    })
+                })
+                .collect(),
+            children: vec![],
+        }
+    }
+
+    fn apply(&mut self, node: &InspectNode) -> Result<(), InspectError> {
+        if node.kind != InspectKind::Flags {
+            return Err(InspectError::Mismatch);
+        }
+        *self = node
+            .selected()
+            .map(find_variant::<E>)
+            .collect::<Result<Flags<E>, _>>()?;
+        Ok(())
+    }
+}
+
+macro_rules! impl_inspect_tuple {
+    ($($T:ident),*) => {
+        impl<$($T),*> Inspect for ($($T,)*)
+        where
+            $($T: Inspect,)*
+        {
+            #[allow(non_snake_case)]
+            fn inspect(&self) -> InspectNode {
+                let ($($T,)*) = self;
+                InspectNode {
+                    name: String::new(),
+                    kind: InspectKind::Group,
+                    options: vec![],
+                    children: vec![$($T.inspect()),*],
+                }
+            }
+
+            #[allow(non_snake_case, unused_mut, unused_variables)]
+            fn apply(&mut self, node: &InspectNode) -> Result<(), InspectError> {
+                let ($($T,)*) = self;
+                let mut children = node.children.iter();
+                if node.kind != InspectKind::Group || children.len() != count_tuple_elems!($($T),*) {
+                    return Err(InspectError::Mismatch);
+                }
+                $($T.apply(children.next().unwrap())?;)*
+                Ok(())
+            }
+        }
+    };
+}
+
+macro_rules! count_tuple_elems {
+    () => { 0 };
+    ($head:ident) => { 1 };
+    ($head:ident, $($tail:ident),+) => { 1 + count_tuple_elems!($($tail),+) };
+}
+
+impl_inspect_tuple!();
+impl_inspect_tuple!(A);
+impl_inspect_tuple!(A, B);
+impl_inspect_tuple!(A, B, C);
+impl_inspect_tuple!(A, B, C, D);
+impl_inspect_tuple!(A, B, C, D, E);
+impl_inspect_tuple!(A, B, C, D, E, F);
+impl_inspect_tuple!(A, B, C, D, E, F, G);
+impl_inspect_tuple!(A, B, C, D, E, F, G, H);
+impl_inspect_tuple!(A, B, C, D, E, F, G, H, I);
+impl_inspect_tuple!(A, B, C, D, E, F, G, H, I, J);
+impl_inspect_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+impl_inspect_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+impl_inspect_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M);
+impl_inspect_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N);
+impl_inspect_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O);
+impl_inspect_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P);
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+
+    #[ReflectEnum]
+    #[derive(Debug, Eq, PartialEq, Clone, Copy)]
+    enum Quality {
+        Low,
+        High,
+        Custom(u8),
+    }
+
+    #[FlagEnum]
+    #[derive(Debug)]
+    enum Options {
+        VSync,
+        Fullscreen,
+        Shadows,
+    }
+
+    #[test]
+    fn test_inspect_enum() {
+        let mut quality = Quality::Low;
+        let mut node = quality.inspect();
+        assert_eq!(node.name, "Quality");
+        assert_eq!(node.kind, InspectKind::Enum);
+        assert_eq!(node.selected().collect::<Vec<_>>(), ["Low"]);
+        assert!(!node.options[2].editable);
+
+        assert!(node.select("High"));
+        assert!(!node.select("Ultra"));
+        assert_eq!(quality.apply(&node), Ok(()));
+        assert_eq!(quality, Quality::High);
+
+        assert!(node.select("Custom"));
+        assert_eq!(
+            quality.apply(&node),
+            Err(InspectError::NotEditable("Custom"))
+        );
+        assert_eq!(quality, Quality::High);
+    }
+
+    #[test]
+    fn test_inspect_custom_variant() {
+        let mut quality = Quality::Custom(5);
+        let node = quality.inspect();
+        assert_eq!(node.selected().collect::<Vec<_>>(), ["Custom"]);
+        assert_eq!(quality.apply(&node), Ok(()));
+        assert_eq!(quality, Quality::Custom(5));
+    }
+
+    #[test]
+    fn test_inspect_flags() {
+        let mut options = Options::VSync | Options::Shadows;
+        let mut node = options.inspect();
+        assert_eq!(node.name, "Options");
+        assert_eq!(node.kind, InspectKind::Flags);
+        assert_eq!(node.selected().collect::<Vec<_>>(), ["VSync", "Shadows"]);
+
+        assert!(node.toggle("VSync"));
+        assert!(node.toggle("Fullscreen"));
+        assert!(!node.select("VSync"));
+        assert_eq!(options.apply(&node), Ok(()));
+        assert_eq!(options, Options::Fullscreen | Options::Shadows);
+    }
+
+    #[test]
+    fn test_inspect_tuple() {
+        let mut settings = (Quality::Low, Flags::from(Options::VSync));
+        let mut node = settings.inspect();
+        assert_eq!(node.kind, InspectKind::Group);
+        assert_eq!(node.children.len(), 2);
+
+        node.children[0].select("High");
+        node.children[1].toggle("Shadows");
+        assert_eq!(settings.apply(&node), Ok(()));
+        assert_eq!(settings.0, Quality::High);
+        assert_eq!(settings.1, Options::VSync | Options::Shadows);
+
+        node.children.pop();
+        assert_eq!(settings.apply(&node), Err(InspectError::Mismatch));
+        assert_eq!(
+            Quality::Low.apply(&Flags::from(Options::VSync).inspect()),
+            Err(InspectError::Mismatch)
+        );
+    }
+}
@@ -0,0 +1,5 @@
+mod inspectable;
+mod node;
+
+pub use inspectable::*;
+pub use node::*;
@@ -0,0 +1,101 @@
+//! [`InspectNode`] is a generic tree model of reflected values.
+
+use std::fmt::Display;
+
+/// Kind of an [`InspectNode`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum InspectKind {
+    /// Exactly one option is selected. Created from [`crate::enums::ReflectEnum`] values.
+    Enum,
+    /// Any number of options are selected. Created from [`crate::enums::Flags`] values.
+    Flags,
+    /// Has no options, only children. Created from tuples.
+    Group,
+}
+
+/// Possible value of an [`InspectNode`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct InspectOption {
+    pub name: &'static str,
+    pub selected: bool,
+    /// `false` if the option can not be applied back (e.g. enum variants with fields).
+    pub editable: bool,
+}
+
+/// Node of the tree model created by [`crate::inspect::Inspect::inspect()`].
+/// Editors can modify the selected options and apply the node back to the value with [`crate::inspect::Inspect::apply()`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct InspectNode {
+    /// Name of the inspected type. Groups are unnamed.
+    pub name: String,
+    pub kind: InspectKind,
+    pub options: Vec<InspectOption>,
+    pub children: Vec<InspectNode>,
+}
+
+impl InspectNode {
+    /// Acquires an iterator over the names of the selected options.
+    pub fn selected(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.options
+            .iter()
+            .filter(|option| option.selected)
+            .map(|option| option.name)
+    }
+
+    /// Selects the option and deselects every other option of an [`InspectKind::Enum`] node.
+    ///
+    /// # Returns
+    /// `false` if the node is not an enum or the option does not exist.
+    pub fn select(&mut self, name: &str) -> bool {
+        if self.kind != InspectKind::Enum || !self.options.iter().any(|o| o.name == name) {
+            return false;
+        }
+        for option in &mut self.options {
+            option.selected = option.name == name;
+        }
+        true
+    }
+
+    /// Toggles the option of an [`InspectKind::Flags`] node.
+    ///
+    /// # Returns
+    /// `false` if the node is not a flags node or the option does not exist.
+    pub fn toggle(&mut self, name: &str) -> bool {
+        if self.kind != InspectKind::Flags {
+            return false;
+        }
+        match self.options.iter_mut().find(|option| option.name == name) {
+            Some(option) => {
+                option.selected = !option.selected;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Error returned by [`crate::inspect::Inspect::apply()`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum InspectError {
+    /// The node's kind or number of children does not match the value.
+    Mismatch,
+    /// Enum node without exactly one selected option.
+    InvalidSelection,
+    /// The option does not exist in the value.
+    UnknownOption(&'static str),
+    /// The option exists but can not be applied back.
+    NotEditable(&'static str),
+}
+
+impl Display for InspectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Mismatch => write!(f, "Node does not match the value"),
+            Self::InvalidSelection => write!(f, "Exactly one option must be selected"),
+            Self::UnknownOption(name) => write!(f, "Unknown option: {}", name),
+            Self::NotEditable(name) => write!(f, "Option is not editable: {}", name),
+        }
+    }
+}
+
+impl std::error::Error for InspectError {}
@@ -1,13 +1,36 @@
-#![doc = include_str!("../README.md")]
+#![cfg_attr(feature = "std", doc = include_str!("../README.md"))]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod inspect;
+#[cfg(feature = "std")]
 pub mod state_machine;
+#[cfg(feature = "std")]
 pub mod tuples;
 pub use adar_macros as macros;
+#[cfg(feature = "clap")]
+#[doc(hidden)]
+pub use clap;
+#[cfg(feature = "rkyv")]
+#[doc(hidden)]
+pub use rkyv;
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde;
 pub mod enums;
+pub mod structs;
 
 pub mod prelude {
     pub use crate::enums::*;
+    #[cfg(feature = "std")]
+    pub use crate::inspect::*;
     pub use crate::macros::*;
+    #[cfg(feature = "std")]
     pub use crate::state_machine::*;
+    pub use crate::structs::*;
+    #[cfg(feature = "std")]
     pub use crate::tuples::*;
 }
@@ -0,0 +1,244 @@
+use crate::state_machine::{Machine, State, StateMachine, StateTypes};
+
+type UpdateHook<S, C> = Box<dyn FnMut(&S, &mut C) + Send + Sync>;
+type TransitionHook<S, C> = Box<dyn FnMut(&S, &S, &mut C) + Send + Sync>;
+
+/// Closures attached to a [`StateMachine`] by [`StateMachineBuilder`].
+pub(crate) struct Middleware<S, C> {
+    before_update: Vec<UpdateHook<S, C>>,
+    after_update: Vec<UpdateHook<S, C>>,
+    before_transition: Vec<TransitionHook<S, C>>,
+    after_transition: Vec<UpdateHook<S, C>>,
+}
+
+impl<S, C> Default for Middleware<S, C> {
+    fn default() -> Self {
+        Self {
+            before_update: Vec::new(),
+            after_update: Vec::new(),
+            before_transition: Vec::new(),
+            after_transition: Vec::new(),
+        }
+    }
+}
+
+impl<S, C> Middleware<S, C> {
+    pub(crate) fn before_update(&mut self, state: &S, context: &mut C) {
+        self.before_update
+            .iter_mut()
+            .for_each(|f| f(state, context));
+    }
+
+    pub(crate) fn after_update(&mut self, state: &S, context: &mut C) {
+        self.after_update.iter_mut().for_each(|f| f(state, context));
+    }
+
+    pub(crate) fn before_transition(&mut self, old_state: &S, new_state: &S, context: &mut C) {
+        self.before_transition
+            .iter_mut()
+            .for_each(|f| f(old_state, new_state, context));
+    }
+
+    pub(crate) fn after_transition(&mut self, state: &S, context: &mut C) {
+        self.after_transition
+            .iter_mut()
+            .for_each(|f| f(state, context));
+    }
+}
+
+/// Creates a [`StateMachine`] with middleware closures, which are called around updates and transitions.
+/// Useful for logging, metrics and tracing without implementing [`Machine`] callbacks for every enum.
+/// Multiple closures can be attached to the same hook, they are called in the order they were added.
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+/// use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+///
+/// #[StateEnum]
+/// #[ReflectEnum]
+/// enum Door {
+///     Opened,
+///     Closed,
+/// }
+/// impl Machine for Door {}
+/// impl State for Opened {}
+/// impl State for Closed {}
+///
+/// let updates = Arc::new(AtomicUsize::new(0));
+/// let mut sm = StateMachineBuilder::new(Opened)
+///     .before_update({
+///         let updates = updates.clone();
+///         move |_state, _context| {
+///             updates.fetch_add(1, Ordering::Relaxed);
+///         }
+///     })
+///     .before_transition(|old_state: &Door, new_state, _context| {
+///         println!("{} -> {}", old_state.name(), new_state.name())
+///     })
+///     .build();
+/// sm.update();
+/// sm.transition(Closed);
+/// assert_eq!(updates.load(Ordering::Relaxed), 1);
+/// ```
+pub struct StateMachineBuilder<
+    S,
+    P1 = (),
+    P2 = (),
+    P3 = (),
+    P4 = (),
+    P5 = (),
+    P6 = (),
+    P7 = (),
+    P8 = (),
+> where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>,
+{
+    state: S,
+    context: S::Context,
+    middleware: Middleware<S, S::Context>,
+    #[allow(clippy::type_complexity)]
+    phantom: std::marker::PhantomData<(P1, P2, P3, P4, P5, P6, P7, P8)>,
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> StateMachineBuilder<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>,
+{
+    /// Creates a builder of a machine which starts in `state` with `context`. See [`StateMachine::new_context()`].
+    pub fn new_context(state: impl Into<S>, context: S::Context) -> Self {
+        Self {
+            state: state.into(),
+            context,
+            middleware: Middleware::default(),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Adds a closure which is called with the current state before each update.
+    pub fn before_update<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&S, &mut S::Context) + Send + Sync + 'static,
+    {
+        self.middleware.before_update.push(Box::new(f));
+        self
+    }
+
+    /// Adds a closure which is called with the current state after each update, before the requested transition.
+    pub fn after_update<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&S, &mut S::Context) + Send + Sync + 'static,
+    {
+        self.middleware.after_update.push(Box::new(f));
+        self
+    }
+
+    /// Adds a closure which is called with the old and the new state before each transition (including pushes and pops),
+    /// before the old state is left.
+    pub fn before_transition<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&S, &S, &mut S::Context) + Send + Sync + 'static,
+    {
+        self.middleware.before_transition.push(Box::new(f));
+        self
+    }
+
+    /// Adds a closure which is called with the new state after each transition (including pushes and pops),
+    /// after the new state is entered.
+    pub fn after_transition<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&S, &mut S::Context) + Send + Sync + 'static,
+    {
+        self.middleware.after_transition.push(Box::new(f));
+        self
+    }
+
+    /// Creates the machine and enters the initial state.
+    pub fn build(self) -> StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8> {
+        StateMachine::with_middleware(self.state, self.context, Some(Box::new(self.middleware)))
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> StateMachineBuilder<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>,
+    S::Context: Default,
+{
+    /// Creates a builder of a machine which starts in `state` with the default context. See [`StateMachine::new()`].
+    pub fn new(state: impl Into<S>) -> Self {
+        Self::new_context(state, Default::default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+
+    #[StateEnum(context = Vec<String>)]
+    #[ReflectEnum]
+    enum TestBuilder {
+        Idle,
+        Active,
+        Overlay,
+    }
+    impl Machine for TestBuilder {}
+    impl State for Idle {
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            context.push("update".into());
+            Some(Active.into())
+        }
+
+        fn on_leave(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            context.push("leave".into());
+        }
+    }
+    impl State for Active {
+        fn on_enter(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            context.push("enter".into());
+        }
+    }
+    impl State for Overlay {}
+
+    #[test]
+    fn test_builder_middleware() {
+        let mut sm = StateMachineBuilder::new(Idle)
+            .before_update(|state: &TestBuilder, context| {
+                context.push(format!("before update {}", state.name()))
+            })
+            .after_update(|state, context| context.push(format!("after update {}", state.name())))
+            .before_transition(|old_state, new_state, context| {
+                context.push(format!("{} -> {}", old_state.name(), new_state.name()))
+            })
+            .after_transition(|state, context| context.push(format!("in {}", state.name())))
+            .build();
+        sm.update();
+        sm.push(Overlay);
+        sm.pop();
+        assert_eq!(
+            sm.context(),
+            &[
+                "before update Idle",
+                "update",
+                "after update Idle",
+                "Idle -> Active",
+                "leave",
+                "enter",
+                "in Active",
+                "Active -> Overlay",
+                "in Overlay",
+                "Overlay -> Active",
+                "in Active",
+            ]
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    thread::{self, Thread},
+    time::Duration,
+};
+
+/// Aborts [`crate::state_machine::StateMachine::run_cancellable_args()`] from another thread.
+///
+/// The clones share the same flag. Checking the flag is lock-free, cancelling also wakes up a machine
+/// which is waiting for a timeout.
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+///
+/// let token = CancelToken::new();
+/// let token2 = token.clone();
+/// std::thread::spawn(move || token2.cancel()).join().unwrap();
+/// assert!(token.is_cancelled());
+/// ```
+#[derive(Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    waiters: Mutex<Vec<Thread>>,
+}
+
+impl CancelToken {
+    /// Creates a token which is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation and wakes up the machines waiting for a timeout.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::Release);
+        for waiter in self
+            .inner
+            .waiters
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .drain(..)
+        {
+            waiter.unpark();
+        }
+    }
+
+    /// Returns `true` if the token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Clears the cancellation, so the token can be reused.
+    pub fn reset(&self) {
+        self.inner.cancelled.store(false, Ordering::Release);
+    }
+
+    /// Blocks the current thread for `duration` or until the token is cancelled. Might return early.
+    pub(crate) fn wait(&self, duration: Duration) {
+        self.inner
+            .waiters
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(thread::current());
+        if !self.is_cancelled() {
+            thread::park_timeout(duration);
+        }
+        let current = thread::current().id();
+        self.inner
+            .waiters
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|waiter| waiter.id() != current);
+    }
+}
+
+impl std::fmt::Debug for CancelToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CancelToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
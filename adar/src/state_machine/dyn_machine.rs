@@ -0,0 +1,268 @@
+use std::any::Any;
+
+/// State of a [`DynStateMachine`]. Unlike [`crate::state_machine::State`] the states don't belong to a generated enum,
+/// so new states can be contributed at runtime (e.g. by plugins).
+#[allow(unused_variables)]
+pub trait DynState<Args = (), Context = ()>: Any {
+    /// Called when the state becomes the current state.
+    #[inline(always)]
+    fn on_enter(&mut self, args: Option<&mut Args>, context: &mut Context) {}
+
+    /// Called at each update. Returns the next state to transition to.
+    #[inline(always)]
+    fn on_update(
+        &mut self,
+        args: Option<&mut Args>,
+        context: &mut Context,
+    ) -> Option<BoxedState<Args, Context>> {
+        None
+    }
+
+    /// Called when the state is left.
+    #[inline(always)]
+    fn on_leave(&mut self, args: Option<&mut Args>, context: &mut Context) {}
+
+    /// Returns the name of the state used in diagnostics.
+    #[inline(always)]
+    fn state_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+}
+
+/// Boxed [`DynState`] stored by a [`DynStateMachine`] and returned as a transition target.
+pub type BoxedState<Args = (), Context = ()> = Box<dyn DynState<Args, Context>>;
+
+/// State machine which stores its current state as a [`BoxedState`] instead of a generated enum.
+/// It trades the static dispatch of [`crate::state_machine::StateMachine`] for the ability to add states at runtime.
+///
+/// The current state is left when the machine is dropped (requires `leave-on-drop` feature, skipped while panicking).
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+///
+/// struct Idle;
+/// impl DynState<(), Vec<&'static str>> for Idle {
+///     fn on_update(&mut self, _args: Option<&mut ()>, _context: &mut Vec<&'static str>) -> Option<BoxedState<(), Vec<&'static str>>> {
+///         Some(Box::new(Plugin))
+///     }
+/// }
+///
+/// // Contributed by a plugin
+/// struct Plugin;
+/// impl DynState<(), Vec<&'static str>> for Plugin {
+///     fn on_enter(&mut self, _args: Option<&mut ()>, context: &mut Vec<&'static str>) {
+///         context.push("plugin");
+///     }
+/// }
+///
+/// let mut sm = DynStateMachine::new(Idle);
+/// sm.update();
+/// assert!(sm.is_in::<Plugin>());
+/// assert_eq!(sm.context(), &["plugin"]);
+/// ```
+pub struct DynStateMachine<Args = (), Context = ()>
+where
+    Args: 'static,
+    Context: 'static,
+{
+    state: BoxedState<Args, Context>,
+    context: Context,
+}
+
+impl<Args, Context> DynStateMachine<Args, Context>
+where
+    Args: 'static,
+    Context: 'static,
+{
+    /// Creates a new machine with a context and enters the state.
+    pub fn new_context(state: impl DynState<Args, Context>, context: Context) -> Self {
+        Self::new_boxed(Box::new(state), context)
+    }
+
+    /// Creates a new machine with a context and enters the boxed state.
+    pub fn new_boxed(state: BoxedState<Args, Context>, context: Context) -> Self {
+        let mut this = Self { state, context };
+        this.state.on_enter(None, &mut this.context);
+        this
+    }
+
+    /// Updates the current state and transitions to the state it returns.
+    pub fn update_args(&mut self, args: &mut Args) {
+        if let Some(new_state) = self.state.on_update(Some(args), &mut self.context) {
+            self.transition_boxed(new_state, Some(args));
+        }
+    }
+
+    /// Updates the machine until the current state doesn't request a transition.
+    pub fn run_args(&mut self, args: &mut Args) {
+        while let Some(new_state) = self.state.on_update(Some(args), &mut self.context) {
+            self.transition_boxed(new_state, Some(args));
+        }
+    }
+
+    /// Leaves the current state and enters the new one.
+    pub fn transition(&mut self, new_state: impl DynState<Args, Context>) {
+        self.transition_boxed(Box::new(new_state), None);
+    }
+
+    /// Leaves the current state and enters the new boxed one.
+    pub fn transition_boxed(
+        &mut self,
+        new_state: BoxedState<Args, Context>,
+        mut args: Option<&mut Args>,
+    ) {
+        self.state.on_leave(args.as_deref_mut(), &mut self.context);
+        self.state = new_state;
+        self.state.on_enter(args, &mut self.context);
+    }
+
+    pub fn state(&self) -> &dyn DynState<Args, Context> {
+        &*self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut dyn DynState<Args, Context> {
+        &mut *self.state
+    }
+
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    pub fn context_mut(&mut self) -> &mut Context {
+        &mut self.context
+    }
+
+    /// Returns `true` if the current state is `T`.
+    pub fn is_in<T: DynState<Args, Context>>(&self) -> bool {
+        self.state_as::<T>().is_some()
+    }
+
+    /// Gets the current state as `T`.
+    ///
+    /// # Returns
+    /// `Some` - The current state if it's `T` \
+    /// `None` - Otherwise
+    pub fn state_as<T: DynState<Args, Context>>(&self) -> Option<&T> {
+        (&*self.state as &dyn Any).downcast_ref()
+    }
+
+    /// Gets the current state as mutable `T`. See [`DynStateMachine::state_as()`].
+    pub fn state_as_mut<T: DynState<Args, Context>>(&mut self) -> Option<&mut T> {
+        (&mut *self.state as &mut dyn Any).downcast_mut()
+    }
+}
+
+impl<Args, Context> DynStateMachine<Args, Context>
+where
+    Args: 'static,
+    Context: Default + 'static,
+{
+    /// Creates a new machine with the default context and enters the state.
+    pub fn new(state: impl DynState<Args, Context>) -> Self {
+        Self::new_context(state, Context::default())
+    }
+}
+
+impl<Context> DynStateMachine<(), Context>
+where
+    Context: 'static,
+{
+    pub fn update(&mut self) {
+        self.update_args(&mut ());
+    }
+
+    pub fn run(&mut self) {
+        self.run_args(&mut ());
+    }
+}
+
+impl<Args, Context> std::fmt::Debug for DynStateMachine<Args, Context>
+where
+    Args: 'static,
+    Context: 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("DynStateMachine")
+            .field("state", &self.state.state_name())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Args, Context> Drop for DynStateMachine<Args, Context>
+where
+    Args: 'static,
+    Context: 'static,
+{
+    fn drop(&mut self) {
+        if cfg!(feature = "leave-on-drop") && !std::thread::panicking() {
+            self.state.on_leave(None, &mut self.context);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    type Log = Vec<String>;
+
+    struct Countdown(u32);
+    impl DynState<u32, Log> for Countdown {
+        fn on_enter(&mut self, _args: Option<&mut u32>, context: &mut Log) {
+            context.push(format!("enter {}", self.0));
+        }
+
+        fn on_update(
+            &mut self,
+            args: Option<&mut u32>,
+            _context: &mut Log,
+        ) -> Option<BoxedState<u32, Log>> {
+            let step = args.map_or(1, |args| *args);
+            match self.0.checked_sub(step) {
+                Some(0) | None => Some(Box::new(Launched)),
+                Some(value) => Some(Box::new(Countdown(value))),
+            }
+        }
+
+        fn on_leave(&mut self, _args: Option<&mut u32>, context: &mut Log) {
+            context.push(format!("leave {}", self.0));
+        }
+    }
+
+    struct Launched;
+    impl DynState<u32, Log> for Launched {
+        fn on_enter(&mut self, _args: Option<&mut u32>, context: &mut Log) {
+            context.push("launched".into());
+        }
+
+        fn state_name(&self) -> &'static str {
+            "Launched"
+        }
+    }
+
+    #[test]
+    fn test_dyn_state_machine() {
+        let mut sm = DynStateMachine::new(Countdown(3));
+        sm.update_args(&mut 1);
+        assert_eq!(sm.state_as::<Countdown>().map(|state| state.0), Some(2));
+        sm.state_as_mut::<Countdown>().unwrap().0 = 5;
+        sm.run_args(&mut 2);
+        assert!(sm.is_in::<Launched>());
+        assert_eq!(sm.state().state_name(), "Launched");
+        assert_eq!(
+            format!("{:?}", sm),
+            "DynStateMachine { state: \"Launched\", .. }"
+        );
+        assert_eq!(
+            sm.context(),
+            &[
+                "enter 3", "leave 3", "enter 2", "leave 5", "enter 3", "leave 3", "enter 1",
+                "leave 1", "launched"
+            ]
+        );
+
+        sm.transition(Countdown(1));
+        assert!(sm.is_in::<Countdown>());
+    }
+}
@@ -0,0 +1,137 @@
+use crate::state_machine::{HasEndState, Machine, State, StateMachine, StateTypes};
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + HasEndState,
+{
+    /// Updates the machine with each item as arguments (see [`StateMachine::update_args()`]) until
+    /// the items run out or the machine is finished. Pass `iter.by_ref()` to keep the remaining items.
+    ///
+    /// # Returns
+    /// Number of consumed items.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[StateEnum(args = char, context = String)]
+    /// enum Word {
+    ///     Reading,
+    ///     EndState,
+    /// }
+    /// impl Machine for Word {}
+    /// impl State for Reading {
+    ///     fn on_update(&mut self, args: Option<&mut char>, context: &mut String) -> Option<Word> {
+    ///         match *args? {
+    ///             ' ' => Some(EndState.into()),
+    ///             c => {
+    ///                 context.push(c);
+    ///                 None
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut chars = "hello world".chars();
+    /// let mut sm = StateMachine::new_context(Reading, String::new());
+    /// assert_eq!(sm.feed(chars.by_ref()), 6);
+    /// assert_eq!(sm.context(), "hello");
+    /// assert_eq!(chars.as_str(), "world");
+    /// ```
+    pub fn feed<I>(&mut self, items: I) -> usize
+    where
+        I: IntoIterator<Item = S::Args>,
+    {
+        let mut consumed = 0;
+        let mut items = items.into_iter();
+        while !self.is_finished() {
+            let Some(mut args) = items.next() else {
+                break;
+            };
+            self.update_args(&mut args);
+            consumed += 1;
+        }
+        consumed
+    }
+
+    /// Same as [`StateMachine::feed()`], but the items are pulled from a [`futures_core::Stream`].
+    #[cfg(feature = "futures")]
+    pub async fn feed_stream<St>(&mut self, stream: St) -> usize
+    where
+        St: futures_core::Stream<Item = S::Args>,
+    {
+        let mut consumed = 0;
+        let mut stream = std::pin::pin!(stream);
+        while !self.is_finished() {
+            let Some(mut args) = std::future::poll_fn(|cx| stream.as_mut().poll_next(cx)).await
+            else {
+                break;
+            };
+            self.update_args(&mut args);
+            consumed += 1;
+        }
+        consumed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+
+    #[StateEnum(args = u8, context = Vec<u8>)]
+    enum Packet {
+        Header,
+        Payload(usize),
+        EndState,
+    }
+    impl Machine for Packet {}
+    impl State for Header {
+        fn on_update(
+            &mut self,
+            args: Option<&mut Self::Args>,
+            _context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            Some(Payload(*args? as usize).into())
+        }
+    }
+    impl State for Payload {
+        fn on_update(
+            &mut self,
+            args: Option<&mut Self::Args>,
+            context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            context.push(*args?);
+            self.0 -= 1;
+            (self.0 == 0).then_some(EndState.into())
+        }
+    }
+
+    #[test]
+    fn test_feed() {
+        let mut bytes = [2, 10, 20, 30].into_iter();
+        let mut sm = StateMachine::new_context(Header, vec![]);
+        assert_eq!(sm.feed(bytes.by_ref()), 3);
+        assert!(sm.is_finished());
+        assert_eq!(sm.context(), &[10, 20]);
+        assert_eq!(bytes.next(), Some(30));
+        assert_eq!(sm.feed([1, 2]), 0);
+
+        let mut sm = StateMachine::new_context(Header, vec![]);
+        assert_eq!(sm.feed([3, 1]), 2);
+        assert!(sm.is_in::<Payload>());
+    }
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn test_feed_stream() {
+        let mut sm = StateMachine::new_context(Header, vec![]);
+        let consumed =
+            futures::executor::block_on(sm.feed_stream(futures::stream::iter([1, 7, 8])));
+        assert_eq!(consumed, 2);
+        assert!(sm.is_finished());
+        assert_eq!(sm.context(), &[7]);
+    }
+}
@@ -0,0 +1,184 @@
+use crate::enums::{FlagTypeConstraints, Flags, ReflectEnum};
+
+/// Collects the prerequisite conditions of a transition as [`Flags`], e.g. the resources of a loading state.
+/// The transition is taken once all the required flags are satisfied.
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+///
+/// #[FlagEnum]
+/// #[derive(Debug)]
+/// enum Assets {
+///     Textures,
+///     Sounds,
+///     Config,
+/// }
+///
+/// #[StateEnum]
+/// enum Game {
+///     Loading(TransitionGate<Assets>),
+///     Playing,
+/// }
+/// impl Machine for Game {}
+/// impl State for Loading {
+///     fn on_update(&mut self, _args: Option<&mut Self::Args>, _context: &mut Self::Context) -> Option<Self::States> {
+///         self.0.then(Playing)
+///     }
+/// }
+/// impl State for Playing {}
+///
+/// let mut sm = StateMachine::new(Loading(TransitionGate::all()));
+/// let gate = &mut sm.state_as_mut::<Loading>().unwrap().0;
+/// gate.satisfy(Assets::Textures | Assets::Sounds);
+/// assert_eq!(gate.missing(), Assets::Config);
+/// sm.update();
+/// assert!(sm.is_in::<Loading>());
+///
+/// sm.state_as_mut::<Loading>().unwrap().0.satisfy(Assets::Config);
+/// sm.update();
+/// assert!(sm.is_in::<Playing>());
+/// ```
+pub struct TransitionGate<E>
+where
+    E: ReflectEnum,
+{
+    required: Flags<E>,
+    satisfied: Flags<E>,
+}
+
+impl<E> TransitionGate<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints,
+{
+    /// Creates a gate which requires the given flags.
+    pub fn new(required: impl Into<Flags<E>>) -> Self {
+        Self {
+            required: required.into(),
+            satisfied: Flags::empty(),
+        }
+    }
+
+    /// Creates a gate which requires all the flags of `E`.
+    pub fn all() -> Self {
+        Self::new(Flags::full())
+    }
+
+    /// Marks the flags as satisfied.
+    pub fn satisfy(&mut self, flags: impl Into<Flags<E>>) {
+        self.satisfied.set(flags);
+    }
+
+    /// Marks the flags as no longer satisfied.
+    pub fn unsatisfy(&mut self, flags: impl Into<Flags<E>>) {
+        self.satisfied.reset(flags);
+    }
+
+    /// Marks all the flags as no longer satisfied.
+    pub fn reset(&mut self) {
+        self.satisfied = Flags::empty();
+    }
+
+    /// Returns `true` if all the required flags are satisfied.
+    pub fn ready(&self) -> bool {
+        self.satisfied.all(self.required)
+    }
+
+    /// Returns the required flags.
+    pub fn required(&self) -> Flags<E> {
+        self.required
+    }
+
+    /// Returns the satisfied flags.
+    pub fn satisfied(&self) -> Flags<E> {
+        self.satisfied
+    }
+
+    /// Returns the required flags which are not satisfied yet.
+    pub fn missing(&self) -> Flags<E> {
+        self.required
+            .iter()
+            .filter_map(|flag| flag.value)
+            .filter(|&flag| !self.satisfied.any(flag))
+            .collect()
+    }
+
+    /// Returns the target state if the gate is [`TransitionGate::ready()`].
+    /// Meant to be returned from [`crate::state_machine::State::on_update()`].
+    pub fn then<S>(&self, target: impl Into<S>) -> Option<S> {
+        self.ready().then(|| target.into())
+    }
+}
+
+impl<E> Clone for TransitionGate<E>
+where
+    E: ReflectEnum + Copy,
+    E::Type: FlagTypeConstraints,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for TransitionGate<E>
+where
+    E: ReflectEnum + Copy,
+    E::Type: FlagTypeConstraints,
+{
+}
+
+impl<E> std::fmt::Debug for TransitionGate<E>
+where
+    E: ReflectEnum + Into<E::Type> + Copy + 'static,
+    E::Type: FlagTypeConstraints,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TransitionGate")
+            .field("required", &self.required)
+            .field("satisfied", &self.satisfied)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+
+    #[FlagEnum]
+    #[derive(Debug)]
+    enum TestGateFlags {
+        Network,
+        Storage,
+        Login,
+    }
+
+    #[test]
+    fn test_transition_gate() {
+        let mut gate = TransitionGate::new(TestGateFlags::Network | TestGateFlags::Storage);
+        assert!(!gate.ready());
+        assert_eq!(gate.then::<u8>(1), None);
+
+        gate.satisfy(TestGateFlags::Network | TestGateFlags::Login);
+        assert!(!gate.ready());
+        assert_eq!(gate.missing(), TestGateFlags::Storage);
+
+        gate.satisfy(TestGateFlags::Storage);
+        assert!(gate.ready());
+        assert_eq!(gate.then::<u8>(1), Some(1));
+        assert_eq!(
+            format!("{:?}", gate),
+            "TransitionGate { required: (Network,Storage), satisfied: (Network,Storage,Login) }"
+        );
+
+        gate.unsatisfy(TestGateFlags::Network);
+        assert_eq!(gate.missing(), TestGateFlags::Network);
+        gate.reset();
+        assert_eq!(gate.satisfied(), ());
+        assert_eq!(
+            gate.required(),
+            TestGateFlags::Network | TestGateFlags::Storage
+        );
+        assert!(TransitionGate::<TestGateFlags>::new(()).ready());
+    }
+}
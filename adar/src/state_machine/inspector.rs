@@ -0,0 +1,144 @@
+use crate::state_machine::{Machine, State, StateMachine, StateTypes};
+use std::{any::Any, time::Duration};
+
+/// Field of a state, as returned by [`StateFields::fields()`].
+#[derive(Debug, Clone, Copy)]
+pub struct StateField<'a> {
+    /// Name of the field, or its index for tuple states.
+    pub name: &'static str,
+    pub value: &'a dyn Any,
+}
+
+impl<'a> StateField<'a> {
+    /// Gets the value of the field if it's a `T`.
+    pub fn downcast_ref<T: Any>(&self) -> Option<&'a T> {
+        self.value.downcast_ref()
+    }
+}
+
+/// Lists the fields of the current state. Generated by `#[StateEnum]`.
+pub trait StateFields {
+    fn fields(&self) -> Vec<StateField<'_>>;
+}
+
+/// Snapshot of a running machine, created by [`InspectMachine::status()`].
+#[derive(Debug)]
+pub struct MachineStatus<'a> {
+    /// Name of the current state. See [`State::state_name()`].
+    pub state: &'static str,
+    /// Fields of the current state.
+    pub fields: Vec<StateField<'a>>,
+    /// Names of the paused states on the stack, from the bottom.
+    pub stack: Vec<&'static str>,
+    /// Name of the state the next update transitions to. See [`StateMachine::request_transition()`].
+    pub pending: Option<&'static str>,
+    /// Time left until the timeout of the current state. See [`StateMachine::remaining_timeout()`].
+    pub remaining_timeout: Option<Duration>,
+}
+
+/// Object safe access to the live status of a machine, e.g. for debug overlays which display
+/// machines of different types.
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+///
+/// #[StateEnum]
+/// enum Door {
+///     Closed,
+///     Opening { progress: u8 },
+/// }
+/// impl Machine for Door {}
+/// impl State for Closed {}
+/// impl State for Opening {}
+///
+/// let sm = StateMachine::new(Opening { progress: 40 });
+/// sm.request_transition(Closed);
+///
+/// let machines: [&dyn InspectMachine; 1] = [&sm];
+/// let status = machines[0].status();
+/// assert_eq!(status.state, "Opening");
+/// assert_eq!(status.fields[0].name, "progress");
+/// assert_eq!(status.fields[0].downcast_ref::<u8>(), Some(&40));
+/// assert_eq!(status.pending, Some("Closed"));
+/// ```
+pub trait InspectMachine {
+    fn status(&self) -> MachineStatus<'_>;
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> InspectMachine
+    for StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + StateFields,
+{
+    fn status(&self) -> MachineStatus<'_> {
+        MachineStatus {
+            state: self.state().state_name(),
+            fields: self.state().fields(),
+            stack: self
+                .stack()
+                .iter()
+                .map(|state| state.state_name())
+                .collect(),
+            pending: self.pending_transition(),
+            remaining_timeout: self.remaining_timeout(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+    use std::time::Duration;
+
+    #[StateEnum]
+    enum Player {
+        Idle,
+        #[timeout(duration = "10s", to = Idle)]
+        Walking(f32, u8),
+        Menu {
+            page: &'static str,
+        },
+    }
+
+    impl Machine for Player {}
+    impl State for Idle {}
+    impl State for Walking {}
+    impl State for Menu {}
+
+    #[test]
+    fn test_status() {
+        let mut sm = StateMachine::new(Walking(1.5, 3));
+        let status = sm.status();
+        assert_eq!(status.state, "Walking");
+        assert_eq!(
+            status.fields.iter().map(|f| f.name).collect::<Vec<_>>(),
+            ["0", "1"]
+        );
+        assert_eq!(status.fields[0].downcast_ref::<f32>(), Some(&1.5));
+        assert_eq!(status.fields[1].downcast_ref::<f32>(), None);
+        assert!(status.remaining_timeout.unwrap() <= Duration::from_secs(10));
+        assert_eq!(status.pending, None);
+
+        sm.push(Menu { page: "Settings" });
+        sm.request_transition(Idle);
+        let status = sm.status();
+        assert_eq!(status.state, "Menu");
+        assert_eq!(status.fields[0].name, "page");
+        assert_eq!(
+            status.fields[0].downcast_ref::<&'static str>(),
+            Some(&"Settings")
+        );
+        assert_eq!(status.stack, ["Walking"]);
+        assert_eq!(status.pending, Some("Idle"));
+        assert_eq!(status.remaining_timeout, None);
+
+        sm.update();
+        let status = sm.status();
+        assert_eq!(status.state, "Idle");
+        assert!(status.fields.is_empty());
+    }
+}
@@ -0,0 +1,175 @@
+use crate::state_machine::{Machine, State, StateMachine, StateTypes, UnitType};
+
+/// Projection of a parent context onto the context of a nested machine. See [`StateMachine::update_in()`].
+///
+/// Implemented by the context type of the nested machine, so a parent context can have one lens per field type.
+/// Use [`StateMachine::map_context()`] with a closure when the parent has multiple fields of the same type.
+pub trait ContextLens<Parent> {
+    fn focus(parent: &mut Parent) -> &mut Self;
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>,
+{
+    /// Calls `f` with the machine operating on a projection of a parent context instead of its own context.
+    /// The contexts are swapped for the duration of the call, so the machine's own context is only a placeholder
+    /// (e.g. [`Default::default()`]) between the calls. Callbacks outside of the call (e.g. leaving the states on drop)
+    /// see the placeholder. If `f` panics, the contexts are not swapped back.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[derive(Default)]
+    /// struct Network {
+    ///     sent: u32,
+    /// }
+    ///
+    /// struct Game {
+    ///     network: Network,
+    /// }
+    ///
+    /// #[StateEnum(context = Network)]
+    /// enum Sync {
+    ///     Sending,
+    /// }
+    /// impl Machine for Sync {}
+    /// impl State for Sending {
+    ///     fn on_update(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) -> Option<Self::States> {
+    ///         context.sent += 1;
+    ///         None
+    ///     }
+    /// }
+    ///
+    /// let mut game = Game { network: Network::default() };
+    /// let mut sync = StateMachine::new(Sending);
+    /// sync.map_context(&mut game, |game| &mut game.network, |sync| sync.update());
+    /// assert_eq!(game.network.sent, 1);
+    /// assert_eq!(sync.context().sent, 0);
+    /// ```
+    pub fn map_context<Parent, R>(
+        &mut self,
+        parent: &mut Parent,
+        lens: impl FnOnce(&mut Parent) -> &mut S::Context,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        let context = lens(parent);
+        std::mem::swap(self.context_mut(), context);
+        let result = f(self);
+        std::mem::swap(self.context_mut(), context);
+        result
+    }
+
+    /// Creates a new machine which operates on the projection of `parent` given by [`ContextLens`].
+    /// The initial state is entered with the projected context. See [`StateMachine::map_context()`].
+    pub fn new_in<S2, Parent>(
+        state: S2,
+        parent: &mut Parent,
+    ) -> StateMachine<S2::States, P1, P2, P3, P4, P5, P6, P7, P8>
+    where
+        S2: StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S> + Into<S2::States>,
+        S::Context: ContextLens<Parent> + Default,
+    {
+        let context = S::Context::focus(parent);
+        let mut this = Self::new_context(state, std::mem::take(context));
+        std::mem::swap(this.context_mut(), context);
+        this
+    }
+
+    /// Same as [`StateMachine::update_args()`], but operates on the projection of `parent` given by [`ContextLens`].
+    pub fn update_args_in<Parent>(&mut self, args: &mut S::Args, parent: &mut Parent)
+    where
+        S::Context: ContextLens<Parent>,
+    {
+        self.map_context(parent, S::Context::focus, |this| this.update_args(args));
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>,
+    S::Args: UnitType,
+{
+    /// Same as [`StateMachine::update()`], but operates on the projection of `parent` given by [`ContextLens`].
+    pub fn update_in<Parent>(&mut self, parent: &mut Parent)
+    where
+        S::Context: ContextLens<Parent>,
+    {
+        self.update_args_in(&mut S::Args::unit(), parent);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+
+    #[derive(Default)]
+    struct TestNetwork {
+        log: Vec<&'static str>,
+    }
+
+    struct TestParent {
+        network: TestNetwork,
+        backup: TestNetwork,
+    }
+
+    impl ContextLens<TestParent> for TestNetwork {
+        fn focus(parent: &mut TestParent) -> &mut Self {
+            &mut parent.network
+        }
+    }
+
+    #[StateEnum(context = TestNetwork)]
+    enum TestLens {
+        Handshake,
+        Streaming,
+    }
+    impl Machine for TestLens {}
+    impl State for Handshake {
+        fn on_enter(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            context.log.push("Handshake::on_enter");
+        }
+
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            _context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            Some(Streaming.into())
+        }
+    }
+    impl State for Streaming {
+        fn on_enter(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            context.log.push("Streaming::on_enter");
+        }
+    }
+
+    #[test]
+    fn test_context_lens() {
+        let mut parent = TestParent {
+            network: TestNetwork::default(),
+            backup: TestNetwork::default(),
+        };
+        let mut sm = StateMachine::new_in(Handshake, &mut parent);
+        assert!(sm.context().log.is_empty());
+        sm.update_in(&mut parent);
+        assert_eq!(
+            parent.network.log,
+            ["Handshake::on_enter", "Streaming::on_enter"]
+        );
+
+        sm.map_context(
+            &mut parent,
+            |parent| &mut parent.backup,
+            |sm| sm.transition(Handshake),
+        );
+        assert_eq!(parent.backup.log, ["Handshake::on_enter"]);
+        assert_eq!(parent.network.log.len(), 2);
+        assert!(sm.context().log.is_empty());
+    }
+}
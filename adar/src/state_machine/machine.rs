@@ -0,0 +1,2858 @@
+use crate::state_machine::{CancelToken, Middleware};
+use std::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// Transition requested by [`State::on_update_transition()`].
+#[derive(Debug, Eq, PartialEq)]
+pub enum Transition<S> {
+    /// Leaves the current state and enters the new one.
+    To(S),
+    /// Pauses the current state, stores it on the stack and enters the new one.
+    Push(S),
+    /// Leaves the current state and resumes the last one from the stack.
+    Pop,
+}
+
+pub trait StateTypes<P1 = (), P2 = (), P3 = (), P4 = (), P5 = (), P6 = (), P7 = (), P8 = ()> {
+    type States;
+    type Context;
+    type Args;
+}
+
+/// Event type passed to [`State::on_event()`]. Generated by `#[StateEnum]`, see the `event` parameter.
+pub trait HasEvent<P1 = (), P2 = (), P3 = (), P4 = (), P5 = (), P6 = (), P7 = (), P8 = ()>:
+    StateTypes<P1, P2, P3, P4, P5, P6, P7, P8>
+{
+    type Event;
+}
+
+/// Error type of the fallible callbacks (e.g. [`State::try_on_enter()`], [`State::try_on_update()`]).
+/// Generated by `#[StateEnum]`, see the `error` parameter.
+pub trait HasError<P1 = (), P2 = (), P3 = (), P4 = (), P5 = (), P6 = (), P7 = (), P8 = ()>:
+    StateTypes<P1, P2, P3, P4, P5, P6, P7, P8>
+{
+    type Error;
+}
+
+pub trait State<P1 = (), P2 = (), P3 = (), P4 = (), P5 = (), P6 = (), P7 = (), P8 = ()>
+where
+    Self: StateTypes<P1, P2, P3, P4, P5, P6, P7, P8>,
+{
+    #[allow(unused_variables)]
+    #[inline(always)]
+    fn on_enter(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) {}
+
+    /// Called by the machine when the state is entered. Returns the state to transition to right away.
+    /// The default implementation calls [`State::on_enter()`]. `#[StateEnum]` calls [`State::try_on_enter()`]
+    /// instead and passes its errors to [`Machine::on_error()`].
+    #[inline(always)]
+    fn enter_state(
+        &mut self,
+        args: Option<&mut Self::Args>,
+        context: &mut Self::Context,
+    ) -> Option<Self::States>
+    where
+        Self: Machine<P1, P2, P3, P4, P5, P6, P7, P8>,
+    {
+        self.on_enter(args, context);
+        None
+    }
+
+    /// Fallible version of [`State::on_enter()`]. The default implementation calls [`State::on_enter()`].
+    /// Errors are passed to [`Machine::on_error()`], except in [`StateMachine::try_new_context()`].
+    #[allow(clippy::type_complexity)]
+    #[inline(always)]
+    fn try_on_enter(
+        &mut self,
+        args: Option<&mut Self::Args>,
+        context: &mut Self::Context,
+    ) -> Result<(), <Self as HasError<P1, P2, P3, P4, P5, P6, P7, P8>>::Error>
+    where
+        Self: HasError<P1, P2, P3, P4, P5, P6, P7, P8>,
+    {
+        self.on_enter(args, context);
+        Ok(())
+    }
+
+    #[allow(unused_variables)]
+    #[inline(always)]
+    fn on_update(
+        &mut self,
+        args: Option<&mut Self::Args>,
+        context: &mut Self::Context,
+    ) -> Option<Self::States> {
+        None
+    }
+
+    #[allow(unused_variables)]
+    #[inline(always)]
+    fn on_leave(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) {}
+
+    /// Same as [`State::on_update()`], but it can also push and pop states. See [`Transition`].
+    #[inline(always)]
+    fn on_update_transition(
+        &mut self,
+        args: Option<&mut Self::Args>,
+        context: &mut Self::Context,
+    ) -> Option<Transition<Self::States>> {
+        self.on_update(args, context).map(Transition::To)
+    }
+
+    /// Fallible version of [`State::on_update_transition()`]. The default implementation calls [`State::on_update_transition()`].
+    /// `#[StateEnum]` calls this from [`State::update_state()`] and passes its errors to [`Machine::on_error()`].
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[StateEnum(context = Vec<&'static str>, error = std::num::ParseIntError)]
+    /// enum Parser {
+    ///     Parsing,
+    ///     Failed,
+    /// }
+    /// impl Machine for Parser {
+    ///     fn on_error(&mut self, _error: std::num::ParseIntError, _context: &mut Self::Context) -> Option<Self::States> {
+    ///         Some(Failed.into())
+    ///     }
+    /// }
+    /// impl State for Parsing {
+    ///     fn try_on_update(
+    ///         &mut self,
+    ///         _args: Option<&mut Self::Args>,
+    ///         context: &mut Self::Context,
+    ///     ) -> Result<Option<Transition<Self::States>>, std::num::ParseIntError> {
+    ///         context.pop().map(str::parse::<u32>).transpose()?;
+    ///         Ok(None)
+    ///     }
+    /// }
+    /// impl State for Failed {}
+    ///
+    /// let mut sm = StateMachine::new_context(Parsing, vec!["nan", "1"]);
+    /// sm.update();
+    /// assert!(sm.is_in::<Parsing>());
+    /// sm.update();
+    /// assert!(sm.is_in::<Failed>());
+    /// ```
+    #[allow(clippy::type_complexity)]
+    #[inline(always)]
+    fn try_on_update(
+        &mut self,
+        args: Option<&mut Self::Args>,
+        context: &mut Self::Context,
+    ) -> Result<
+        Option<Transition<Self::States>>,
+        <Self as HasError<P1, P2, P3, P4, P5, P6, P7, P8>>::Error,
+    >
+    where
+        Self: HasError<P1, P2, P3, P4, P5, P6, P7, P8>,
+    {
+        Ok(self.on_update_transition(args, context))
+    }
+
+    /// Called by the machine when the state is updated. Returns the requested transition.
+    /// The default implementation calls [`State::on_update_transition()`]. `#[StateEnum]` calls [`State::try_on_update()`]
+    /// instead and passes its errors to [`Machine::on_error()`].
+    #[inline(always)]
+    fn update_state(
+        &mut self,
+        args: Option<&mut Self::Args>,
+        context: &mut Self::Context,
+    ) -> Option<Transition<Self::States>>
+    where
+        Self: Machine<P1, P2, P3, P4, P5, P6, P7, P8>,
+    {
+        self.on_update_transition(args, context)
+    }
+
+    /// Called when a new state is pushed over this state.
+    #[allow(unused_variables)]
+    #[inline(always)]
+    fn on_pause(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) {}
+
+    /// Called when this state becomes the current state again after the state above it was popped.
+    #[allow(unused_variables)]
+    #[inline(always)]
+    fn on_resume(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) {}
+
+    /// Returns `true` if the state is resumed from the history when it's entered again. \
+    /// Use the `#[history]` attribute on a [`crate::macros::StateEnum`] variant to enable it.
+    #[inline(always)]
+    fn has_history(&self) -> bool {
+        false
+    }
+
+    /// Returns the name of the state used in diagnostics (e.g. the `tracing` feature). \
+    /// [`crate::macros::StateEnum`] returns the name from [`crate::enums::ReflectEnum`] if the enum has it, otherwise the name of the variant.
+    #[inline(always)]
+    fn state_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    #[allow(unused_variables)]
+    #[inline(always)]
+    fn on_event(
+        &mut self,
+        event: <Self as HasEvent<P1, P2, P3, P4, P5, P6, P7, P8>>::Event,
+        context: &mut Self::Context,
+    ) -> Option<Self::States>
+    where
+        Self: HasEvent<P1, P2, P3, P4, P5, P6, P7, P8>,
+    {
+        None
+    }
+
+    /// Returns the time after which [`State::on_timeout()`] is called. \
+    /// Use the `#[timeout(duration = "2s", to = NextState)]` attribute on a [`crate::macros::StateEnum`] variant to set it.
+    #[inline(always)]
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Called instead of [`State::on_update()`] when the [`State::timeout()`] has elapsed since the state was entered or resumed.
+    /// Called once per elapsed timeout, returning `None` stays in the state without a pending timeout.
+    #[allow(unused_variables)]
+    #[inline(always)]
+    fn on_timeout(&mut self, context: &mut Self::Context) -> Option<Self::States> {
+        None
+    }
+}
+
+pub trait Machine<P1 = (), P2 = (), P3 = (), P4 = (), P5 = (), P6 = (), P7 = (), P8 = ()>
+where
+    Self: StateTypes<P1, P2, P3, P4, P5, P6, P7, P8>,
+{
+    /// Called at each transition on the old state, after it's left and before the new state is entered.
+    #[allow(unused_variables)]
+    #[inline(always)]
+    fn on_transition(&mut self, new_state: &Self::States, context: &mut Self::Context) {}
+
+    /// Called at each transition on the old state with both ends of the edge, right after [`Machine::on_transition()`].
+    /// Useful for logging or validating transitions.
+    #[allow(unused_variables)]
+    #[inline(always)]
+    fn on_transition_from(&self, new_state: &Self::States, context: &mut Self::Context) {}
+    #[allow(unused_variables)]
+    #[inline(always)]
+    fn on_update(&mut self, context: &mut Self::Context) {}
+
+    /// Called on the current state when one of its fallible callbacks fails. The returned state is transitioned to.
+    #[allow(unused_variables)]
+    #[inline(always)]
+    fn on_error(
+        &mut self,
+        error: <Self as HasError<P1, P2, P3, P4, P5, P6, P7, P8>>::Error,
+        context: &mut Self::Context,
+    ) -> Option<Self::States>
+    where
+        Self: HasError<P1, P2, P3, P4, P5, P6, P7, P8>,
+    {
+        None
+    }
+}
+
+/// Drives the states of `S` and owns the shared context.
+///
+/// # Panic safety
+/// A panic inside a callback propagates to the caller and leaves the machine in a consistent state:
+/// - `on_update`, `on_event`, `on_leave` and `on_pause`: the current state is kept.
+/// - `Machine::on_transition`: the current state is kept, but it has already been left (or paused).
+/// - `on_enter` and `on_resume`: the machine is already in the new state.
+///
+/// When the machine is dropped the current state and the stacked states are left by calling `on_leave`.
+/// This can be disabled by turning off the `leave-on-drop` feature. The callbacks are never invoked while
+/// the thread is panicking, because a second panic during unwinding would abort the process.
+///
+/// # Serialization
+/// With the `serde` feature the machine can be serialized if its states and context are serializable
+/// (see `#[StateEnum(serde)]`). The current state, the state stack, the history and the context are stored.
+/// Deserializing does not call any callbacks and the timeout of the current state restarts.
+pub struct StateMachine<S, P1 = (), P2 = (), P3 = (), P4 = (), P5 = (), P6 = (), P7 = (), P8 = ()>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>,
+{
+    state: Part<S::States>,
+    stack: Vec<S::States>,
+    history: Vec<S::States>,
+    context: Part<S::Context>,
+    entered: Option<Instant>,
+    requests: Mutex<Vec<S::States>>,
+    middleware: Option<Box<Middleware<S::States, S::Context>>>,
+    #[cfg(feature = "registry")]
+    pub(crate) transition_event: Option<Box<crate::state_machine::TransitionEvent<S::States>>>,
+    #[allow(clippy::type_complexity)]
+    phantom: PhantomData<(P1, P2, P3, P4, P5, P6, P7, P8)>,
+}
+
+/// Field of [`StateMachine`] that is only moved out when the machine is consumed by [`StateMachine::into_parts()`].
+struct Part<T>(Option<T>);
+
+impl<T> Deref for Part<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.0.as_ref().expect("Part of a consumed StateMachine")
+    }
+}
+
+impl<T> DerefMut for Part<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.0.as_mut().expect("Part of a consumed StateMachine")
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+    impl Sealed for () {}
+}
+
+/// Implemented only by `()`. Sealed, so the set of argument types which allow argument-less updates stays fixed.
+pub trait UnitType: sealed::Sealed {
+    fn unit() -> Self;
+}
+impl UnitType for () {
+    fn unit() -> Self {}
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>,
+{
+    pub fn new_context<S2>(
+        state: S2,
+        context: S::Context,
+    ) -> StateMachine<S2::States, P1, P2, P3, P4, P5, P6, P7, P8>
+    where
+        S2: StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S> + Into<S2::States>,
+    {
+        StateMachine::with_middleware(state.into(), context, None)
+    }
+
+    pub(crate) fn with_middleware(
+        state: S,
+        context: S::Context,
+        middleware: Option<Box<Middleware<S, S::Context>>>,
+    ) -> Self {
+        let mut machine = Self {
+            entered: state.timeout().map(|_| Instant::now()),
+            state: Part(Some(state)),
+            stack: Vec::new(),
+            history: Vec::new(),
+            context: Part(Some(context)),
+            requests: Mutex::new(Vec::new()),
+            middleware,
+            #[cfg(feature = "registry")]
+            transition_event: None,
+            phantom: PhantomData,
+        };
+        machine.enter(None);
+        machine
+    }
+
+    /// Same as [`StateMachine::new_context()`], but fails if [`State::try_on_enter()`] of the initial state fails.
+    ///
+    /// # Returns
+    /// `Ok` - The new machine \
+    /// `Err` - The context and the error
+    pub fn try_new_context<S2>(
+        state: S2,
+        mut context: S::Context,
+    ) -> Result<Self, (S::Context, S::Error)>
+    where
+        S: HasError<P1, P2, P3, P4, P5, P6, P7, P8>,
+        S2: StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S> + Into<S2::States>,
+    {
+        let mut state = state.into() as S2::States;
+        let entered = state.timeout().map(|_| Instant::now());
+        if let Err(error) = state.try_on_enter(None, &mut context) {
+            return Err((context, error));
+        }
+        Ok(Self {
+            state: Part(Some(state)),
+            stack: Vec::new(),
+            history: Vec::new(),
+            context: Part(Some(context)),
+            entered,
+            requests: Mutex::new(Vec::new()),
+            middleware: None,
+            #[cfg(feature = "registry")]
+            transition_event: None,
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn new<S2>(state: S2) -> Self
+    where
+        S2: StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S> + Into<S2::States>,
+        S::Context: Default,
+    {
+        Self::new_context(state, S::Context::default())
+    }
+
+    /// Updates the machine until it settles. Pending timeouts are waited for. See [`State::timeout()`].
+    pub fn run_args(&mut self, args: &mut S::Args) {
+        self.run_budget(args, usize::MAX, None, None);
+    }
+
+    /// Same as [`StateMachine::run_args()`], but stops after `max_iterations` passes.
+    /// Each update, timeout transition or wait for a timeout is one pass.
+    ///
+    /// # Returns
+    /// `true` if the machine settled within the budget.
+    pub fn run_for_args(&mut self, args: &mut S::Args, max_iterations: usize) -> bool {
+        self.run_budget(args, max_iterations, None, None)
+    }
+
+    /// Same as [`StateMachine::run_args()`], but stops when the deadline is reached.
+    ///
+    /// # Returns
+    /// `true` if the machine settled before the deadline.
+    pub fn run_until_args(&mut self, args: &mut S::Args, deadline: Instant) -> bool {
+        self.run_budget(args, usize::MAX, Some(deadline), None)
+    }
+
+    /// Same as [`StateMachine::run_args()`], but stops when the token is cancelled. The token is checked
+    /// before each pass and cancelling it interrupts the wait for a timeout.
+    ///
+    /// # Returns
+    /// `true` if the machine settled before the token was cancelled.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// #[StateEnum]
+    /// enum Worker {
+    ///     #[timeout(duration = "1h", to = Done)]
+    ///     Sleeping,
+    ///     Done,
+    /// }
+    /// impl Machine for Worker {}
+    /// impl State for Sleeping {}
+    /// impl State for Done {}
+    ///
+    /// let token = CancelToken::new();
+    /// let token2 = token.clone();
+    /// std::thread::spawn(move || {
+    ///     std::thread::sleep(Duration::from_millis(10));
+    ///     token2.cancel();
+    /// });
+    /// let mut sm = StateMachine::new(Sleeping);
+    /// assert!(!sm.run_cancellable(&token));
+    /// assert!(sm.is_in::<Sleeping>());
+    /// ```
+    pub fn run_cancellable_args(&mut self, args: &mut S::Args, token: &CancelToken) -> bool {
+        self.run_budget(args, usize::MAX, None, Some(token))
+    }
+
+    fn run_budget(
+        &mut self,
+        args: &mut S::Args,
+        mut iterations: usize,
+        deadline: Option<Instant>,
+        token: Option<&CancelToken>,
+    ) -> bool {
+        loop {
+            if iterations == 0
+                || deadline.is_some_and(|deadline| Instant::now() >= deadline)
+                || token.is_some_and(CancelToken::is_cancelled)
+            {
+                return false;
+            }
+            iterations -= 1;
+            if self.apply_requests(None) {
+                continue;
+            }
+            if let Some(new_state) = self.take_timeout() {
+                self.transition_args(new_state, None);
+            } else if let Some(transition) = self.update_transition(args) {
+                self.apply_transition_args(transition, None);
+            } else if let Some(remaining) = self.remaining_timeout() {
+                let remaining = match deadline {
+                    Some(deadline) => {
+                        remaining.min(deadline.saturating_duration_since(Instant::now()))
+                    }
+                    None => remaining,
+                };
+                match token {
+                    Some(token) => token.wait(remaining),
+                    None => std::thread::sleep(remaining),
+                }
+            } else {
+                return true;
+            }
+        }
+    }
+
+    pub fn update_args(&mut self, args: &mut S::Args) {
+        if self.apply_requests(Some(args)) {
+            return;
+        }
+        if let Some(new_state) = self.take_timeout() {
+            self.transition_args(new_state, Some(args));
+        } else if let Some(transition) = self.update_transition(args) {
+            self.apply_transition_args(transition, Some(args));
+        }
+    }
+
+    fn update_transition(&mut self, args: &mut S::Args) -> Option<Transition<S>> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!("update", state = self.state.state_name()).entered();
+        if let Some(middleware) = &mut self.middleware {
+            middleware.before_update(&self.state, &mut self.context);
+        }
+        let transition = State::update_state(&mut *self.state, Some(args), &mut self.context);
+        if let Some(middleware) = &mut self.middleware {
+            middleware.after_update(&self.state, &mut self.context);
+        }
+        transition
+    }
+
+    /// Schedules a transition which is applied at the next update instead of updating the current state.
+    /// Only needs a shared reference, so it can be called from callbacks which can not borrow the machine mutably.
+    /// Multiple requests are applied in order.
+    pub fn request_transition(&self, new_state: impl Into<S>) {
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(new_state.into());
+    }
+
+    /// Returns the number of transitions waiting for the next update. See [`StateMachine::request_transition()`].
+    pub fn pending_transitions(&self) -> usize {
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Returns the name of the state the next update transitions to. See [`StateMachine::request_transition()`].
+    pub fn pending_transition(&self) -> Option<&'static str> {
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .first()
+            .map(|state| state.state_name())
+    }
+
+    fn apply_requests(&mut self, mut args: Option<&mut S::Args>) -> bool {
+        let requests = std::mem::take(
+            self.requests
+                .get_mut()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        if requests.is_empty() {
+            return false;
+        }
+        for new_state in requests {
+            self.transition_args(new_state, args.as_deref_mut());
+        }
+        true
+    }
+
+    /// Passes an external event to the current state. If the state reacts with a new state the machine transitions to it.
+    pub fn handle_event(&mut self, event: S::Event)
+    where
+        S: HasEvent<P1, P2, P3, P4, P5, P6, P7, P8>,
+    {
+        if let Some(new_state) = State::on_event(&mut *self.state, event, &mut self.context) {
+            self.transition(new_state);
+        }
+    }
+
+    #[inline(always)]
+    pub fn transition(&mut self, new_state: impl Into<S>) {
+        self.transition_args(new_state, None);
+    }
+
+    pub fn transition_args(&mut self, new_state: impl Into<S>, mut args: Option<&mut S::Args>) {
+        let new_state = new_state.into();
+        #[cfg(feature = "tracing")]
+        let _span = self.transition_span("to", &new_state);
+        self.before_transition(&new_state);
+        self.trace("leave");
+        self.state.on_leave(args.as_deref_mut(), &mut self.context);
+        let new_state = self.restore_history(new_state);
+        self.state.on_transition(&new_state, &mut self.context);
+        self.state.on_transition_from(&new_state, &mut self.context);
+        self.dispatch_transition(&new_state);
+        let old_state = std::mem::replace(&mut *self.state, new_state);
+        self.store_history(old_state);
+        self.reset_timeout();
+        self.enter(args);
+        self.after_transition();
+    }
+
+    /// Pauses the current state, stores it on the stack and enters the new state.
+    #[inline(always)]
+    pub fn push(&mut self, new_state: impl Into<S>) {
+        self.push_args(new_state, None);
+    }
+
+    pub fn push_args(&mut self, new_state: impl Into<S>, mut args: Option<&mut S::Args>) {
+        let new_state = new_state.into();
+        #[cfg(feature = "tracing")]
+        let _span = self.transition_span("push", &new_state);
+        self.before_transition(&new_state);
+        self.trace("pause");
+        self.state.on_pause(args.as_deref_mut(), &mut self.context);
+        let new_state = self.restore_history(new_state);
+        self.state.on_transition(&new_state, &mut self.context);
+        self.state.on_transition_from(&new_state, &mut self.context);
+        self.dispatch_transition(&new_state);
+        self.stack
+            .push(std::mem::replace(&mut self.state, new_state));
+        self.reset_timeout();
+        self.enter(args);
+        self.after_transition();
+    }
+
+    /// Leaves the current state and resumes the last state from the stack.
+    ///
+    /// # Returns
+    /// `false` if the stack is empty. In this case the current state is kept.
+    #[inline(always)]
+    pub fn pop(&mut self) -> bool {
+        self.pop_args(None)
+    }
+
+    pub fn pop_args(&mut self, mut args: Option<&mut S::Args>) -> bool {
+        let Some(new_state) = self.stack.pop() else {
+            return false;
+        };
+        #[cfg(feature = "tracing")]
+        let _span = self.transition_span("pop", &new_state);
+        self.before_transition(&new_state);
+        self.trace("leave");
+        self.state.on_leave(args.as_deref_mut(), &mut self.context);
+        self.state.on_transition(&new_state, &mut self.context);
+        self.state.on_transition_from(&new_state, &mut self.context);
+        self.dispatch_transition(&new_state);
+        let old_state = std::mem::replace(&mut *self.state, new_state);
+        self.store_history(old_state);
+        self.reset_timeout();
+        self.trace("resume");
+        self.state.on_resume(args, &mut self.context);
+        self.after_transition();
+        true
+    }
+
+    /// Swaps the current state without calling any callbacks (e.g. to reload the data of a state) and returns the old one.
+    /// The history, the transition observers and the middleware are not involved either.
+    /// The timeout keeps running if both states have one.
+    pub fn replace_state(&mut self, new_state: impl Into<S>) -> S {
+        let old_state = std::mem::replace(&mut *self.state, new_state.into());
+        if self.state.timeout().is_none() {
+            self.entered = None;
+        } else if self.entered.is_none() {
+            self.entered = Some(Instant::now());
+        }
+        old_state
+    }
+
+    pub fn apply_transition_args(&mut self, transition: Transition<S>, args: Option<&mut S::Args>) {
+        match transition {
+            Transition::To(new_state) => self.transition_args(new_state, args),
+            Transition::Push(new_state) => self.push_args(new_state, args),
+            Transition::Pop => {
+                self.pop_args(args);
+            }
+        }
+    }
+
+    /// Returns the time left until the timeout of the current state elapses. See [`State::timeout()`].
+    pub fn remaining_timeout(&self) -> Option<Duration> {
+        Some(
+            self.state
+                .timeout()?
+                .saturating_sub(self.entered?.elapsed()),
+        )
+    }
+
+    /// Leaves the current state and the stacked states, unless the `leave-on-drop` feature is disabled
+    /// or the thread is panicking.
+    fn leave_all(&mut self) {
+        if cfg!(not(feature = "leave-on-drop")) || std::thread::panicking() {
+            return;
+        }
+        self.trace("leave");
+        self.state.on_leave(None, &mut self.context);
+        while let Some(mut state) = self.stack.pop() {
+            #[cfg(feature = "tracing")]
+            tracing::trace!(state = state.state_name(), "leave");
+            state.on_leave(None, &mut self.context);
+        }
+    }
+
+    /// Decomposes the machine into its current state and context without leaving any state.
+    /// The stacked and history states are dropped without calling [`State::on_leave()`].
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[StateEnum(context = Vec<&'static str>)]
+    /// enum Workflow {
+    ///     Working,
+    /// }
+    /// impl Machine for Workflow {}
+    /// impl State for Working {
+    ///     fn on_leave(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+    ///         context.push("left");
+    ///     }
+    /// }
+    ///
+    /// let (state, context) = StateMachine::new_context(Working, vec!["started"]).into_parts();
+    /// assert!(matches!(state, Workflow::Working(_)));
+    /// assert_eq!(context, ["started"]);
+    /// ```
+    pub fn into_parts(mut self) -> (S::States, S::Context) {
+        // Drop skips leaving once the parts are taken
+        match (self.state.0.take(), self.context.0.take()) {
+            (Some(state), Some(context)) => (state, context),
+            _ => unreachable!("StateMachine is consumed only once"),
+        }
+    }
+
+    /// Drops the machine like [`Drop`] would, but keeps the context.
+    /// The current and the stacked states are left if the `leave-on-drop` feature is enabled.
+    pub fn take_context(mut self) -> S::Context {
+        self.leave_all();
+        self.into_parts().1
+    }
+
+    #[inline(always)]
+    fn before_transition(&mut self, new_state: &S) {
+        if let Some(middleware) = &mut self.middleware {
+            middleware.before_transition(&self.state, new_state, &mut self.context);
+        }
+    }
+
+    #[inline(always)]
+    fn after_transition(&mut self) {
+        if let Some(middleware) = &mut self.middleware {
+            middleware.after_transition(&self.state, &mut self.context);
+        }
+    }
+
+    #[inline(always)]
+    fn dispatch_transition(&self, new_state: &S) {
+        #[cfg(feature = "registry")]
+        if let Some(event) = &self.transition_event {
+            event.dispatch(&self.state, new_state);
+        }
+        #[cfg(not(feature = "registry"))]
+        let _ = new_state;
+    }
+
+    /// Emits a `tracing` event about a callback of the current state (requires `tracing` feature).
+    #[inline(always)]
+    fn trace(&self, callback: &'static str) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(state = self.state.state_name(), "{}", callback);
+        #[cfg(not(feature = "tracing"))]
+        let _ = callback;
+    }
+
+    /// Enters a `tracing` span covering a transition and emits an event about it.
+    #[cfg(feature = "tracing")]
+    fn transition_span(&self, kind: &'static str, new_state: &S) -> tracing::span::EnteredSpan {
+        let from = self.state.state_name();
+        let to = new_state.state_name();
+        let span = tracing::debug_span!("transition", from, to, kind).entered();
+        tracing::debug!(from, to, kind, "transition");
+        span
+    }
+
+    fn enter(&mut self, args: Option<&mut S::Args>) {
+        self.trace("enter");
+        if let Some(new_state) = self.state.enter_state(args, &mut self.context) {
+            self.transition(new_state);
+        }
+    }
+
+    fn reset_timeout(&mut self) {
+        self.entered = self.state.timeout().map(|_| Instant::now());
+    }
+
+    fn take_timeout(&mut self) -> Option<S> {
+        if !self.remaining_timeout()?.is_zero() {
+            return None;
+        }
+        // Note: Consumed even if `on_timeout()` declines, otherwise `run()` would keep waiting for an elapsed timeout.
+        self.entered = None;
+        self.state.on_timeout(&mut self.context)
+    }
+
+    /// Forgets all the states stored in the history. See [`State::has_history()`].
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    fn restore_history(&mut self, new_state: S) -> S {
+        if !new_state.has_history() {
+            return new_state;
+        }
+        let discriminant = std::mem::discriminant(&new_state);
+        match self
+            .history
+            .iter()
+            .position(|state| std::mem::discriminant(state) == discriminant)
+        {
+            Some(index) => self.history.swap_remove(index),
+            None => new_state,
+        }
+    }
+
+    fn store_history(&mut self, old_state: S) {
+        if old_state.has_history() {
+            let discriminant = std::mem::discriminant(&old_state);
+            self.history
+                .retain(|state| std::mem::discriminant(state) != discriminant);
+            self.history.push(old_state);
+        }
+    }
+
+    /// Returns the paused states. The last element is the most recently pushed one.
+    pub fn stack(&self) -> &[S::States] {
+        &self.stack
+    }
+
+    pub fn context(&self) -> &S::Context {
+        &self.context
+    }
+
+    pub fn context_mut(&mut self) -> &mut S::Context {
+        &mut self.context
+    }
+
+    pub fn state(&self) -> &S::States {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut S::States {
+        &mut self.state
+    }
+
+    /// Checks whether the current state is `T`.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[StateEnum]
+    /// enum MyStates {
+    ///     Running,
+    ///     Stopped,
+    /// }
+    /// impl Machine for MyStates {}
+    /// impl State for Running {}
+    /// impl State for Stopped {}
+    ///
+    /// let sm = StateMachine::new(Running);
+    /// assert!(sm.is_in::<Running>());
+    /// assert!(!sm.is_in::<Stopped>());
+    /// ```
+    pub fn is_in<T>(&self) -> bool
+    where
+        T: StateVariant<S::States>,
+    {
+        T::downcast(&self.state).is_some()
+    }
+
+    /// Gets the current state as `T`.
+    ///
+    /// # Returns
+    /// `Some` - The current state if it's `T` \
+    /// `None` - Otherwise
+    pub fn state_as<T>(&self) -> Option<&T>
+    where
+        T: StateVariant<S::States>,
+    {
+        T::downcast(&self.state)
+    }
+
+    /// Gets the current state as mutable `T`. See [`StateMachine::state_as()`].
+    pub fn state_as_mut<T>(&mut self) -> Option<&mut T>
+    where
+        T: StateVariant<S::States>,
+    {
+        T::downcast_mut(&mut self.state)
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>,
+    S::Args: UnitType,
+{
+    pub fn update(&mut self) {
+        self.update_args(&mut S::Args::unit());
+    }
+    pub fn run(&mut self) {
+        self.run_args(&mut S::Args::unit());
+    }
+    pub fn run_for(&mut self, max_iterations: usize) -> bool {
+        self.run_for_args(&mut S::Args::unit(), max_iterations)
+    }
+    pub fn run_until(&mut self, deadline: Instant) -> bool {
+        self.run_until_args(&mut S::Args::unit(), deadline)
+    }
+    pub fn run_cancellable(&mut self, token: &CancelToken) -> bool {
+        self.run_cancellable_args(&mut S::Args::unit(), token)
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + InitialState,
+{
+    /// Creates a new machine in the initial state. See [`InitialState`].
+    pub fn start() -> Self
+    where
+        S::Context: Default,
+    {
+        Self::new(S::initial())
+    }
+
+    /// Creates a new machine with the given context in the initial state. See [`InitialState`].
+    pub fn start_context(context: S::Context) -> Self {
+        Self::new_context(S::initial(), context)
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> Default for StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + InitialState,
+    S::Context: Default,
+{
+    fn default() -> Self {
+        Self::start()
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> HasEndState
+    for StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + HasEndState,
+{
+    fn is_finished(&self) -> bool {
+        self.state.is_finished()
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> HasOutcome
+    for StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + HasOutcome,
+{
+    type Outcome = S::Outcome;
+
+    fn outcome(&self) -> Option<Self::Outcome> {
+        self.state.outcome()
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + FinishedState,
+{
+    /// Returns the payload of the `EndState` if it was reached. See [`FinishedState`].
+    pub fn output(&self) -> Option<&S::Output> {
+        self.state.output()
+    }
+
+    /// Converts the machine into the payload of the `EndState` if it was reached. See [`FinishedState`].
+    /// Like [`StateMachine::into_parts()`], no state is left.
+    pub fn into_output(self) -> Option<S::Output> {
+        self.into_parts().0.into_output()
+    }
+}
+
+/// Copy of a [`StateMachine`], created by [`StateMachine::snapshot()`] and applied with [`StateMachine::restore()`].
+pub struct Snapshot<S, P1 = (), P2 = (), P3 = (), P4 = (), P5 = (), P6 = (), P7 = (), P8 = ()>
+where
+    S: StateTypes<P1, P2, P3, P4, P5, P6, P7, P8>,
+{
+    state: S::States,
+    stack: Vec<S::States>,
+    history: Vec<S::States>,
+    context: S::Context,
+    elapsed: Option<Duration>,
+    requests: Vec<S::States>,
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> Snapshot<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: StateTypes<P1, P2, P3, P4, P5, P6, P7, P8>,
+{
+    /// Gets the current state at the time of the snapshot.
+    pub fn state(&self) -> &S::States {
+        &self.state
+    }
+
+    /// Gets the context at the time of the snapshot.
+    pub fn context(&self) -> &S::Context {
+        &self.context
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> Clone for Snapshot<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: StateTypes<P1, P2, P3, P4, P5, P6, P7, P8>,
+    S::States: Clone,
+    S::Context: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            stack: self.stack.clone(),
+            history: self.history.clone(),
+            context: self.context.clone(),
+            elapsed: self.elapsed,
+            requests: self.requests.clone(),
+        }
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + Clone,
+    S::Context: Clone,
+{
+    /// Copies the current state, the state stack, the history, the context and the pending transitions.
+    /// Middleware and observers are not part of the snapshot.
+    pub fn snapshot(&self) -> Snapshot<S, P1, P2, P3, P4, P5, P6, P7, P8> {
+        Snapshot {
+            state: self.state.clone(),
+            stack: self.stack.clone(),
+            history: self.history.clone(),
+            context: self.context.clone(),
+            elapsed: self.entered.map(|entered| entered.elapsed()),
+            requests: self
+                .requests
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone(),
+        }
+    }
+
+    /// Rewinds the machine to the snapshot. No callbacks are called, the states which are dropped
+    /// are not left. The timeout of the current state continues from where it was at the time of the snapshot.
+    ///
+    /// # Example
+    /// ```
+    /// use adar::prelude::*;
+    ///
+    /// #[StateEnum(context = u32)]
+    /// #[derive(Clone)]
+    /// enum Plan {
+    ///     Explore,
+    ///     Dead,
+    /// }
+    /// impl Machine for Plan {}
+    /// impl State for Explore {
+    ///     fn on_update(&mut self, _args: Option<&mut ()>, context: &mut u32) -> Option<Plan> {
+    ///         *context += 1;
+    ///         (*context == 3).then_some(Dead.into())
+    ///     }
+    /// }
+    /// impl State for Dead {}
+    ///
+    /// let mut sm = StateMachine::new_context(Explore, 1);
+    /// let snapshot = sm.snapshot();
+    /// sm.update();
+    /// sm.update();
+    /// assert!(sm.is_in::<Dead>());
+    ///
+    /// sm.restore(snapshot);
+    /// assert!(sm.is_in::<Explore>());
+    /// assert_eq!(*sm.context(), 1);
+    /// ```
+    pub fn restore(&mut self, snapshot: Snapshot<S, P1, P2, P3, P4, P5, P6, P7, P8>) {
+        *self.state = snapshot.state;
+        self.stack = snapshot.stack;
+        self.history = snapshot.history;
+        *self.context = snapshot.context;
+        self.entered = snapshot.elapsed.map(|elapsed| {
+            Instant::now()
+                .checked_sub(elapsed)
+                .unwrap_or_else(Instant::now)
+        });
+        *self.requests.lock().unwrap_or_else(PoisonError::into_inner) = snapshot.requests;
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> std::fmt::Debug
+    for StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>,
+    S::States: std::fmt::Debug,
+    S::Context: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("StateMachine")
+            .field("state", &*self.state)
+            .field("context", &*self.context)
+            .finish()
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> Drop for StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>,
+{
+    fn drop(&mut self) {
+        if self.state.0.is_some() {
+            self.leave_all();
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+#[serde(rename = "StateMachine")]
+struct StateMachineRef<'a, S, C> {
+    state: &'a S,
+    stack: &'a [S],
+    history: &'a [S],
+    context: &'a C,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(rename = "StateMachine")]
+struct StateMachineData<S, C> {
+    state: S,
+    stack: Vec<S>,
+    history: Vec<S>,
+    context: C,
+}
+
+#[cfg(feature = "serde")]
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> serde::Serialize
+    for StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + serde::Serialize,
+    S::Context: serde::Serialize,
+{
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: serde::Serializer,
+    {
+        let data = StateMachineRef {
+            state: &*self.state,
+            stack: &self.stack,
+            history: &self.history,
+            context: &*self.context,
+        };
+        serde::Serialize::serialize(&data, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'d, S, P1, P2, P3, P4, P5, P6, P7, P8> serde::Deserialize<'d>
+    for StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + serde::Deserialize<'d>,
+    S::Context: serde::Deserialize<'d>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'d>,
+    {
+        let data: StateMachineData<S, S::Context> = serde::Deserialize::deserialize(deserializer)?;
+        Ok(Self {
+            entered: data.state.timeout().map(|_| Instant::now()),
+            state: Part(Some(data.state)),
+            stack: data.stack,
+            history: data.history,
+            context: Part(Some(data.context)),
+            requests: Mutex::new(Vec::new()),
+            middleware: None,
+            #[cfg(feature = "registry")]
+            transition_event: None,
+            phantom: PhantomData,
+        })
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EndState;
+
+impl StateTypes for EndState {
+    type States = ();
+    type Context = ();
+    type Args = ();
+}
+
+impl State for EndState {}
+
+/// Implemented by `#[StateEnum]` enums with terminal states: a variant named `EndState` or variants marked with `#[end]`.
+pub trait HasEndState {
+    /// Returns `true` if a terminal state was reached.
+    fn is_finished(&self) -> bool;
+}
+
+/// Tells which terminal state was reached. Generated by `#[StateEnum]` next to [`HasEndState`].
+pub trait HasOutcome: HasEndState {
+    /// Identifies the terminal state which was reached. Generated as `<Enum>Outcome` with a variant per terminal state.
+    type Outcome;
+
+    /// Returns the terminal state if one was reached.
+    fn outcome(&self) -> Option<Self::Outcome>;
+}
+
+/// Implemented by `#[StateEnum]` enums with an `EndState(Output)` variant, which carries the final result of the machine.
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+///
+/// #[StateEnum]
+/// enum Download {
+///     Fetching,
+///     EndState(Result<usize, String>),
+/// }
+/// impl Machine for Download {}
+/// impl State for Fetching {
+///     fn on_update(&mut self, _args: Option<&mut Self::Args>, _context: &mut Self::Context) -> Option<Self::States> {
+///         Some(EndState(Ok(42)).into())
+///     }
+/// }
+///
+/// let mut sm = StateMachine::new(Fetching);
+/// assert_eq!(sm.output(), None);
+/// sm.update();
+/// assert!(sm.is_finished());
+/// assert_eq!(sm.into_output(), Some(Ok(42)));
+/// ```
+pub trait FinishedState: HasEndState {
+    type Output;
+
+    /// Returns the payload of the `EndState` if it was reached.
+    fn output(&self) -> Option<&Self::Output>;
+
+    /// Converts into the payload of the `EndState` if it was reached.
+    fn into_output(self) -> Option<Self::Output>;
+}
+
+/// State struct wrapped by a variant of the `States` enum. Generated by `#[StateEnum]`.
+pub trait StateVariant<States>: Sized {
+    fn downcast(state: &States) -> Option<&Self>;
+    fn downcast_mut(state: &mut States) -> Option<&mut Self>;
+}
+
+/// State the machine starts in. Generated by `#[StateEnum(initial = ...)]`.
+pub trait InitialState {
+    fn initial() -> Self;
+}
+
+/// Runs an `#[on_enter(...)]` or `#[on_leave(...)]` action of a `#[StateEnum]` variant. Invoked by [`crate::macros::StateEnum`].
+#[doc(hidden)]
+#[inline(always)]
+pub fn run_action<C>(context: &mut C, action: impl FnOnce(&mut C)) {
+    action(context)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+    use once_cell::sync::Lazy;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Eq, PartialEq, Debug)]
+    enum MockState {
+        A,
+        B,
+        C,
+    }
+
+    type MockContext = u32;
+    type MockArgs = u16;
+    #[derive(Eq, PartialEq, Debug)]
+    #[allow(clippy::enum_variant_names)]
+    enum MockCall {
+        OnEnter((Option<MockArgs>, MockContext)),
+        OnUpdate((Option<MockArgs>, MockContext)),
+        OnLeave((Option<MockArgs>, MockContext)),
+    }
+
+    #[derive(Default, Clone)]
+    struct Mock(Arc<Mutex<MockInner>>);
+
+    static MOCK: Lazy<Mock> = Lazy::new(Mock::default);
+
+    #[derive(Default)]
+    struct MockInner {
+        calls: Vec<(MockState, MockCall)>,
+        b_transition: Option<Test>,
+    }
+
+    impl Mock {
+        pub fn push(&self, state: MockState, call: MockCall) {
+            self.0.lock().unwrap().calls.push((state, call));
+        }
+
+        pub fn take(&self) -> Vec<(MockState, MockCall)> {
+            std::mem::take(&mut self.0.lock().unwrap().calls)
+        }
+
+        pub fn b_transition(&self, state: Test) {
+            self.0.lock().unwrap().b_transition = Some(state);
+        }
+    }
+
+    #[StateEnum(context=MockContext, args=MockArgs)]
+    enum Test {
+        A,
+        B,
+        C,
+    }
+
+    impl Machine for Test {}
+
+    impl State for A {
+        fn on_enter(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            MOCK.push(MockState::A, MockCall::OnEnter((args.cloned(), *context)));
+        }
+
+        fn on_update(
+            &mut self,
+            args: Option<&mut Self::Args>,
+            context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            MOCK.push(MockState::A, MockCall::OnUpdate((args.cloned(), *context)));
+            None
+        }
+
+        fn on_leave(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            MOCK.push(MockState::A, MockCall::OnLeave((args.cloned(), *context)));
+        }
+    }
+    impl State for B {
+        fn on_enter(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            MOCK.push(MockState::B, MockCall::OnEnter((args.cloned(), *context)));
+        }
+
+        fn on_update(
+            &mut self,
+            args: Option<&mut Self::Args>,
+            context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            MOCK.push(MockState::B, MockCall::OnUpdate((args.cloned(), *context)));
+            MOCK.0.lock().unwrap().b_transition.take()
+        }
+
+        fn on_leave(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            MOCK.push(MockState::B, MockCall::OnLeave((args.cloned(), *context)));
+        }
+    }
+    impl State for C {
+        fn on_enter(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            MOCK.push(MockState::C, MockCall::OnEnter((args.cloned(), *context)));
+        }
+
+        fn on_update(
+            &mut self,
+            args: Option<&mut Self::Args>,
+            context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            MOCK.push(MockState::C, MockCall::OnUpdate((args.cloned(), *context)));
+            None
+        }
+
+        fn on_leave(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            MOCK.push(MockState::C, MockCall::OnLeave((args.cloned(), *context)));
+        }
+    }
+
+    #[StateEnum]
+    #[derive(Debug)]
+    enum TestDerive {
+        A2,
+    }
+    impl Machine for TestDerive {}
+    impl State for A2 {}
+
+    #[StateEnum(context = Arc<Mutex<MockInner>>)]
+    enum TestWithComplexContext {
+        A3,
+    }
+    impl Machine for TestWithComplexContext {}
+    impl State for A3 {}
+
+    #[StateEnum(context = for<T> Option<T> where T: std::fmt::Debug)]
+    enum TestWithGenericWithContext {
+        A4,
+    }
+    impl Machine for TestWithGenericWithContext {}
+    impl<T> State<T> for A4 where T: std::fmt::Debug {}
+
+    #[derive(Debug, Eq, PartialEq)]
+    enum Button {
+        Press,
+        Release,
+    }
+
+    #[StateEnum(context = u32, event = Button)]
+    #[derive(Debug)]
+    enum TestEvent {
+        Released,
+        Pressed,
+    }
+    impl Machine for TestEvent {}
+    impl State for Released {
+        fn on_event(&mut self, event: Button, context: &mut Self::Context) -> Option<Self::States> {
+            *context += 1;
+            (event == Button::Press).then_some(Pressed.into())
+        }
+    }
+    impl State for Pressed {
+        fn on_event(&mut self, event: Button, context: &mut Self::Context) -> Option<Self::States> {
+            *context += 1;
+            (event == Button::Release).then_some(Released.into())
+        }
+    }
+
+    #[StateEnum(context = Vec<&'static str>, args = bool)]
+    enum TestStack {
+        Gameplay,
+        PauseMenu,
+    }
+    impl Machine for TestStack {}
+    impl State for Gameplay {
+        fn on_enter(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            context.push("Gameplay::on_enter");
+        }
+        fn on_update_transition(
+            &mut self,
+            args: Option<&mut Self::Args>,
+            _context: &mut Self::Context,
+        ) -> Option<Transition<Self::States>> {
+            (*args.unwrap()).then_some(Transition::Push(PauseMenu.into()))
+        }
+        fn on_pause(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            context.push("Gameplay::on_pause");
+        }
+        fn on_resume(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            context.push("Gameplay::on_resume");
+        }
+        fn on_leave(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            context.push("Gameplay::on_leave");
+        }
+    }
+    impl State for PauseMenu {
+        fn on_enter(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            context.push("PauseMenu::on_enter");
+        }
+        fn on_update_transition(
+            &mut self,
+            args: Option<&mut Self::Args>,
+            _context: &mut Self::Context,
+        ) -> Option<Transition<Self::States>> {
+            (*args.unwrap()).then_some(Transition::Pop)
+        }
+        fn on_leave(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            context.push("PauseMenu::on_leave");
+        }
+    }
+
+    #[StateEnum]
+    enum TestHistoryChild {
+        Child1,
+        Child2,
+    }
+    impl Machine for TestHistoryChild {}
+    impl State for Child1 {
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            _context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            Some(Child2.into())
+        }
+    }
+    impl State for Child2 {}
+
+    #[StateEnum]
+    enum TestHistory {
+        #[history]
+        WithHistory(StateMachine<TestHistoryChild>),
+        WithoutHistory(StateMachine<TestHistoryChild>),
+        Other,
+    }
+    impl Machine for TestHistory {}
+    impl State for WithHistory {
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            _context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            self.0.update();
+            None
+        }
+    }
+    impl State for WithoutHistory {
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            _context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            self.0.update();
+            None
+        }
+    }
+    impl State for Other {}
+
+    #[test]
+    fn test_macro_edge_cases() {
+        // Note: Just to make sure they can be constructed
+        let sm = StateMachine::new(A2);
+        println!("{:?}", sm);
+        StateMachine::new_context(A3, Arc::new(Mutex::new(MockInner::default())));
+        StateMachine::new_context(A4, Some(()));
+    }
+
+    #[test]
+    fn test_external_transition_and_update() {
+        let mut sm = StateMachine::new_context(A, 0);
+        assert_eq!(
+            MOCK.take(),
+            vec![(MockState::A, MockCall::OnEnter((None, 0)))]
+        );
+        sm.update_args(&mut 0);
+        assert_eq!(
+            MOCK.take(),
+            vec![(MockState::A, MockCall::OnUpdate((Some(0), 0)))]
+        );
+        sm.transition(B);
+        assert_eq!(
+            MOCK.take(),
+            vec![
+                (MockState::A, MockCall::OnLeave((None, 0))),
+                (MockState::B, MockCall::OnEnter((None, 0)))
+            ]
+        );
+        sm.update_args(&mut 0);
+        assert_eq!(
+            MOCK.take(),
+            vec![(MockState::B, MockCall::OnUpdate((Some(0), 0)))]
+        );
+        sm.transition(C);
+        assert_eq!(
+            MOCK.take(),
+            vec![
+                (MockState::B, MockCall::OnLeave((None, 0))),
+                (MockState::C, MockCall::OnEnter((None, 0)))
+            ]
+        );
+        sm.update_args(&mut 0);
+        assert_eq!(
+            MOCK.take(),
+            vec![(MockState::C, MockCall::OnUpdate((Some(0), 0)))]
+        );
+        sm.update_args(&mut 0);
+        assert_eq!(
+            MOCK.take(),
+            vec![(MockState::C, MockCall::OnUpdate((Some(0), 0)))]
+        );
+        drop(sm);
+        let expected = if cfg!(feature = "leave-on-drop") {
+            vec![(MockState::C, MockCall::OnLeave((None, 0)))]
+        } else {
+            vec![]
+        };
+        assert_eq!(MOCK.take(), expected);
+    }
+
+    #[test]
+    fn test_handle_event() {
+        let mut sm = StateMachine::new_context(Released, 0);
+        sm.handle_event(Button::Release);
+        assert!(matches!(sm.state(), TestEvent::Released(_)));
+        sm.handle_event(Button::Press);
+        assert!(matches!(sm.state(), TestEvent::Pressed(_)));
+        sm.handle_event(Button::Press);
+        assert!(matches!(sm.state(), TestEvent::Pressed(_)));
+        sm.handle_event(Button::Release);
+        assert!(matches!(sm.state(), TestEvent::Released(_)));
+        assert_eq!(*sm.context(), 4);
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let mut sm = StateMachine::new_context(Gameplay, vec![]);
+        sm.update_args(&mut false);
+        assert!(sm.stack().is_empty());
+        sm.update_args(&mut true);
+        assert!(matches!(sm.state(), TestStack::PauseMenu(_)));
+        assert!(matches!(sm.stack(), [TestStack::Gameplay(_)]));
+        sm.update_args(&mut false);
+        assert!(matches!(sm.state(), TestStack::PauseMenu(_)));
+        sm.update_args(&mut true);
+        assert!(matches!(sm.state(), TestStack::Gameplay(_)));
+        assert!(sm.stack().is_empty());
+        assert!(!sm.pop());
+        assert_eq!(
+            std::mem::take(sm.context_mut()),
+            vec![
+                "Gameplay::on_enter",
+                "Gameplay::on_pause",
+                "PauseMenu::on_enter",
+                "PauseMenu::on_leave",
+                "Gameplay::on_resume",
+            ]
+        );
+        sm.push(PauseMenu);
+        sm.push(PauseMenu);
+        assert_eq!(sm.stack().len(), 2);
+        sm.transition(Gameplay);
+        assert_eq!(sm.stack().len(), 2);
+        assert_eq!(
+            std::mem::take(sm.context_mut()),
+            vec![
+                "Gameplay::on_pause",
+                "PauseMenu::on_enter",
+                "PauseMenu::on_enter",
+                "PauseMenu::on_leave",
+                "Gameplay::on_enter",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replace_state() {
+        let mut sm = StateMachine::new_context(Gameplay, vec![]);
+        sm.context_mut().clear();
+        let old_state = sm.replace_state(PauseMenu);
+        assert!(matches!(old_state, TestStack::Gameplay(_)));
+        assert!(matches!(sm.state(), TestStack::PauseMenu(_)));
+        assert!(sm.context().is_empty());
+    }
+
+    #[test]
+    fn test_history() {
+        let mut sm = StateMachine::new(WithHistory(StateMachine::new(Child1)));
+        sm.update();
+        sm.transition(Other);
+        sm.transition(WithHistory(StateMachine::new(Child1)));
+        let TestHistory::WithHistory(WithHistory(child)) = sm.state() else {
+            panic!("Unexpected state");
+        };
+        assert!(matches!(child.state(), TestHistoryChild::Child2(_)));
+
+        sm.transition(WithoutHistory(StateMachine::new(Child1)));
+        sm.update();
+        sm.transition(Other);
+        sm.transition(WithoutHistory(StateMachine::new(Child1)));
+        let TestHistory::WithoutHistory(WithoutHistory(child)) = sm.state() else {
+            panic!("Unexpected state");
+        };
+        assert!(matches!(child.state(), TestHistoryChild::Child1(_)));
+
+        sm.clear_history();
+        sm.transition(WithHistory(StateMachine::new(Child1)));
+        let TestHistory::WithHistory(WithHistory(child)) = sm.state() else {
+            panic!("Unexpected state");
+        };
+        assert!(matches!(child.state(), TestHistoryChild::Child1(_)));
+    }
+
+    #[test]
+    fn test_internal_transition_and_update() {
+        let mut sm = StateMachine::new_context(B, 0);
+        assert_eq!(
+            MOCK.take(),
+            vec![(MockState::B, MockCall::OnEnter((None, 0)))]
+        );
+        sm.update_args(&mut 0);
+        assert_eq!(
+            MOCK.take(),
+            vec![(MockState::B, MockCall::OnUpdate((Some(0), 0)))]
+        );
+        MOCK.b_transition(C.into());
+        sm.update_args(&mut 0);
+        assert_eq!(
+            MOCK.take(),
+            vec![
+                (MockState::B, MockCall::OnUpdate((Some(0), 0))),
+                (MockState::B, MockCall::OnLeave((Some(0), 0))),
+                (MockState::C, MockCall::OnEnter((Some(0), 0)))
+            ]
+        );
+        drop(sm);
+        let expected = if cfg!(feature = "leave-on-drop") {
+            vec![(MockState::C, MockCall::OnLeave((None, 0)))]
+        } else {
+            vec![]
+        };
+        assert_eq!(MOCK.take(), expected);
+    }
+
+    #[StateEnum(context = u32, initial = Waiting { ticks: 2 })]
+    enum TestInitial {
+        Waiting { ticks: u32 },
+        Done,
+    }
+    impl Machine for TestInitial {}
+    impl State for Waiting {
+        fn on_enter(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            *context += self.ticks;
+        }
+    }
+    impl State for Done {}
+
+    #[test]
+    fn test_initial_state() {
+        let sm = StateMachine::<TestInitial>::start();
+        assert!(matches!(
+            sm.state(),
+            TestInitial::Waiting(Waiting { ticks: 2 })
+        ));
+        assert_eq!(*sm.context(), 2);
+
+        let sm = StateMachine::<TestInitial>::start_context(5);
+        assert_eq!(*sm.context(), 7);
+
+        let sm = StateMachine::<TestInitial>::default();
+        assert!(matches!(sm.state(), TestInitial::Waiting(_)));
+    }
+
+    #[StateEnum]
+    enum TestTimeout {
+        #[timeout(duration = "20ms", to = Red)]
+        Green,
+        #[timeout(duration = "0.01s", to = Off)]
+        Red,
+        Off,
+    }
+    impl Machine for TestTimeout {}
+    impl State for Green {}
+    impl State for Red {}
+    impl State for Off {}
+
+    #[test]
+    fn test_timeout_update() {
+        let mut sm = StateMachine::new(Green);
+        assert_eq!(
+            sm.state().timeout(),
+            Some(std::time::Duration::from_millis(20))
+        );
+        sm.update();
+        assert!(matches!(sm.state(), TestTimeout::Green(_)));
+        std::thread::sleep(sm.remaining_timeout().unwrap());
+        sm.update();
+        assert!(matches!(sm.state(), TestTimeout::Red(_)));
+        assert_eq!(
+            sm.state().timeout(),
+            Some(std::time::Duration::from_millis(10))
+        );
+    }
+
+    #[test]
+    fn test_timeout_run() {
+        let start = std::time::Instant::now();
+        let mut sm = StateMachine::new(Green);
+        sm.run();
+        assert!(matches!(sm.state(), TestTimeout::Off(_)));
+        assert_eq!(sm.remaining_timeout(), None);
+        assert!(start.elapsed() >= std::time::Duration::from_millis(30));
+    }
+
+    #[StateEnum]
+    enum TestTimeoutDeclined {
+        Pending,
+    }
+    impl Machine for TestTimeoutDeclined {}
+    impl State for Pending {
+        fn timeout(&self) -> Option<std::time::Duration> {
+            Some(std::time::Duration::from_millis(5))
+        }
+    }
+
+    #[test]
+    fn test_timeout_declined() {
+        let mut sm = StateMachine::new(Pending);
+        assert!(sm.remaining_timeout().is_some());
+        sm.run();
+        assert!(matches!(sm.state(), TestTimeoutDeclined::Pending(_)));
+        assert_eq!(sm.remaining_timeout(), None);
+        sm.update();
+        assert_eq!(sm.remaining_timeout(), None);
+    }
+
+    #[StateEnum(context = u32)]
+    enum TestBudget {
+        Ping,
+        Pong,
+        Idle,
+    }
+    impl Machine for TestBudget {}
+    impl State for Ping {
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            *context += 1;
+            Some(Pong.into())
+        }
+    }
+    impl State for Pong {
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            *context += 1;
+            Some(Ping.into())
+        }
+    }
+    impl State for Idle {}
+
+    #[test]
+    fn test_run_for() {
+        let mut sm = StateMachine::new_context(Ping, 0);
+        assert!(!sm.run_for(5));
+        assert_eq!(*sm.context(), 5);
+        assert!(matches!(sm.state(), TestBudget::Pong(_)));
+
+        sm.transition(Idle);
+        assert!(sm.run_for(1));
+        assert!(!sm.run_for(0));
+    }
+
+    #[test]
+    fn test_run_until() {
+        let mut sm = StateMachine::new_context(Ping, 0);
+        let deadline = std::time::Instant::now() + std::time::Duration::from_millis(5);
+        assert!(!sm.run_until(deadline));
+        assert!(std::time::Instant::now() >= deadline);
+        assert!(*sm.context() > 0);
+
+        let mut sm = StateMachine::new(Green);
+        let deadline = std::time::Instant::now() + std::time::Duration::from_millis(5);
+        assert!(!sm.run_until(deadline));
+        assert!(matches!(sm.state(), TestTimeout::Green(_)));
+        assert!(sm.run_until(deadline + std::time::Duration::from_secs(1)));
+        assert!(matches!(sm.state(), TestTimeout::Off(_)));
+    }
+
+    #[test]
+    fn test_run_cancellable() {
+        let token = CancelToken::new();
+        let token2 = token.clone();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            token2.cancel();
+        });
+        let mut sm = StateMachine::new_context(Ping, 0);
+        assert!(!sm.run_cancellable(&token));
+        assert!(*sm.context() > 0);
+        handle.join().unwrap();
+
+        let context = *sm.context();
+        assert!(!sm.run_cancellable(&token));
+        assert_eq!(*sm.context(), context);
+
+        token.reset();
+        sm.transition(Idle);
+        assert!(sm.run_cancellable(&token));
+    }
+
+    #[StateEnum(context = bool, error = String)]
+    enum TestFallible {
+        Connecting,
+        Connected,
+        Failed,
+    }
+    impl Machine for TestFallible {
+        fn on_error(
+            &mut self,
+            _error: String,
+            _context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            Some(Failed.into())
+        }
+    }
+    impl State for Connecting {
+        fn try_on_enter(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            context: &mut Self::Context,
+        ) -> Result<(), String> {
+            match context {
+                true => Ok(()),
+                false => Err("Connection refused".to_string()),
+            }
+        }
+    }
+    impl State for Connected {
+        fn try_on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            context: &mut Self::Context,
+        ) -> Result<Option<Transition<Self::States>>, String> {
+            match context {
+                true => Ok(None),
+                false => Err("Connection lost".to_string()),
+            }
+        }
+    }
+    impl State for Failed {}
+
+    #[test]
+    fn test_try_new_context() {
+        let sm = StateMachine::try_new_context(Connecting, true).unwrap();
+        assert!(matches!(sm.state(), TestFallible::Connecting(_)));
+
+        let Err((context, error)) = StateMachine::try_new_context(Connecting, false) else {
+            panic!("Expected an error");
+        };
+        assert!(!context);
+        assert_eq!(error, "Connection refused");
+    }
+
+    #[test]
+    fn test_enter_error() {
+        let sm = StateMachine::new_context(Connecting, false);
+        assert!(matches!(sm.state(), TestFallible::Failed(_)));
+
+        let mut sm = StateMachine::new_context(Connected, false);
+        sm.transition(Connecting);
+        assert!(matches!(sm.state(), TestFallible::Failed(_)));
+        *sm.context_mut() = true;
+        sm.transition(Connecting);
+        assert!(matches!(sm.state(), TestFallible::Connecting(_)));
+    }
+
+    #[test]
+    fn test_update_error() {
+        let mut sm = StateMachine::new_context(Connected, true);
+        sm.update();
+        assert!(sm.is_in::<Connected>());
+        *sm.context_mut() = false;
+        sm.update();
+        assert!(sm.is_in::<Failed>());
+    }
+
+    #[StateEnum(context = Vec<(&'static str, &'static str)>)]
+    #[ReflectEnum]
+    enum TestEdges {
+        Start,
+        Middle,
+        Overlay,
+    }
+    impl Machine for TestEdges {
+        fn on_transition_from(&self, new_state: &Self::States, context: &mut Self::Context) {
+            context.push((self.name(), new_state.name()));
+        }
+    }
+    impl State for Start {}
+    impl State for Middle {}
+    impl State for Overlay {}
+
+    #[test]
+    fn test_transition_edges() {
+        let mut sm = StateMachine::new_context(Start, vec![]);
+        sm.transition(Middle);
+        sm.push(Overlay);
+        sm.pop();
+        assert_eq!(
+            sm.context(),
+            &[
+                ("Start", "Middle"),
+                ("Middle", "Overlay"),
+                ("Overlay", "Middle")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_request_transition() {
+        let mut sm = StateMachine::new_context(Start, vec![]);
+        let shared = &sm;
+        shared.request_transition(Middle);
+        shared.request_transition(Overlay);
+        assert_eq!(sm.pending_transitions(), 2);
+        assert!(matches!(sm.state(), TestEdges::Start(_)));
+
+        sm.update();
+        assert_eq!(sm.pending_transitions(), 0);
+        assert!(matches!(sm.state(), TestEdges::Overlay(_)));
+        assert_eq!(sm.context(), &[("Start", "Middle"), ("Middle", "Overlay")]);
+    }
+
+    #[StateEnum(initial = Resting, unreachable = deny)]
+    enum TestTable {
+        #[transitions(Busy)]
+        Resting,
+        #[transitions(EndState)]
+        #[timeout(duration = "1h", to = Resting)]
+        Busy,
+        EndState,
+    }
+    impl Machine for TestTable {}
+    impl State for Resting {
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            _context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            Some(Busy.into())
+        }
+    }
+    impl State for Busy {
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            _context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            Some(EndState.into())
+        }
+    }
+
+    #[test]
+    fn test_transition_table() {
+        let mut sm = StateMachine::<TestTable>::start();
+        sm.run();
+        assert!(sm.is_finished());
+    }
+
+    #[StateEnum(context = u32)]
+    enum TestOutcome {
+        Running,
+        #[end]
+        Succeeded,
+        #[end]
+        Aborted,
+    }
+    impl Machine for TestOutcome {}
+    impl State for Running {
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            match context {
+                0 => None,
+                1 => Some(Succeeded.into()),
+                _ => Some(Aborted.into()),
+            }
+        }
+    }
+    impl State for Succeeded {}
+    impl State for Aborted {}
+
+    #[test]
+    fn test_outcome() {
+        let mut sm = StateMachine::new_context(Running, 0);
+        sm.update();
+        assert!(!sm.is_finished());
+        assert_eq!(sm.outcome(), None);
+
+        *sm.context_mut() = 1;
+        sm.update();
+        assert!(sm.is_finished());
+        assert_eq!(sm.outcome(), Some(TestOutcomeOutcome::Succeeded));
+
+        let mut sm = StateMachine::new_context(Running, 2);
+        sm.update();
+        assert_eq!(sm.outcome(), Some(TestOutcomeOutcome::Aborted));
+    }
+
+    #[StateEnum]
+    enum TestManualEnd {
+        Working,
+        Stopped,
+    }
+    impl Machine for TestManualEnd {}
+    impl State for Working {
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            _context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            Some(Stopped.into())
+        }
+    }
+    impl State for Stopped {}
+    impl HasEndState for TestManualEnd {
+        fn is_finished(&self) -> bool {
+            matches!(self, TestManualEnd::Stopped(_))
+        }
+    }
+
+    struct ManualCounter(u32);
+    impl StateTypes for ManualCounter {
+        type States = Self;
+        type Context = ();
+        type Args = ();
+    }
+    impl State for ManualCounter {
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            _context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            self.0 += 1;
+            None
+        }
+    }
+    impl Machine for ManualCounter {}
+
+    #[test]
+    fn test_manual_state_types() {
+        let mut sm = StateMachine::new(ManualCounter(0));
+        sm.update();
+        sm.update();
+        assert_eq!(sm.state().0, 2);
+    }
+
+    #[test]
+    fn test_manual_end_state() {
+        let mut sm = StateMachine::new(Working);
+        assert!(!sm.is_finished());
+        sm.update();
+        assert!(sm.is_finished());
+    }
+
+    #[test]
+    fn test_state_as() {
+        let mut sm = StateMachine::new(Waiting { ticks: 2 });
+        assert!(sm.is_in::<Waiting>());
+        assert!(!sm.is_in::<Done>());
+        assert!(sm.state_as::<Done>().is_none());
+        sm.state_as_mut::<Waiting>().unwrap().ticks = 5;
+        assert_eq!(sm.state_as::<Waiting>().map(|s| s.ticks), Some(5));
+    }
+
+    #[FlagEnum]
+    enum PanicAt {
+        Enter,
+        Update,
+        Leave,
+        Transition,
+    }
+
+    #[StateEnum(context = Flags<PanicAt>)]
+    enum TestPanic {
+        Calm,
+        Panicking,
+    }
+    impl Machine for TestPanic {
+        fn on_transition(&mut self, _new_state: &Self::States, context: &mut Self::Context) {
+            assert!(!context.any(PanicAt::Transition));
+        }
+    }
+    impl State for Calm {}
+    impl State for Panicking {
+        fn on_enter(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            assert!(!context.any(PanicAt::Enter));
+        }
+
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            assert!(!context.any(PanicAt::Update));
+            None
+        }
+
+        fn on_leave(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            assert!(!context.any(PanicAt::Leave));
+        }
+    }
+
+    fn panics(f: impl FnOnce()) -> bool {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).is_err()
+    }
+
+    #[test]
+    fn test_panic_in_on_update() {
+        let mut sm = StateMachine::new_context(Panicking, PanicAt::Update.into());
+        assert!(panics(|| sm.update()));
+        assert!(matches!(sm.state(), TestPanic::Panicking(_)));
+    }
+
+    #[test]
+    fn test_panic_in_on_leave() {
+        let mut sm = StateMachine::new_context(Panicking, PanicAt::Leave.into());
+        assert!(panics(|| sm.transition(Calm)));
+        assert!(matches!(sm.state(), TestPanic::Panicking(_)));
+        sm.context_mut().reset(PanicAt::Leave);
+    }
+
+    #[test]
+    fn test_panic_in_on_transition() {
+        let mut sm = StateMachine::new_context(Panicking, PanicAt::Transition.into());
+        assert!(panics(|| sm.transition(Calm)));
+        assert!(matches!(sm.state(), TestPanic::Panicking(_)));
+    }
+
+    #[test]
+    fn test_panic_in_on_enter() {
+        let mut sm = StateMachine::new_context(Calm, PanicAt::Enter.into());
+        assert!(panics(|| sm.transition(Panicking)));
+        assert!(matches!(sm.state(), TestPanic::Panicking(_)));
+    }
+
+    #[test]
+    fn test_panic_skips_leave_on_drop() {
+        // Without skipping on_leave the second panic would abort the test process
+        assert!(panics(|| {
+            let mut sm = StateMachine::new_context(Panicking, PanicAt::Update | PanicAt::Leave);
+            sm.push(Calm);
+            sm.pop();
+            sm.update();
+        }));
+    }
+
+    #[test]
+    #[cfg(feature = "leave-on-drop")]
+    #[should_panic]
+    fn test_leave_on_drop() {
+        let sm = StateMachine::new_context(Panicking, PanicAt::Leave.into());
+        drop(sm);
+    }
+
+    #[test]
+    fn test_into_parts() {
+        let mut sm = StateMachine::new_context(Calm, PanicAt::Leave.into());
+        sm.push(Panicking);
+        let (state, context) = sm.into_parts();
+        assert!(matches!(state, TestPanic::Panicking(_)));
+        assert!(context == PanicAt::Leave);
+    }
+
+    #[test]
+    fn test_take_context() {
+        let sm = StateMachine::new_context(Calm, PanicAt::Update.into());
+        assert!(sm.take_context() == PanicAt::Update);
+
+        let mut sm = StateMachine::new_context(Calm, PanicAt::Leave.into());
+        sm.push(Panicking);
+        assert_eq!(
+            panics(|| {
+                sm.take_context();
+            }),
+            cfg!(feature = "leave-on-drop")
+        );
+    }
+
+    // The payload EndState struct shadows adar::prelude::EndState, so it's kept in its own module
+    mod end_payload {
+        use crate::{self as adar, prelude::*};
+
+        #[StateEnum(context = u32)]
+        #[derive(Debug)]
+        enum TestPayload {
+            #[transitions(Cancelled, EndState)]
+            Computing,
+            #[end]
+            Cancelled,
+            EndState(Result<u32, String>),
+        }
+        impl Machine for TestPayload {}
+        impl State for Computing {
+            fn on_update(
+                &mut self,
+                _args: Option<&mut Self::Args>,
+                context: &mut Self::Context,
+            ) -> Option<Self::States> {
+                match *context {
+                    0 => Some(EndState(Err("empty".into())).into()),
+                    value => Some(EndState(Ok(value * 2)).into()),
+                }
+            }
+        }
+        impl State for Cancelled {}
+
+        #[test]
+        fn test_end_state_payload() {
+            let mut sm = StateMachine::new_context(Computing, 21);
+            assert!(sm.output().is_none());
+            sm.update();
+            assert_eq!(sm.outcome(), Some(TestPayloadOutcome::EndState));
+            assert_eq!(sm.output(), Some(&Ok(42)));
+            assert_eq!(sm.into_output(), Some(Ok(42)));
+
+            let mut sm = StateMachine::new_context(Computing, 0);
+            sm.update();
+            assert_eq!(sm.into_output(), Some(Err("empty".to_string())));
+
+            let mut sm = StateMachine::new_context(Computing, 1);
+            sm.transition(Cancelled);
+            assert!(sm.is_finished());
+            assert_eq!(sm.into_output(), None);
+            assert!(TestPayload::topology().state("EndState").unwrap().has_data);
+        }
+    }
+
+    mod actions {
+        use crate::{self as adar, prelude::*};
+
+        #[StateEnum(context = Vec<&'static str>)]
+        enum TestActions {
+            #[on_enter(|log| log.push("enter idle"))]
+            #[on_leave(|log| log.push("leave idle"))]
+            Idle,
+            #[on_leave(|log| log.clear())]
+            Busy(u32),
+        }
+        impl Machine for TestActions {}
+        impl State for Idle {}
+        impl State for Busy {
+            fn on_enter(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+                context.push("enter busy");
+            }
+
+            fn on_update(
+                &mut self,
+                _args: Option<&mut Self::Args>,
+                context: &mut Self::Context,
+            ) -> Option<Self::States> {
+                self.0 -= 1;
+                context.push("busy");
+                (self.0 == 0).then_some(Idle.into())
+            }
+
+            fn on_leave(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+                context.push("leave busy");
+            }
+        }
+
+        #[test]
+        fn test_actions() {
+            let mut sm = StateMachine::new_context(Idle, vec![]);
+            sm.transition(Busy(2));
+            assert_eq!(sm.context(), &["enter idle", "leave idle", "enter busy"]);
+            sm.update();
+            assert!(sm.is_in::<Busy>());
+            sm.update();
+            assert_eq!(sm.context(), &["enter idle"]);
+            assert!(sm.is_in::<Idle>());
+        }
+    }
+
+    mod snapshot {
+        use crate::{self as adar, prelude::*};
+        use std::time::Duration;
+
+        #[StateEnum(context = Vec<u32>)]
+        #[derive(Clone, Debug, PartialEq)]
+        enum TestSnapshot {
+            #[timeout(duration = "1h", to = Menu)]
+            Playing(u32),
+            Menu,
+        }
+        impl Machine for TestSnapshot {}
+        impl State for Playing {
+            fn on_update(
+                &mut self,
+                _args: Option<&mut Self::Args>,
+                context: &mut Self::Context,
+            ) -> Option<Self::States> {
+                self.0 += 1;
+                context.push(self.0);
+                None
+            }
+        }
+        impl State for Menu {}
+
+        #[test]
+        fn test_snapshot() {
+            let mut sm = StateMachine::new_context(Playing(0), vec![]);
+            sm.update();
+            let snapshot = sm.snapshot();
+            assert_eq!(snapshot.state(), &TestSnapshot::Playing(Playing(1)));
+            assert_eq!(snapshot.context(), &[1]);
+
+            sm.update();
+            sm.push(Menu);
+            sm.request_transition(Menu);
+            sm.restore(snapshot.clone());
+            assert_eq!(sm.state(), &TestSnapshot::Playing(Playing(1)));
+            assert_eq!(sm.context(), &[1]);
+            assert!(sm.stack().is_empty());
+            assert_eq!(sm.pending_transitions(), 0);
+            assert!(sm.remaining_timeout().unwrap() > Duration::from_secs(3590));
+
+            sm.update();
+            sm.restore(snapshot);
+            assert_eq!(sm.context(), &[1]);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod state_attrs {
+        use crate::{self as adar, prelude::*};
+
+        #[StateEnum(serde)]
+        #[state_attr(derive(Default, PartialEq))]
+        #[derive(Debug)]
+        enum TestAttrs {
+            /// Waits for input.
+            #[transitions(Counting, Disabled)]
+            Waiting,
+            #[state_attr(serde(rename_all = "camelCase"), allow(dead_code))]
+            #[transitions(Waiting)]
+            Counting { max_value: u32 },
+            #[cfg(any())]
+            #[transitions(Waiting)]
+            Disabled,
+        }
+        impl Machine for TestAttrs {}
+        impl State for Waiting {}
+        impl State for Counting {}
+        #[cfg(any())]
+        impl State for Disabled {}
+
+        #[test]
+        fn test_state_attrs() {
+            assert_eq!(Counting::default(), Counting { max_value: 0 });
+            assert_eq!(
+                serde_json::to_string(&Counting { max_value: 3 }).unwrap(),
+                r#"{"maxValue":3}"#
+            );
+
+            let mut sm = StateMachine::new(Waiting);
+            sm.transition(Counting::default());
+            assert_eq!(sm.state().state_name(), "Counting");
+            let edges = [
+                TestAttrsEdge::WaitingToCounting,
+                TestAttrsEdge::CountingToWaiting,
+            ];
+            assert_eq!(TestAttrs::Waiting(Waiting).edge(sm.state()), Some(edges[0]));
+            assert_eq!(TestAttrs::topology().states.len(), 3);
+        }
+    }
+
+    mod variant_args {
+        use crate::{self as adar, prelude::*};
+
+        struct Input {
+            login: LoginArgs,
+            code: Option<Code>,
+        }
+
+        struct LoginArgs {
+            user: &'static str,
+            attempts: u32,
+        }
+
+        impl<'a> From<&'a mut Input> for &'a mut LoginArgs {
+            fn from(input: &'a mut Input) -> Self {
+                &mut input.login
+            }
+        }
+
+        struct Code(u32);
+
+        impl<'a> TryFrom<&'a mut Input> for &'a mut Code {
+            type Error = String;
+
+            fn try_from(input: &'a mut Input) -> Result<Self, Self::Error> {
+                input
+                    .code
+                    .as_mut()
+                    .ok_or_else(|| "missing code".to_string())
+            }
+        }
+
+        #[StateEnum(args = Input, context = Vec<String>, error = String)]
+        enum TestArgs {
+            #[args(LoginArgs)]
+            Login,
+            #[try_args(Code)]
+            Verify,
+            Done,
+            Failed,
+        }
+        impl Machine for TestArgs {
+            fn on_error(
+                &mut self,
+                error: String,
+                context: &mut Self::Context,
+            ) -> Option<Self::States> {
+                context.push(error);
+                Some(Failed.into())
+            }
+        }
+        impl State for Login {
+            fn on_enter(&mut self, args: Option<&mut LoginArgs>, context: &mut Self::Context) {
+                context.push(format!("enter {}", args.map_or("-", |args| args.user)));
+            }
+
+            fn on_update(
+                &mut self,
+                args: Option<&mut LoginArgs>,
+                context: &mut Self::Context,
+            ) -> Option<Self::States> {
+                let args = args?;
+                args.attempts += 1;
+                context.push(format!("login {}", args.user));
+                Some(Verify.into())
+            }
+        }
+        impl State for Verify {
+            fn on_update(
+                &mut self,
+                args: Option<&mut Code>,
+                context: &mut Self::Context,
+            ) -> Option<Self::States> {
+                let code = args?.0;
+                context.push(format!("code {}", code));
+                (code == 42).then_some(Done.into())
+            }
+        }
+        impl State for Done {
+            fn on_enter(&mut self, args: Option<&mut Input>, context: &mut Self::Context) {
+                if let Some(args) = args {
+                    args.code = None;
+                }
+                context.push("done".into());
+            }
+        }
+        impl State for Failed {}
+
+        #[test]
+        fn test_variant_args() {
+            let mut sm = StateMachine::new_context(Login, vec![]);
+            let mut input = Input {
+                login: LoginArgs {
+                    user: "admin",
+                    attempts: 0,
+                },
+                code: Some(Code(7)),
+            };
+            sm.update_args(&mut input);
+            assert!(sm.is_in::<Verify>());
+            assert_eq!(input.login.attempts, 1);
+            sm.update_args(&mut input);
+            assert!(sm.is_in::<Verify>());
+            input.code = Some(Code(42));
+            sm.update_args(&mut input);
+            assert!(sm.is_in::<Done>());
+            assert!(input.code.is_none());
+            assert_eq!(
+                sm.context(),
+                &["enter -", "login admin", "code 7", "code 42", "done"]
+            );
+        }
+
+        #[test]
+        fn test_variant_args_error() {
+            let mut sm = StateMachine::new_context(Verify, vec![]);
+            let mut input = Input {
+                login: LoginArgs {
+                    user: "admin",
+                    attempts: 0,
+                },
+                code: None,
+            };
+            sm.update_args(&mut input);
+            assert!(sm.is_in::<Failed>());
+            assert_eq!(sm.context(), &["missing code"]);
+        }
+    }
+
+    #[allow(dead_code)]
+    mod layout {
+        use crate::{self as adar, prelude::*};
+        use std::{mem::size_of, num::NonZeroU32};
+
+        macro_rules! states {
+            ($($state:ident),*) => {
+                $(impl State for $state {})*
+            };
+        }
+
+        #[StateEnum]
+        enum TestUnits {
+            Unit1,
+            Unit2,
+            Unit3,
+        }
+        enum PlainUnits {
+            _Unit1,
+            _Unit2,
+            _Unit3,
+        }
+        impl Machine for TestUnits {}
+        states!(Unit1, Unit2, Unit3);
+
+        #[StateEnum]
+        enum TestBoxed {
+            Empty,
+            Boxed(Box<u64>),
+        }
+        enum PlainBoxed {
+            _Empty,
+            _Boxed(Box<u64>),
+        }
+        impl Machine for TestBoxed {}
+        states!(Empty, Boxed);
+
+        #[StateEnum]
+        enum TestFlag {
+            Unset,
+            Set(bool),
+        }
+        enum PlainFlag {
+            _Unset,
+            _Set(bool),
+        }
+        impl Machine for TestFlag {}
+        states!(Unset, Set);
+
+        #[StateEnum]
+        enum TestMixed {
+            Vacant,
+            Counter { value: NonZeroU32 },
+            Named(&'static str),
+            EndState,
+        }
+        enum PlainMixed {
+            _Vacant,
+            _Counter { _value: NonZeroU32 },
+            _Named(&'static str),
+            _EndState,
+        }
+        impl Machine for TestMixed {}
+        states!(Vacant, Counter, Named);
+
+        #[StateEnum]
+        enum TestPadded {
+            Blank,
+            Padded(&'static str, u8),
+            Filled(u64),
+        }
+        enum PlainPadded {
+            _Blank,
+            _Padded(&'static str, u8),
+            _Filled(u64),
+        }
+        impl Machine for TestPadded {}
+        states!(Blank, Padded, Filled);
+
+        #[StateEnum]
+        enum TestSingle {
+            Only,
+        }
+        impl Machine for TestSingle {}
+        states!(Only);
+
+        // The generated state structs must not cost more than the variants of a hand-written enum,
+        // since machines are often stored per entity in bulk.
+        #[test]
+        fn test_layout() {
+            assert_eq!(size_of::<EndState>(), 0);
+            assert_eq!(size_of::<Unit1>(), 0);
+            assert_eq!(size_of::<TestUnits>(), size_of::<PlainUnits>());
+            assert_eq!(size_of::<TestUnits>(), 1);
+            assert_eq!(size_of::<Option<TestUnits>>(), 1);
+            assert_eq!(size_of::<TestBoxed>(), size_of::<PlainBoxed>());
+            assert_eq!(size_of::<TestBoxed>(), size_of::<Box<u64>>());
+            assert_eq!(size_of::<TestFlag>(), size_of::<PlainFlag>());
+            assert_eq!(size_of::<TestFlag>(), 1);
+            assert_eq!(size_of::<TestMixed>(), size_of::<PlainMixed>());
+            assert_eq!(
+                size_of::<Option<TestMixed>>(),
+                size_of::<Option<PlainMixed>>()
+            );
+            assert_eq!(size_of::<TestSingle>(), 0);
+            // Known limitation: the discriminant can't be stored in the padding of a state struct with multiple fields.
+            assert!(
+                size_of::<TestPadded>() <= size_of::<PlainPadded>() + align_of::<PlainPadded>()
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod serialization {
+        use crate::{self as adar, prelude::*};
+
+        #[StateEnum(context = u32, serde)]
+        #[derive(Debug)]
+        enum TestSerde {
+            Idle,
+            Running { ticks: u32 },
+            Menu,
+        }
+        impl Machine for TestSerde {}
+        impl State for Idle {}
+        impl State for Running {
+            fn on_enter(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+                *context += 1;
+            }
+        }
+        impl State for Menu {}
+
+        #[test]
+        fn test_serde() {
+            let mut sm = StateMachine::new_context(Idle, 0);
+            sm.transition(Running { ticks: 5 });
+            sm.push(Menu);
+            let serialized = serde_json::to_string(&sm).unwrap();
+            assert_eq!(
+                serialized,
+                r#"{"state":{"Menu":null},"stack":[{"Running":{"ticks":5}}],"history":[],"context":1}"#
+            );
+
+            let mut deserialized =
+                serde_json::from_str::<StateMachine<TestSerde>>(&serialized).unwrap();
+            assert!(matches!(deserialized.state(), TestSerde::Menu(_)));
+            assert_eq!(*deserialized.context(), 1);
+            assert!(deserialized.pop());
+            assert!(matches!(
+                deserialized.state(),
+                TestSerde::Running(Running { ticks: 5 })
+            ));
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    mod tracing_events {
+        use crate::{self as adar, prelude::*};
+        use std::{
+            fmt::{Debug, Write},
+            sync::{Arc, Mutex},
+        };
+        use tracing::{
+            field::{Field, Visit},
+            span, Metadata, Subscriber,
+        };
+
+        #[StateEnum]
+        #[ReflectEnum]
+        enum TestTracing {
+            Dormant,
+            Alert,
+        }
+        impl Machine for TestTracing {}
+        impl State for Dormant {
+            fn on_update(
+                &mut self,
+                _args: Option<&mut Self::Args>,
+                _context: &mut Self::Context,
+            ) -> Option<Self::States> {
+                Some(Alert.into())
+            }
+        }
+        impl State for Alert {}
+
+        #[derive(Default)]
+        struct TestSubscriber {
+            records: Arc<Mutex<Vec<String>>>,
+        }
+
+        struct TestVisitor(String);
+
+        impl Visit for TestVisitor {
+            fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+                write!(self.0, " {}={:?}", field.name(), value).unwrap();
+            }
+        }
+
+        impl Subscriber for TestSubscriber {
+            fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+                let mut visitor = TestVisitor(format!("span {}", span.metadata().name()));
+                span.record(&mut visitor);
+                self.records.lock().unwrap().push(visitor.0);
+                span::Id::from_u64(1)
+            }
+
+            fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+            fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+            fn event(&self, event: &tracing::Event<'_>) {
+                let mut visitor = TestVisitor(event.metadata().level().to_string());
+                event.record(&mut visitor);
+                self.records.lock().unwrap().push(visitor.0);
+            }
+
+            fn enter(&self, _span: &span::Id) {}
+
+            fn exit(&self, _span: &span::Id) {}
+        }
+
+        #[test]
+        fn test_tracing() {
+            let subscriber = TestSubscriber::default();
+            let records = subscriber.records.clone();
+            tracing::subscriber::with_default(subscriber, || {
+                let mut sm = StateMachine::new(Dormant);
+                sm.update();
+                sm.push(Dormant);
+                sm.pop();
+            });
+            assert_eq!(
+                *records.lock().unwrap(),
+                [
+                    "TRACE message=enter state=\"Dormant\"",
+                    "span update state=\"Dormant\"",
+                    "span transition from=\"Dormant\" to=\"Alert\" kind=\"to\"",
+                    "DEBUG message=transition from=\"Dormant\" to=\"Alert\" kind=\"to\"",
+                    "TRACE message=leave state=\"Dormant\"",
+                    "TRACE message=enter state=\"Alert\"",
+                    "span transition from=\"Alert\" to=\"Dormant\" kind=\"push\"",
+                    "DEBUG message=transition from=\"Alert\" to=\"Dormant\" kind=\"push\"",
+                    "TRACE message=pause state=\"Alert\"",
+                    "TRACE message=enter state=\"Dormant\"",
+                    "span transition from=\"Dormant\" to=\"Alert\" kind=\"pop\"",
+                    "DEBUG message=transition from=\"Dormant\" to=\"Alert\" kind=\"pop\"",
+                    "TRACE message=leave state=\"Dormant\"",
+                    "TRACE message=resume state=\"Alert\"",
+                    "TRACE message=leave state=\"Alert\"",
+                ]
+            );
+        }
+    }
+}
@@ -0,0 +1,34 @@
+mod builder;
+mod cancel;
+mod dyn_machine;
+mod feed;
+mod gate;
+mod inspector;
+mod lens;
+mod machine;
+#[cfg(feature = "registry")]
+mod observer;
+mod pool;
+mod region;
+mod replay;
+#[cfg(feature = "registry")]
+mod scheduler;
+mod shared;
+mod topology;
+
+pub use builder::*;
+pub use cancel::*;
+pub use dyn_machine::*;
+pub use gate::*;
+pub use inspector::*;
+pub use lens::*;
+pub use machine::*;
+#[cfg(feature = "registry")]
+pub use observer::*;
+pub use pool::*;
+pub use region::*;
+pub use replay::*;
+#[cfg(feature = "registry")]
+pub use scheduler::*;
+pub use shared::*;
+pub use topology::*;
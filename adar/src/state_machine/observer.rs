@@ -0,0 +1,235 @@
+use crate::{
+    enums::ReflectEnum,
+    state_machine::{HasEvent, Machine, State, StateMachine, StateTypes},
+};
+use adar_registry::prelude::{Entry, Event, EventObserver};
+use std::sync::{Arc, Mutex};
+
+/// Name of a state. See [`ReflectEnum::name()`].
+pub type StateName = &'static str;
+
+/// Event dispatched on every transition of a [`StateMachine`] with the names of the old and the new state.
+pub(crate) struct TransitionEvent<S> {
+    event: Event<(StateName, StateName)>,
+    name: fn(&S) -> StateName,
+}
+
+impl<S> TransitionEvent<S> {
+    pub(crate) fn dispatch(&self, old_state: &S, new_state: &S) {
+        self.event
+            .dispatch(((self.name)(old_state), (self.name)(new_state)));
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + ReflectEnum,
+{
+    /// Gets the event which is dispatched on every transition (including pushes and pops)
+    /// with the names of the old and the new state. The event is created on first use.
+    pub fn transition_event(&mut self) -> &Event<(StateName, StateName)> {
+        &self
+            .transition_event
+            .get_or_insert_with(|| {
+                Box::new(TransitionEvent {
+                    event: Event::new(),
+                    name: S::name,
+                })
+            })
+            .event
+    }
+
+    /// Registers an observer which is notified on every transition. See [`StateMachine::transition_event()`].
+    ///
+    /// # Returns
+    /// [`Entry`] which controls the lifetime of the observer.
+    #[must_use = "Entry will be immediately revoked if not used"]
+    pub fn register_observer<O>(&mut self, observer: O) -> Entry
+    where
+        O: EventObserver<(StateName, StateName)> + 'static,
+    {
+        self.transition_event().register_observer(observer)
+    }
+}
+
+/// Drives a shared [`StateMachine`] from [`Event`] dispatches. Each dispatch calls
+/// [`StateMachine::update_args()`] with the converted event arguments.
+pub struct MachineObserver<M> {
+    machine: Arc<Mutex<M>>,
+}
+
+/// Drives a shared [`StateMachine`] from [`Event`] dispatches. Each dispatch calls
+/// [`StateMachine::handle_event()`] with the converted event arguments.
+pub struct MachineEventObserver<M> {
+    machine: Arc<Mutex<M>>,
+}
+
+impl<M> MachineObserver<M> {
+    pub fn new(machine: Arc<Mutex<M>>) -> Self {
+        Self { machine }
+    }
+
+    /// Registers a [`MachineObserver`] to the event.
+    ///
+    /// # Returns
+    /// [`Entry`] which controls the lifetime of the observer.
+    #[must_use = "Entry will be immediately revoked if not used"]
+    pub fn register<Args>(event: &Event<Args>, machine: Arc<Mutex<M>>) -> Entry
+    where
+        Args: Send + Sync + 'static,
+        Self: EventObserver<Args> + 'static,
+    {
+        event.register_observer(Self::new(machine))
+    }
+}
+
+impl<M> MachineEventObserver<M> {
+    pub fn new(machine: Arc<Mutex<M>>) -> Self {
+        Self { machine }
+    }
+
+    /// Registers a [`MachineEventObserver`] to the event.
+    ///
+    /// # Returns
+    /// [`Entry`] which controls the lifetime of the observer.
+    #[must_use = "Entry will be immediately revoked if not used"]
+    pub fn register<Args>(event: &Event<Args>, machine: Arc<Mutex<M>>) -> Entry
+    where
+        Args: Send + Sync + 'static,
+        Self: EventObserver<Args> + 'static,
+    {
+        event.register_observer(Self::new(machine))
+    }
+}
+
+impl<Args, S, P1, P2, P3, P4, P5, P6, P7, P8> EventObserver<Args>
+    for MachineObserver<StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>,
+    StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>: Send,
+    Args: Clone + Into<S::Args>,
+{
+    fn notify(&self, args: &Args) {
+        self.machine
+            .lock()
+            .unwrap()
+            .update_args(&mut args.clone().into());
+    }
+}
+
+impl<Args, S, P1, P2, P3, P4, P5, P6, P7, P8> EventObserver<Args>
+    for MachineEventObserver<StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + HasEvent<P1, P2, P3, P4, P5, P6, P7, P8>,
+    StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>: Send,
+    Args: Clone + Into<S::Event>,
+{
+    fn notify(&self, args: &Args) {
+        self.machine
+            .lock()
+            .unwrap()
+            .handle_event(args.clone().into());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+    use adar_registry::prelude::*;
+    use std::sync::{Arc, Mutex};
+
+    #[StateEnum(context = u32, args = u32, event = bool)]
+    enum TestObserver {
+        Counting,
+        Stopped,
+    }
+    impl Machine for TestObserver {}
+    impl State for Counting {
+        fn on_update(
+            &mut self,
+            args: Option<&mut Self::Args>,
+            context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            *context += *args.unwrap();
+            None
+        }
+
+        fn on_event(&mut self, event: bool, _context: &mut Self::Context) -> Option<Self::States> {
+            event.then_some(Stopped.into())
+        }
+    }
+    impl State for Stopped {}
+
+    #[test]
+    fn test_machine_observer() {
+        let machine = Arc::new(Mutex::new(StateMachine::new_context(Counting, 0)));
+        let event = Event::<u32>::new();
+        let entry = MachineObserver::register(&event, machine.clone());
+        event.dispatch(2);
+        event.dispatch(3);
+        assert_eq!(*machine.lock().unwrap().context(), 5);
+        drop(entry);
+        event.dispatch(3);
+        assert_eq!(*machine.lock().unwrap().context(), 5);
+    }
+
+    #[StateEnum]
+    #[ReflectEnum]
+    enum TestTransitions {
+        First,
+        Second,
+    }
+    impl Machine for TestTransitions {}
+    impl State for First {}
+    impl State for Second {}
+
+    #[test]
+    fn test_register_observer() {
+        let transitions = Arc::new(Mutex::new(vec![]));
+        let mut sm = StateMachine::new(First);
+        let entry = sm.register_observer({
+            let transitions = transitions.clone();
+            move |transition: &(StateName, StateName)| transitions.lock().unwrap().push(*transition)
+        });
+        sm.transition(Second);
+        sm.push(First);
+        sm.pop();
+        assert_eq!(
+            *transitions.lock().unwrap(),
+            [
+                ("First", "Second"),
+                ("Second", "First"),
+                ("First", "Second")
+            ]
+        );
+
+        drop(entry);
+        sm.transition(First);
+        assert_eq!(transitions.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_machine_event_observer() {
+        let machine = Arc::new(Mutex::new(StateMachine::new_context(Counting, 0)));
+        let event = Event::<bool>::new();
+        let _entry = MachineEventObserver::register(&event, machine.clone());
+        event.dispatch(false);
+        assert!(matches!(
+            machine.lock().unwrap().state(),
+            TestObserver::Counting(_)
+        ));
+        event.dispatch(true);
+        assert!(matches!(
+            machine.lock().unwrap().state(),
+            TestObserver::Stopped(_)
+        ));
+    }
+}
@@ -0,0 +1,233 @@
+use crate::state_machine::{InitialState, Machine, State, StateMachine, StateTypes};
+use std::{
+    ops::{Deref, DerefMut},
+    sync::Mutex,
+};
+
+/// Recycles the contexts of finished [`StateMachine`]s, so the allocations they own are reused.
+///
+/// [`MachinePool::acquire()`] returns a machine in the [`InitialState`]. When it's dropped the machine is left
+/// like a regular [`StateMachine`] and its context goes back to the pool.
+pub struct MachinePool<S, P1 = (), P2 = (), P3 = (), P4 = (), P5 = (), P6 = (), P7 = (), P8 = ()>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + InitialState,
+{
+    contexts: Mutex<Vec<S::Context>>,
+    create: Box<dyn Fn() -> S::Context + Send + Sync>,
+    #[allow(clippy::type_complexity)]
+    reset: Box<dyn Fn(&mut S::Context) + Send + Sync>,
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> MachinePool<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + InitialState,
+{
+    /// Creates an empty pool.
+    ///
+    /// `create` is called when the pool is empty and a new context is needed. \
+    /// `reset` is called on a recycled context before the machine enters the initial state again.
+    pub fn new<C, R>(create: C, reset: R) -> Self
+    where
+        C: Fn() -> S::Context + Send + Sync + 'static,
+        R: Fn(&mut S::Context) + Send + Sync + 'static,
+    {
+        Self {
+            contexts: Mutex::new(Vec::new()),
+            create: Box::new(create),
+            reset: Box::new(reset),
+        }
+    }
+
+    /// Acquires a machine in the initial state. A recycled context is used if available.
+    ///
+    /// # Returns
+    /// [`PooledMachine`] which returns the context to the pool when it's dropped.
+    pub fn acquire(&self) -> PooledMachine<'_, S, P1, P2, P3, P4, P5, P6, P7, P8> {
+        let recycled = self.contexts.lock().unwrap().pop();
+        let context = match recycled {
+            Some(mut context) => {
+                (self.reset)(&mut context);
+                context
+            }
+            None => (self.create)(),
+        };
+        PooledMachine {
+            pool: self,
+            machine: Some(StateMachine::start_context(context)),
+        }
+    }
+
+    /// Returns the number of contexts waiting to be recycled.
+    pub fn idle(&self) -> usize {
+        self.contexts.lock().unwrap().len()
+    }
+
+    /// Drops all the contexts waiting to be recycled.
+    pub fn clear(&self) {
+        self.contexts.lock().unwrap().clear();
+    }
+
+    fn release(&self, machine: StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>) {
+        let context = machine.take_context();
+        self.contexts.lock().unwrap().push(context);
+    }
+}
+
+/// Machine acquired from a [`MachinePool`]. See [`MachinePool::acquire()`].
+pub struct PooledMachine<
+    'a,
+    S,
+    P1 = (),
+    P2 = (),
+    P3 = (),
+    P4 = (),
+    P5 = (),
+    P6 = (),
+    P7 = (),
+    P8 = (),
+> where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + InitialState,
+{
+    pool: &'a MachinePool<S, P1, P2, P3, P4, P5, P6, P7, P8>,
+    #[allow(clippy::type_complexity)]
+    machine: Option<StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>>,
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> PooledMachine<'_, S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + InitialState,
+{
+    /// Takes the machine out of the pool. Its context will not be recycled.
+    pub fn detach(mut self) -> StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8> {
+        self.machine.take().unwrap()
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> Deref
+    for PooledMachine<'_, S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + InitialState,
+{
+    type Target = StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>;
+
+    fn deref(&self) -> &Self::Target {
+        self.machine.as_ref().unwrap()
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> DerefMut
+    for PooledMachine<'_, S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + InitialState,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.machine.as_mut().unwrap()
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> Drop
+    for PooledMachine<'_, S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + InitialState,
+{
+    fn drop(&mut self) {
+        if let Some(machine) = self.machine.take() {
+            self.pool.release(machine);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+
+    #[StateEnum(context = Vec<u32>, initial = Collecting)]
+    enum TestPool {
+        Collecting,
+        Done,
+    }
+    impl Machine for TestPool {}
+    impl State for Collecting {
+        fn on_enter(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            context.push(1);
+        }
+
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            context.push(2);
+            Some(Done.into())
+        }
+
+        fn on_leave(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            context.push(3);
+        }
+    }
+    impl State for Done {}
+
+    #[test]
+    fn test_pool_recycle() {
+        let pool = MachinePool::<TestPool>::new(|| Vec::with_capacity(16), Vec::clear);
+        assert_eq!(pool.idle(), 0);
+
+        let mut machine = pool.acquire();
+        machine.update();
+        assert!(matches!(machine.state(), TestPool::Done(_)));
+        assert_eq!(machine.context(), &[1, 2, 3]);
+        let allocation = machine.context().as_ptr();
+        drop(machine);
+        assert_eq!(pool.idle(), 1);
+
+        let machine = pool.acquire();
+        assert_eq!(pool.idle(), 0);
+        assert!(matches!(machine.state(), TestPool::Collecting(_)));
+        assert_eq!(machine.context(), &[1]);
+        assert_eq!(machine.context().as_ptr(), allocation);
+
+        let second = pool.acquire();
+        drop(machine);
+        drop(second);
+        assert_eq!(pool.idle(), 2);
+        pool.clear();
+        assert_eq!(pool.idle(), 0);
+    }
+
+    #[test]
+    fn test_pool_detach() {
+        let pool = MachinePool::<TestPool>::new(Vec::new, Vec::clear);
+        let machine = pool.acquire().detach();
+        assert!(matches!(machine.state(), TestPool::Collecting(_)));
+        assert_eq!(pool.idle(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "leave-on-drop")]
+    fn test_pool_leave_on_release() {
+        let pool = MachinePool::<TestPool>::new(Vec::new, |_| {});
+        drop(pool.acquire());
+        assert_eq!(pool.acquire().context(), &[1, 3, 1]);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::state_machine::{HasEndState, Machine, State, StateMachine, StateTypes, UnitType};
+
+/// Orthogonal region of a composite state, which runs independently of the other regions.
+/// Implemented by [`StateMachine`]s with end states.
+pub trait Region {
+    /// Updates the region once.
+    fn update_region(&mut self);
+    /// Returns `true` if the region reached an end state.
+    fn is_region_finished(&self) -> bool;
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> Region for StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + HasEndState,
+    S::Args: UnitType,
+{
+    fn update_region(&mut self) {
+        self.update();
+    }
+
+    fn is_region_finished(&self) -> bool {
+        self.is_finished()
+    }
+}
+
+/// Composite state whose fields are [`Region`]s.
+/// Generated for the variants marked with the `#[regions]` or `#[regions(then = NextState)]` attribute
+/// of a [`crate::macros::StateEnum`].
+///
+/// The regions are updated before each update of the composite state. Once all of them are finished
+/// and the state doesn't request a transition itself, the machine transitions to the state given by `then`.
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+///
+/// #[StateEnum]
+/// enum Download {
+///     Downloading(u32),
+///     EndState,
+/// }
+/// impl Machine for Download {}
+/// impl State for Downloading {
+///     fn on_update(&mut self, _args: Option<&mut Self::Args>, _context: &mut Self::Context) -> Option<Self::States> {
+///         self.0 -= 1;
+///         (self.0 == 0).then_some(EndState.into())
+///     }
+/// }
+///
+/// #[StateEnum]
+/// #[allow(clippy::large_enum_variant)]
+/// enum Installer {
+///     #[regions(then = EndState)]
+///     Fetching {
+///         data: StateMachine<Download>,
+///         signature: StateMachine<Download>,
+///     },
+///     EndState,
+/// }
+/// impl Machine for Installer {}
+/// impl State for Fetching {}
+///
+/// let mut sm = StateMachine::new(Fetching {
+///     data: StateMachine::new(Downloading(3)),
+///     signature: StateMachine::new(Downloading(1)),
+/// });
+/// let mut ticks = 0;
+/// while !sm.is_finished() {
+///     sm.update();
+///     ticks += 1;
+/// }
+/// assert_eq!(ticks, 3);
+/// ```
+pub trait Regions {
+    /// Updates all the regions once.
+    fn update_regions(&mut self);
+    /// Returns `true` if all the regions are finished.
+    fn regions_finished(&self) -> bool;
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+
+    #[StateEnum]
+    enum TestRegionA {
+        Counting(u32),
+        EndState,
+    }
+    impl Machine for TestRegionA {}
+    impl State for Counting {
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            _context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            self.0 += 1;
+            (self.0 >= 3).then_some(EndState.into())
+        }
+    }
+
+    #[StateEnum]
+    enum TestRegionB {
+        Blinking,
+        #[end]
+        Lit,
+    }
+    impl Machine for TestRegionB {}
+    impl State for Blinking {
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            _context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            Some(Lit.into())
+        }
+    }
+    impl State for Lit {}
+
+    #[StateEnum(context = u32)]
+    #[allow(clippy::large_enum_variant)]
+    enum TestRegions {
+        #[regions(then = Joined)]
+        Forked(StateMachine<TestRegionA>, StateMachine<TestRegionB>),
+        #[regions]
+        Manual { a: StateMachine<TestRegionA> },
+        #[end]
+        Joined,
+    }
+    impl Machine for TestRegions {}
+    impl State for Forked {
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            *context += 1;
+            None
+        }
+    }
+    impl State for Manual {
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            _context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            self.regions_finished().then_some(Joined.into())
+        }
+    }
+    impl State for Joined {}
+
+    #[test]
+    fn test_regions() {
+        let mut sm = StateMachine::new(Forked(
+            StateMachine::new(Counting(0)),
+            StateMachine::new(Blinking),
+        ));
+        sm.update();
+        let forked = sm.state_as::<Forked>().unwrap();
+        assert!(!forked.regions_finished());
+        assert!(forked.1.is_finished());
+        sm.update();
+        assert!(sm.is_in::<Forked>());
+        sm.update();
+        assert!(sm.is_in::<Joined>());
+        assert_eq!(*sm.context(), 3);
+
+        let mut sm = StateMachine::new(Manual {
+            a: StateMachine::new(Counting(1)),
+        });
+        sm.update();
+        assert!(sm.is_in::<Manual>());
+        sm.update();
+        assert!(sm.is_in::<Joined>());
+    }
+}
@@ -0,0 +1,236 @@
+use crate::state_machine::{Machine, State, StateMachine, StateTypes, UnitType};
+
+/// Call recorded by a [`Recorder`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReplayStep<S, Args> {
+    /// [`StateMachine::update_args()`] with the arguments as they were before the update.
+    Update(Args),
+    /// [`StateMachine::transition()`] with the new state.
+    Transition(S),
+}
+
+/// Calls recorded by a [`Recorder`], which can be fed back to a machine with [`ReplayLog::replay()`].
+/// With the `serde` feature the log can be saved, e.g. to reproduce a bug in a test.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReplayLog<S, Args> {
+    steps: Vec<ReplayStep<S, Args>>,
+}
+
+impl<S, Args> Default for ReplayLog<S, Args> {
+    fn default() -> Self {
+        Self { steps: Vec::new() }
+    }
+}
+
+impl<S, Args> ReplayLog<S, Args> {
+    /// Gets the recorded calls in order.
+    pub fn steps(&self) -> &[ReplayStep<S, Args>] {
+        &self.steps
+    }
+
+    /// Returns the number of recorded calls.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Returns `true` if no calls were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Feeds the recorded calls to the machine in order. The machine should be created the same way
+    /// as the recorded one, so it behaves deterministically.
+    pub fn replay<P1, P2, P3, P4, P5, P6, P7, P8>(
+        &self,
+        machine: &mut StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>,
+    ) where
+        S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+            + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+            + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S, Args = Args>
+            + Clone,
+        Args: Clone,
+    {
+        for step in &self.steps {
+            match step {
+                ReplayStep::Update(args) => machine.update_args(&mut args.clone()),
+                ReplayStep::Transition(state) => machine.transition(state.clone()),
+            }
+        }
+    }
+}
+
+/// Wraps a [`StateMachine`] and records its updates and transitions into a [`ReplayLog`].
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+///
+/// #[StateEnum(args = u32, context = u32)]
+/// #[derive(Clone, Debug)]
+/// enum Protocol {
+///     Receiving,
+///     Closed,
+/// }
+/// impl Machine for Protocol {}
+/// impl State for Receiving {
+///     fn on_update(&mut self, args: Option<&mut u32>, context: &mut u32) -> Option<Protocol> {
+///         *context += *args?;
+///         None
+///     }
+/// }
+/// impl State for Closed {}
+///
+/// let mut recorder = Recorder::new(StateMachine::new_context(Receiving, 0));
+/// recorder.update_args(&mut 3);
+/// recorder.update_args(&mut 4);
+/// recorder.transition(Closed);
+/// let (machine, log) = recorder.into_parts();
+/// assert_eq!(log.len(), 3);
+///
+/// let mut replayed = StateMachine::new_context(Receiving, 0);
+/// log.replay(&mut replayed);
+/// assert_eq!(replayed.context(), machine.context());
+/// assert!(replayed.is_in::<Closed>());
+/// ```
+pub struct Recorder<S, P1 = (), P2 = (), P3 = (), P4 = (), P5 = (), P6 = (), P7 = (), P8 = ()>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>,
+{
+    machine: StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>,
+    log: ReplayLog<S, S::Args>,
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> Recorder<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + Clone,
+    S::Args: Clone,
+{
+    /// Starts recording the calls of the machine.
+    pub fn new(machine: StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>) -> Self {
+        Self {
+            machine,
+            log: ReplayLog::default(),
+        }
+    }
+
+    /// Records and calls [`StateMachine::update_args()`].
+    pub fn update_args(&mut self, args: &mut S::Args) {
+        self.log.steps.push(ReplayStep::Update(args.clone()));
+        self.machine.update_args(args);
+    }
+
+    /// Records and calls [`StateMachine::transition()`].
+    pub fn transition(&mut self, new_state: impl Into<S>) {
+        let new_state = new_state.into();
+        self.log
+            .steps
+            .push(ReplayStep::Transition(new_state.clone()));
+        self.machine.transition(new_state);
+    }
+
+    /// Gets the recorded machine.
+    pub fn machine(&self) -> &StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8> {
+        &self.machine
+    }
+
+    /// Gets the calls recorded so far.
+    pub fn log(&self) -> &ReplayLog<S, S::Args> {
+        &self.log
+    }
+
+    /// Stops recording.
+    #[allow(clippy::type_complexity)]
+    pub fn into_parts(
+        self,
+    ) -> (
+        StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>,
+        ReplayLog<S, S::Args>,
+    ) {
+        (self.machine, self.log)
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> Recorder<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>
+        + Clone,
+    S::Args: UnitType + Clone,
+{
+    /// Records and calls [`StateMachine::update()`].
+    pub fn update(&mut self) {
+        self.update_args(&mut S::Args::unit());
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test {
+    use crate::{self as adar, prelude::*};
+
+    #[StateEnum(serde, args = String, context = Vec<String>)]
+    #[derive(Clone, Debug, PartialEq)]
+    enum Handshake {
+        Hello,
+        Auth { attempts: u32 },
+        Ready,
+    }
+    impl Machine for Handshake {}
+    impl State for Hello {
+        fn on_update(
+            &mut self,
+            args: Option<&mut Self::Args>,
+            context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            context.push(args?.clone());
+            Some(Auth { attempts: 0 }.into())
+        }
+    }
+    impl State for Auth {
+        fn on_update(
+            &mut self,
+            args: Option<&mut Self::Args>,
+            context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            let args = args?;
+            self.attempts += 1;
+            context.push(format!("{} #{}", args, self.attempts));
+            args.clear();
+            None
+        }
+    }
+    impl State for Ready {}
+
+    #[test]
+    fn test_replay() {
+        let mut recorder = Recorder::new(StateMachine::new_context(Hello, vec![]));
+        recorder.update_args(&mut "hello".to_string());
+        let mut password = "secret".to_string();
+        recorder.update_args(&mut password);
+        assert!(password.is_empty());
+        recorder.update_args(&mut "retry".to_string());
+        recorder.transition(Ready);
+        assert_eq!(
+            recorder.log().steps()[1],
+            ReplayStep::Update("secret".to_string())
+        );
+
+        let (machine, log) = recorder.into_parts();
+        let saved = serde_json::to_string(&log).unwrap();
+        let log: ReplayLog<Handshake, String> = serde_json::from_str(&saved).unwrap();
+        assert_eq!(log.len(), 4);
+
+        let mut replayed = StateMachine::new_context(Hello, vec![]);
+        log.replay(&mut replayed);
+        assert_eq!(replayed.context(), &["hello", "secret #1", "retry #2"]);
+        assert_eq!(replayed.context(), machine.context());
+        assert_eq!(replayed.state(), machine.state());
+    }
+}
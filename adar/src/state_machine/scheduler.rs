@@ -0,0 +1,139 @@
+use crate::state_machine::Region;
+use adar_registry::prelude::{Entry, Registry};
+
+/// Drives many independent machines with a single [`MachineScheduler::tick()`].
+///
+/// Any [`Region`] can be registered, which includes every [`crate::state_machine::StateMachine`] with end states
+/// and without update arguments. Machines are unregistered automatically once they are finished, or when
+/// the [`Entry`] returned by [`MachineScheduler::register()`] is dropped.
+///
+/// The scheduler can be cloned, the clones share the same machines.
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+///
+/// #[StateEnum]
+/// enum Job {
+///     Working(u32),
+///     EndState,
+/// }
+/// impl Machine for Job {}
+/// impl State for Working {
+///     fn on_update(&mut self, _args: Option<&mut ()>, _context: &mut ()) -> Option<Job> {
+///         self.0 -= 1;
+///         (self.0 == 0).then_some(EndState.into())
+///     }
+/// }
+///
+/// let scheduler = MachineScheduler::new();
+/// let _short = scheduler.register(StateMachine::new(Working(1)));
+/// let _long = scheduler.register(StateMachine::new(Working(2)));
+///
+/// assert_eq!(scheduler.tick(), 1);
+/// assert_eq!(scheduler.len(), 1);
+/// assert_eq!(scheduler.tick(), 1);
+/// assert!(scheduler.is_empty());
+/// ```
+#[derive(Clone, Default)]
+pub struct MachineScheduler {
+    machines: Registry<Box<dyn Region + Send + Sync>>,
+}
+
+impl MachineScheduler {
+    /// Creates an empty scheduler.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a machine, which is updated by every [`MachineScheduler::tick()`] until it's finished.
+    ///
+    /// # Returns
+    /// [`Entry`] which controls the lifetime of the machine.
+    #[must_use = "Entry will be immediately revoked if not used"]
+    pub fn register<M>(&self, machine: M) -> Entry
+    where
+        M: Region + Send + Sync + 'static,
+    {
+        self.machines.register(Box::new(machine)).as_generic()
+    }
+
+    /// Updates every registered machine once and unregisters the finished ones.
+    ///
+    /// The scheduler is locked during the tick, so the machines must not register into the same scheduler
+    /// from their callbacks.
+    ///
+    /// # Returns
+    /// Number of machines which were finished and unregistered.
+    pub fn tick(&self) -> usize {
+        let mut finished = 0;
+        self.machines.write().retain(|_, machine| {
+            if !machine.is_region_finished() {
+                machine.update_region();
+            }
+            let keep = !machine.is_region_finished();
+            finished += usize::from(!keep);
+            keep
+        });
+        finished
+    }
+
+    /// Returns the number of registered machines.
+    pub fn len(&self) -> usize {
+        self.machines.len()
+    }
+
+    /// Returns `true` if there are no registered machines.
+    pub fn is_empty(&self) -> bool {
+        self.machines.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+    use std::sync::{Arc, Mutex};
+
+    #[StateEnum(context = Arc<Mutex<Vec<&'static str>>>)]
+    enum Task {
+        Running(&'static str, u32),
+        EndState,
+    }
+
+    impl Machine for Task {}
+
+    impl State for Running {
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            _context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            self.1 -= 1;
+            (self.1 == 0).then_some(EndState.into())
+        }
+
+        fn on_leave(&mut self, _args: Option<&mut Self::Args>, context: &mut Self::Context) {
+            context.lock().unwrap().push(self.0);
+        }
+    }
+
+    #[test]
+    fn test_scheduler() {
+        let log = Arc::new(Mutex::new(vec![]));
+        let scheduler = MachineScheduler::new();
+        let _a = scheduler.register(StateMachine::new_context(Running("a", 2), log.clone()));
+        let b = scheduler.register(StateMachine::new_context(Running("b", 5), log.clone()));
+        let _c = scheduler.register(StateMachine::new_context(Running("c", 1), log.clone()));
+        assert_eq!(scheduler.len(), 3);
+
+        assert_eq!(scheduler.tick(), 1);
+        assert_eq!(*log.lock().unwrap(), ["c"]);
+        assert_eq!(scheduler.clone().tick(), 1);
+        assert_eq!(*log.lock().unwrap(), ["c", "a"]);
+        assert_eq!(scheduler.len(), 1);
+
+        drop(b);
+        assert!(scheduler.is_empty());
+        assert_eq!(scheduler.tick(), 0);
+    }
+}
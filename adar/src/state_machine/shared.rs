@@ -0,0 +1,217 @@
+use crate::state_machine::{Machine, State, StateMachine, StateTypes, StateVariant, UnitType};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// [`StateMachine`] which can be cloned and driven from multiple threads.
+///
+/// The clones share the same machine behind an [`Arc<Mutex>`](Mutex). Every method locks the machine for the duration
+/// of the call, so callbacks of the states are never executed concurrently. Use [`SharedStateMachine::lock()`] to
+/// perform multiple operations atomically.
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+///
+/// #[StateEnum]
+/// enum Light {
+///     Off,
+///     On,
+/// }
+/// impl Machine for Light {}
+/// impl State for Off {}
+/// impl State for On {}
+///
+/// let sm = SharedStateMachine::new(StateMachine::new(Off));
+/// let sm2 = sm.clone();
+/// std::thread::spawn(move || sm2.transition(On)).join().unwrap();
+/// assert!(sm.is_in::<On>());
+/// assert_eq!(sm.state_name(), "On");
+/// ```
+pub struct SharedStateMachine<
+    S,
+    P1 = (),
+    P2 = (),
+    P3 = (),
+    P4 = (),
+    P5 = (),
+    P6 = (),
+    P7 = (),
+    P8 = (),
+> where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>,
+{
+    #[allow(clippy::type_complexity)]
+    machine: Arc<Mutex<StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>>>,
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> SharedStateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>,
+{
+    /// Wraps the machine, so it can be shared between threads.
+    pub fn new(machine: StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>) -> Self {
+        Self {
+            machine: Arc::new(Mutex::new(machine)),
+        }
+    }
+
+    /// Locks the machine. The other clones block until the returned guard is dropped.
+    ///
+    /// A poisoned lock is recovered, because the machine is always in a valid state between the callbacks.
+    #[allow(clippy::type_complexity)]
+    pub fn lock(&self) -> MutexGuard<'_, StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>> {
+        self.machine
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Updates the machine. See [`StateMachine::update_args()`].
+    pub fn update_args(&self, args: &mut S::Args) {
+        self.lock().update_args(args);
+    }
+
+    /// Transitions the machine to `new_state`. See [`StateMachine::transition()`].
+    pub fn transition(&self, new_state: impl Into<S>) {
+        self.lock().transition(new_state);
+    }
+
+    /// Queues a transition which is applied by the next update. See [`StateMachine::request_transition()`].
+    pub fn request_transition(&self, new_state: impl Into<S>) {
+        self.lock().request_transition(new_state);
+    }
+
+    /// Returns the name of the current state. See [`State::state_name()`].
+    pub fn state_name(&self) -> &'static str {
+        self.lock().state().state_name()
+    }
+
+    /// Checks whether the current state is `T`. See [`StateMachine::is_in()`].
+    pub fn is_in<T>(&self) -> bool
+    where
+        T: StateVariant<S::States>,
+    {
+        self.lock().is_in::<T>()
+    }
+
+    /// Returns the number of clones sharing this machine.
+    pub fn share_count(&self) -> usize {
+        Arc::strong_count(&self.machine)
+    }
+
+    /// Unwraps the machine.
+    ///
+    /// # Returns
+    /// `Ok` - The machine if this was the last clone \
+    /// `Err` - `self` if the machine is still shared
+    #[allow(clippy::type_complexity)]
+    pub fn try_into_inner(self) -> Result<StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>, Self> {
+        Arc::try_unwrap(self.machine)
+            .map(|machine| {
+                machine
+                    .into_inner()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+            })
+            .map_err(|machine| Self { machine })
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> SharedStateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>,
+    S::Args: UnitType,
+{
+    /// Updates the machine. See [`StateMachine::update()`].
+    pub fn update(&self) {
+        self.lock().update();
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> Clone
+    for SharedStateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            machine: self.machine.clone(),
+        }
+    }
+}
+
+impl<S, P1, P2, P3, P4, P5, P6, P7, P8> From<StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>>
+    for SharedStateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>
+where
+    S: State<P1, P2, P3, P4, P5, P6, P7, P8>
+        + Machine<P1, P2, P3, P4, P5, P6, P7, P8>
+        + StateTypes<P1, P2, P3, P4, P5, P6, P7, P8, States = S>,
+{
+    fn from(machine: StateMachine<S, P1, P2, P3, P4, P5, P6, P7, P8>) -> Self {
+        Self::new(machine)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+    use std::thread;
+
+    #[StateEnum]
+    enum Counter {
+        Counting(u32),
+        Done,
+    }
+
+    impl Machine for Counter {}
+
+    impl State for Counting {
+        fn on_update(
+            &mut self,
+            _args: Option<&mut Self::Args>,
+            _context: &mut Self::Context,
+        ) -> Option<Self::States> {
+            self.0 += 1;
+            (self.0 == 400).then_some(Done.into())
+        }
+    }
+
+    impl State for Done {}
+
+    #[test]
+    fn test_update_from_threads() {
+        fn is_send_sync<T: Send + Sync>() {}
+        is_send_sync::<SharedStateMachine<Counter>>();
+
+        let sm = SharedStateMachine::new(StateMachine::new(Counting(0)));
+        let handles = (0..4)
+            .map(|_| {
+                let sm = sm.clone();
+                thread::spawn(move || {
+                    for _ in 0..100 {
+                        sm.update();
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        assert!(sm.share_count() > 1);
+        handles
+            .into_iter()
+            .for_each(|handle| handle.join().unwrap());
+
+        assert!(sm.is_in::<Done>());
+        assert_eq!(sm.state_name(), "Done");
+
+        let sm2 = sm.clone();
+        let sm = sm.try_into_inner().err().unwrap();
+        drop(sm2);
+        let mut sm = sm.try_into_inner().ok().unwrap();
+        sm.transition(Counting(1));
+        assert_eq!(sm.state_as::<Counting>().unwrap().0, 1);
+    }
+}
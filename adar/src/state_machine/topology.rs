@@ -0,0 +1,250 @@
+/// Static description of the states and the declared transitions of a [`StateEnum`](crate::prelude::StateEnum),
+/// returned by the generated `topology()` function. Useful for tools (editors, visualizers, dashboards)
+/// which need to reflect over a machine without parsing its source.
+///
+/// Transitions are only known if they are declared with the `#[transitions(...)]` or `#[timeout(...)]` attributes.
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+///
+/// #[StateEnum(initial = Closed)]
+/// enum Door {
+///     #[transitions(Closed)]
+///     Opened,
+///     #[transitions(Opened, Locked)]
+///     Closed,
+///     #[end]
+///     Locked { code: u32 },
+/// }
+/// impl Machine for Door {}
+/// impl State for Opened {}
+/// impl State for Closed {}
+/// impl State for Locked {}
+///
+/// let topology = Door::topology();
+/// assert_eq!(topology.name, "Door");
+/// assert_eq!(topology.initial, Some("Closed"));
+/// assert!(topology.state("Locked").unwrap().has_data);
+/// assert_eq!(
+///     topology.transitions_from("Closed").map(|t| t.to).collect::<Vec<_>>(),
+///     ["Opened", "Locked"]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Topology {
+    /// Name of the enum.
+    pub name: &'static str,
+    /// Name of the initial state (see `#[StateEnum(initial = ...)]`).
+    pub initial: Option<&'static str>,
+    /// States in declaration order.
+    pub states: &'static [StateInfo],
+    /// Declared transitions in declaration order.
+    pub transitions: &'static [TransitionInfo],
+}
+
+impl Topology {
+    /// Returns the state with the given name.
+    pub fn state(&self, name: &str) -> Option<&'static StateInfo> {
+        self.states.iter().find(|state| state.name == name)
+    }
+
+    /// Returns the declared transitions leaving the given state.
+    pub fn transitions_from<'a>(
+        &self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'static TransitionInfo> + 'a {
+        self.transitions
+            .iter()
+            .filter(move |transition| transition.from == name)
+    }
+
+    /// Returns the declared transitions entering the given state.
+    pub fn transitions_to<'a>(
+        &self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'static TransitionInfo> + 'a {
+        self.transitions
+            .iter()
+            .filter(move |transition| transition.to == name)
+    }
+}
+
+/// Description of a state in a [`Topology`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StateInfo {
+    /// Name of the state.
+    pub name: &'static str,
+    /// Whether the state struct has fields.
+    pub has_data: bool,
+    /// Whether the state is an end state (see [`EndState`](crate::state_machine::EndState) and the `#[end]` attribute).
+    pub is_end: bool,
+    /// Whether the state is a history state (see the `#[history]` attribute).
+    pub has_history: bool,
+}
+
+/// Description of a declared transition in a [`Topology`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TransitionInfo {
+    /// Name of the source state.
+    pub from: &'static str,
+    /// Name of the target state.
+    pub to: &'static str,
+    /// Whether the transition is triggered by a timeout (see the `#[timeout(...)]` attribute).
+    pub timeout: bool,
+}
+
+/// Implemented by `#[StateEnum]` enums which declare their transitions with `#[transitions(...)]`.
+///
+/// The generated `<Enum>Edge` enum has a variant for every declared transition, named `<From>To<To>`.
+/// Matching on it exhaustively makes the compiler flag the unhandled edges when the transition table changes.
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+///
+/// #[StateEnum(context = Vec<&'static str>)]
+/// enum Door {
+///     #[transitions(Closed)]
+///     Opened,
+///     #[transitions(Opened)]
+///     Closed,
+/// }
+/// impl Machine for Door {
+///     fn on_transition(&mut self, new_state: &Self, context: &mut Self::Context) {
+///         match self.edge(new_state) {
+///             Some(DoorEdge::OpenedToClosed) => context.push("slam"),
+///             Some(DoorEdge::ClosedToOpened) => context.push("creak"),
+///             None => context.push("undeclared"),
+///         }
+///     }
+/// }
+/// impl State for Opened {}
+/// impl State for Closed {}
+///
+/// let mut sm = StateMachine::new_context(Closed, vec![]);
+/// sm.transition(Opened);
+/// sm.transition(Opened);
+/// assert_eq!(sm.context(), &["creak", "undeclared"]);
+/// ```
+pub trait TransitionEdges {
+    /// Generated as `<Enum>Edge`.
+    type Edge;
+
+    /// Returns the declared transition from `self` to `to`.
+    ///
+    /// # Returns
+    /// `Some` - The edge if the transition is declared \
+    /// `None` - Otherwise
+    fn edge(&self, to: &Self) -> Option<Self::Edge>;
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+
+    #[StateEnum(initial = Sleeping)]
+    enum TestTopology {
+        #[transitions(Awake)]
+        Sleeping,
+        #[timeout(duration = "1s", to = Sleeping)]
+        #[transitions(Working)]
+        Awake,
+        #[history]
+        #[transitions(Awake, EndState)]
+        Working(u32),
+        EndState,
+    }
+    impl Machine for TestTopology {}
+    impl State for Sleeping {}
+    impl State for Awake {}
+    impl State for Working {}
+
+    #[test]
+    fn test_topology() {
+        let topology = TestTopology::topology();
+        assert_eq!(topology.name, "TestTopology");
+        assert_eq!(topology.initial, Some("Sleeping"));
+        assert_eq!(
+            topology.states,
+            &[
+                StateInfo {
+                    name: "Sleeping",
+                    has_data: false,
+                    is_end: false,
+                    has_history: false,
+                },
+                StateInfo {
+                    name: "Awake",
+                    has_data: false,
+                    is_end: false,
+                    has_history: false,
+                },
+                StateInfo {
+                    name: "Working",
+                    has_data: true,
+                    is_end: false,
+                    has_history: true,
+                },
+                StateInfo {
+                    name: "EndState",
+                    has_data: false,
+                    is_end: true,
+                    has_history: false,
+                },
+            ]
+        );
+        assert_eq!(
+            topology.transitions_from("Awake").collect::<Vec<_>>(),
+            [
+                &TransitionInfo {
+                    from: "Awake",
+                    to: "Sleeping",
+                    timeout: true,
+                },
+                &TransitionInfo {
+                    from: "Awake",
+                    to: "Working",
+                    timeout: false,
+                },
+            ]
+        );
+        assert_eq!(
+            topology
+                .transitions_to("Awake")
+                .map(|t| t.from)
+                .collect::<Vec<_>>(),
+            ["Sleeping", "Working"]
+        );
+        assert!(topology.state("Missing").is_none());
+
+        let sm = StateMachine::new(Working(1));
+        assert_eq!(sm.state_as::<Working>().map(|s| s.0), Some(1));
+    }
+
+    #[test]
+    fn test_transition_edges() {
+        let working = TestTopology::Working(Working(1));
+        assert_eq!(
+            TestTopology::Awake(Awake).edge(&TestTopology::Sleeping(Sleeping)),
+            Some(TestTopologyEdge::AwakeToSleeping)
+        );
+        assert_eq!(
+            working.edge(&TestTopology::EndState(EndState)),
+            Some(TestTopologyEdge::WorkingToEndState)
+        );
+        assert_eq!(working.edge(&TestTopology::Sleeping(Sleeping)), None);
+
+        let handled = |edge| match edge {
+            TestTopologyEdge::SleepingToAwake
+            | TestTopologyEdge::AwakeToSleeping
+            | TestTopologyEdge::AwakeToWorking
+            | TestTopologyEdge::WorkingToAwake
+            | TestTopologyEdge::WorkingToEndState => true,
+        };
+        assert!(handled(TestTopologyEdge::WorkingToAwake));
+    }
+}
@@ -0,0 +1,3 @@
+mod reflect;
+
+pub use reflect::*;
@@ -0,0 +1,157 @@
+use crate::enums::FieldInfo;
+use core::any::Any;
+
+/// Reflects the fields of a struct, generated by [`crate::macros::ReflectStruct`].
+///
+/// The fields are described by the same [`FieldInfo`] as the fields of the enum variants, and can be
+/// accessed by name or position as [`Any`], e.g. to build settings editors.
+///
+/// # Example
+/// ```
+/// use adar::prelude::*;
+///
+/// #[ReflectStruct]
+/// #[derive(Default)]
+/// struct Settings {
+///     /// Volume of the sound effects.
+///     #[reflect(label = "Volume", max = "100")]
+///     volume: u8,
+///     #[reflect(rename = "fullscreen")]
+///     full_screen: bool,
+///     #[reflect(skip)]
+///     dirty: bool,
+/// }
+///
+/// let mut settings = Settings::default();
+/// assert_eq!(Settings::COUNT, 2);
+/// assert_eq!(Settings::FIELDS[0].meta("label"), Some("Volume"));
+/// assert_eq!(Settings::FIELDS[0].doc(), Some("Volume of the sound effects."));
+///
+/// *settings.field_mut("volume").unwrap().downcast_mut::<u8>().unwrap() = 80;
+/// *settings.field_at_mut(1).unwrap().downcast_mut::<bool>().unwrap() = true;
+/// assert_eq!(settings.volume, 80);
+/// assert!(settings.full_screen);
+/// assert!(settings.field("dirty").is_none());
+/// ```
+pub trait ReflectStruct: Sized + 'static {
+    /// Number of the reflected fields, usable in const contexts (e.g. `[u8; MyStruct::COUNT]`).
+    const COUNT: usize;
+
+    /// Reflected fields in declaration order, usable in const contexts. See [`ReflectStruct::fields()`].
+    const FIELDS: &'static [FieldInfo];
+
+    fn fields() -> &'static [FieldInfo] {
+        Self::FIELDS
+    }
+
+    fn count() -> usize {
+        Self::COUNT
+    }
+
+    /// Gets the field at the position in [`ReflectStruct::fields()`].
+    fn field_at(&self, index: usize) -> Option<&dyn Any>;
+
+    /// Gets the mutable field at the position in [`ReflectStruct::fields()`].
+    fn field_at_mut(&mut self, index: usize) -> Option<&mut dyn Any>;
+
+    /// Gets the position of the field with the specified name in [`ReflectStruct::fields()`].
+    fn field_index(name: &str) -> Option<usize> {
+        Self::FIELDS.iter().position(|field| field.name == name)
+    }
+
+    /// Gets the field with the specified name.
+    fn field(&self, name: &str) -> Option<&dyn Any> {
+        self.field_at(Self::field_index(name)?)
+    }
+
+    /// Gets the mutable field with the specified name.
+    fn field_mut(&mut self, name: &str) -> Option<&mut dyn Any> {
+        self.field_at_mut(Self::field_index(name)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{self as adar, prelude::*};
+    use std::any::TypeId;
+
+    #[ReflectStruct]
+    #[derive(Debug, Default, PartialEq)]
+    struct TestStruct {
+        /// First line.
+        ///
+        /// Second line.
+        a: u32,
+        #[reflect(rename = "B", unit = "ms")]
+        b: Option<String>,
+        #[reflect(skip)]
+        c: i8,
+        d: Vec<Self>,
+    }
+
+    #[ReflectStruct]
+    struct TestTuple(u8, &'static str);
+
+    #[ReflectStruct]
+    struct TestUnit;
+
+    #[test]
+    fn test_struct_fields() {
+        assert_eq!(TestStruct::count(), 3);
+        assert_eq!(
+            TestStruct::fields()
+                .iter()
+                .map(|field| field.name)
+                .collect::<Vec<_>>(),
+            ["a", "B", "d"]
+        );
+        assert_eq!(TestStruct::FIELDS[0].type_id, TypeId::of::<u32>());
+        assert_eq!(
+            TestStruct::FIELDS[1].type_id,
+            TypeId::of::<Option<String>>()
+        );
+        assert_eq!(
+            TestStruct::FIELDS[2].type_id,
+            TypeId::of::<Vec<TestStruct>>()
+        );
+        assert_eq!(
+            TestStruct::FIELDS[0].doc(),
+            Some("First line.\n\nSecond line.")
+        );
+        assert_eq!(TestStruct::FIELDS[1].metadata(), [("unit", "ms")]);
+        assert_eq!(TestStruct::FIELDS[2].doc(), None);
+
+        assert_eq!(TestTuple::COUNT, 2);
+        assert_eq!(TestTuple::FIELDS[1].name, "1");
+        assert_eq!(TestTuple::FIELDS[1].type_id, TypeId::of::<&'static str>());
+        assert_eq!(TestUnit::COUNT, 0);
+        assert!(TestUnit::fields().is_empty());
+    }
+
+    #[test]
+    fn test_struct_access() {
+        let mut value = TestStruct::default();
+        *value.field_mut("a").unwrap().downcast_mut::<u32>().unwrap() = 5;
+        *value
+            .field_at_mut(1)
+            .unwrap()
+            .downcast_mut::<Option<String>>()
+            .unwrap() = Some("x".into());
+        assert_eq!(value.field("a").unwrap().downcast_ref::<u32>(), Some(&5));
+        assert_eq!(value.field_at(0).unwrap().downcast_ref::<u8>(), None);
+        assert_eq!(value.b.as_deref(), Some("x"));
+        assert!(value.field("b").is_none());
+        assert!(value.field("c").is_none());
+        assert!(value.field_at(3).is_none());
+        assert_eq!(TestStruct::field_index("d"), Some(2));
+
+        let mut tuple = TestTuple(1, "a");
+        *tuple.field_mut("0").unwrap().downcast_mut::<u8>().unwrap() = 2;
+        assert_eq!(tuple.0, 2);
+        assert_eq!(
+            tuple.field_at(1).unwrap().downcast_ref::<&str>(),
+            Some(&"a")
+        );
+        assert!(TestUnit.field_at(0).is_none());
+    }
+}
@@ -5,7 +5,7 @@ pub trait AsTraitRef<T: ?Sized>: Sized {
 }
 
 pub trait AsTraitRefMut<T: ?Sized>: Sized {
-    fn as_trait_mut(&mut self) -> &T;
+    fn as_trait_mut(&mut self) -> &mut T;
 }
 
 macro_rules! impl_as_trait_ref {
@@ -22,7 +22,7 @@ macro_rules! impl_as_trait_ref {
         where
             T: Sized + $trait + 'static,
         {
-            fn as_trait_mut(&mut self) -> &(dyn $trait + 'static) {
+            fn as_trait_mut(&mut self) -> &mut (dyn $trait + 'static) {
                 self
             }
         }
@@ -63,7 +63,7 @@ impl<T, U> AsTraitRefMut<dyn Deref<Target = U>> for T
 where
     T: Deref<Target = U> + 'static,
 {
-    fn as_trait_mut(&mut self) -> &(dyn Deref<Target = U> + 'static) {
+    fn as_trait_mut(&mut self) -> &mut (dyn Deref<Target = U> + 'static) {
         self
     }
 }
@@ -81,7 +81,30 @@ impl<T, U> AsTraitRefMut<dyn DerefMut<Target = U>> for T
 where
     T: DerefMut<Target = U> + 'static,
 {
-    fn as_trait_mut(&mut self) -> &(dyn DerefMut<Target = U> + 'static) {
+    fn as_trait_mut(&mut self) -> &mut (dyn DerefMut<Target = U> + 'static) {
         self
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_as_trait_mut() {
+        let mut buffer = Vec::<u8>::new();
+        let writer: &mut dyn Write = buffer.as_trait_mut();
+        writer.write_all(b"adar").unwrap();
+        assert_eq!(buffer, b"adar");
+
+        let mut value = Box::new(1);
+        let deref: &mut dyn DerefMut<Target = i32> = value.as_trait_mut();
+        **deref += 1;
+        assert_eq!(*value, 2);
+        assert_eq!(
+            AsTraitRef::<dyn std::fmt::Display>::as_trait_ref(&value).to_string(),
+            "2"
+        );
+    }
+}
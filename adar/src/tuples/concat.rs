@@ -45,9 +45,7 @@ macro_rules! impl_concat_tuple {
     ((,), (,)) => {
         impl ConcatTuple<(), ()> for () {
             #[inline(always)]
-            fn concat(self, _other: ()) -> () {
-                ()
-            }
+            fn concat(self, _other: ()) {}
         }
     };
 }
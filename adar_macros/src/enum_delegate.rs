@@ -0,0 +1,260 @@
+use crate::enum_trait_deref::{variant_field, variant_pattern};
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::*;
+
+/// `#[EnumDelegate]` on a trait defines a declarative macro which implements the trait for an enum,
+/// `#[EnumDelegate(MyTrait)]` on an enum invokes it.
+pub fn enum_delegate_macro_inner(args: TokenStream, input: Item) -> syn::Result<TokenStream> {
+    match input {
+        Item::Trait(input) => {
+            if !args.is_empty() {
+                return Err(syn::Error::new_spanned(
+                    args,
+                    "#[EnumDelegate] macro takes no arguments on traits",
+                ));
+            }
+            delegate_trait(input)
+        }
+        Item::Enum(input) => delegate_enum(parse2(args)?, input),
+        _ => Err(syn::Error::new(
+            Span::call_site(),
+            "#[EnumDelegate] macro only supports traits and enums",
+        )),
+    }
+}
+
+/// Name of the declarative macro generated for the trait.
+fn delegate_macro_ident(trait_ident: &Ident) -> Ident {
+    format_ident!("__enum_delegate_{}", trait_ident)
+}
+
+fn delegate_trait(input: ItemTrait) -> syn::Result<TokenStream> {
+    let macro_ident = delegate_macro_ident(&input.ident);
+    // The impl is generic over the parameters of the trait, the payloads must implement it
+    let (lifetimes, params) = impl_params(&input.generics);
+    let trait_args = (!input.generics.params.is_empty()).then(|| {
+        let args = input.generics.params.iter().map(|param| match param {
+            GenericParam::Lifetime(param) => param.lifetime.to_token_stream(),
+            GenericParam::Type(param) => param.ident.to_token_stream(),
+            GenericParam::Const(param) => param.ident.to_token_stream(),
+        });
+        quote! {<#(#args),*>}
+    });
+    let methods = input
+        .items
+        .iter()
+        .filter_map(|item| delegate_method(item, trait_args.as_ref()).transpose())
+        .collect::<syn::Result<Vec<_>>>()?;
+    let predicates = input
+        .generics
+        .where_clause
+        .as_ref()
+        .map(|where_clause| where_clause.predicates.iter().collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    Ok(quote! {
+        #input
+
+        #[doc(hidden)]
+        macro_rules! #macro_ident {
+            (
+                trait: [$($trait:tt)*],
+                lifetimes: [$($lifetimes:tt)*],
+                generics: [$($generics:tt)*],
+                enum: $enum:ty,
+                where: [$($where:tt)*],
+                binding: $value:ident,
+                variants: [$($pattern:pat => $payload:ty),*]
+            ) => {
+                const _: () = {
+                    // The path can't be repeated for each payload, import it under a single name
+                    use $($trait)* as __EnumDelegateTrait;
+
+                    impl<$($lifetimes)* #(#lifetimes,)* $($generics)* #(#params,)*> __EnumDelegateTrait #trait_args for $enum
+                    where
+                        $($where)*
+                        #(#predicates,)*
+                        $($payload: __EnumDelegateTrait #trait_args,)*
+                    {
+                        #(#methods)*
+                    }
+                };
+            };
+        }
+
+        #[doc(hidden)]
+        #[allow(unused_imports)]
+        pub(crate) use #macro_ident;
+    })
+}
+
+/// Splits the generic parameters into lifetimes and the rest, without defaults, so they can be
+/// merged into the parameters of a single impl.
+fn impl_params(generics: &Generics) -> (Vec<GenericParam>, Vec<GenericParam>) {
+    generics
+        .params
+        .iter()
+        .cloned()
+        .map(|mut param| {
+            match &mut param {
+                GenericParam::Type(param) => {
+                    param.eq_token = None;
+                    param.default = None;
+                }
+                GenericParam::Const(param) => {
+                    param.eq_token = None;
+                    param.default = None;
+                }
+                GenericParam::Lifetime(_) => {}
+            }
+            param
+        })
+        .partition(|param| matches!(param, GenericParam::Lifetime(_)))
+}
+
+/// Forwards a method with a receiver to the payload. Provided methods without a receiver are skipped.
+fn delegate_method(
+    item: &TraitItem,
+    trait_args: Option<&TokenStream>,
+) -> syn::Result<Option<TokenStream>> {
+    let method = match item {
+        TraitItem::Fn(method) => method,
+        TraitItem::Const(TraitItemConst {
+            default: Some(_), ..
+        }) => return Ok(None),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                item,
+                "#[EnumDelegate] macro only supports traits with methods",
+            ))
+        }
+    };
+    let mut sig = method.sig.clone();
+    match sig.receiver() {
+        Some(receiver) if receiver.colon_token.is_none() => {}
+        Some(receiver) => {
+            return Err(syn::Error::new_spanned(
+                receiver,
+                "#[EnumDelegate] macro does not support typed receivers",
+            ))
+        }
+        None if method.default.is_some() => return Ok(None),
+        None => {
+            return Err(syn::Error::new_spanned(
+                &sig,
+                "#[EnumDelegate] macro requires a receiver or a default implementation",
+            ))
+        }
+    }
+    if let Some(self_type) = sig
+        .inputs
+        .iter()
+        .skip(1)
+        .map(ToTokens::to_token_stream)
+        .chain([sig.output.to_token_stream()])
+        .find(|tokens| mentions_self(tokens.clone()))
+    {
+        return Err(syn::Error::new_spanned(
+            self_type,
+            "#[EnumDelegate] macro does not support Self outside of the receiver",
+        ));
+    }
+
+    let mut args = vec![];
+    for (i, input) in sig.inputs.iter_mut().skip(1).enumerate() {
+        if let FnArg::Typed(input) = input {
+            let arg = format_ident!("arg{}", i);
+            *input.pat = parse_quote! {#arg};
+            args.push(arg);
+        }
+    }
+    let method_ident = &sig.ident;
+    let call = quote! {
+        <$payload as __EnumDelegateTrait #trait_args>::#method_ident($value, #(#args),*)
+    };
+    let call = if sig.asyncness.is_some() {
+        quote! {#call.await}
+    } else {
+        call
+    };
+    let call = if sig.unsafety.is_some() {
+        quote! {unsafe { #call }}
+    } else {
+        call
+    };
+    let cfgs = method
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"));
+    Ok(Some(quote! {
+        #(#cfgs)*
+        #sig {
+            match self {
+                $($pattern => #call,)*
+            }
+        }
+    }))
+}
+
+/// Checks if the tokens refer to `Self`.
+fn mentions_self(tokens: TokenStream) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == "Self",
+        TokenTree::Group(group) => mentions_self(group.stream()),
+        _ => false,
+    })
+}
+
+fn delegate_enum(trait_path: Path, mut input: ItemEnum) -> syn::Result<TokenStream> {
+    let Some(last) = trait_path.segments.last() else {
+        return Err(syn::Error::new_spanned(trait_path, "Expected a trait"));
+    };
+    if !last.arguments.is_none() {
+        return Err(syn::Error::new_spanned(
+            &last.arguments,
+            "#[EnumDelegate] macro implements the trait for all of its generic arguments, remove them",
+        ));
+    }
+    let mut macro_path = trait_path.clone();
+    macro_path.segments.last_mut().unwrap().ident = delegate_macro_ident(&last.ident);
+
+    let targets = input
+        .variants
+        .iter_mut()
+        .map(|variant| variant_field(variant, "delegate", "#[EnumDelegate]"))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let binding = format_ident!("v");
+    let variants = input
+        .variants
+        .iter()
+        .zip(&targets)
+        .map(|(variant, (member, ty))| {
+            let pattern = variant_pattern(variant, member, &binding);
+            quote! {#pattern => #ty}
+        });
+
+    let ident = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let (lifetimes, params) = impl_params(&input.generics);
+    let predicates = input
+        .generics
+        .where_clause
+        .as_ref()
+        .map(|where_clause| where_clause.predicates.iter().collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    Ok(quote! {
+        #input
+
+        #macro_path! {
+            trait: [#trait_path],
+            lifetimes: [#(#lifetimes,)*],
+            generics: [#(#params,)*],
+            enum: #ident #ty_generics,
+            where: [#(#predicates,)*],
+            binding: #binding,
+            variants: [#(#variants),*]
+        }
+    })
+}
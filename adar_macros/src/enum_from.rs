@@ -0,0 +1,90 @@
+use proc_macro2::Span;
+use quote::quote;
+use syn::*;
+
+pub fn enum_from_macro_inner(
+    args: proc_macro2::TokenStream,
+    mut input: DeriveInput,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if !args.is_empty() {
+        return Err(syn::Error::new_spanned(
+            args,
+            "#[EnumFrom] macro takes no arguments",
+        ));
+    }
+    let Data::Enum(data_enum) = &mut input.data else {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "#[EnumFrom] macro only supports enums",
+        ));
+    };
+    let skipped = data_enum
+        .variants
+        .iter_mut()
+        .map(|variant| take_skip(&mut variant.attrs))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let Data::Enum(data_enum) = &input.data else {
+        unreachable!()
+    };
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // Only the variants with a single field can be constructed from their payload
+    let impls = data_enum
+        .variants
+        .iter()
+        .zip(skipped)
+        .filter(|(variant, skip)| !skip && variant.fields.len() == 1)
+        .map(|(variant, _)| {
+            let variant_ident = &variant.ident;
+            let field = variant.fields.iter().next().unwrap();
+            let payload = &field.ty;
+            let variant = match &field.ident {
+                Some(name) => quote! {#ident::#variant_ident { #name: value }},
+                None => quote! {#ident::#variant_ident(value)},
+            };
+            quote! {
+                impl #impl_generics ::core::convert::From<#payload> for #ident #ty_generics #where_clause {
+                    fn from(value: #payload) -> Self {
+                        #variant
+                    }
+                }
+
+                impl #impl_generics ::core::convert::TryFrom<#ident #ty_generics> for #payload #where_clause {
+                    type Error = #ident #ty_generics;
+
+                    fn try_from(value: #ident #ty_generics) -> ::core::result::Result<Self, #ident #ty_generics> {
+                        match value {
+                            #variant => Ok(value),
+                            #[allow(unreachable_patterns)]
+                            other => Err(other),
+                        }
+                    }
+                }
+            }
+        });
+
+    Ok(quote! {
+        #input
+
+        #(#impls)*
+    })
+}
+
+/// Checks for `#[from(skip)]`, which is removed from the variant.
+fn take_skip(attrs: &mut Vec<Attribute>) -> syn::Result<bool> {
+    let mut skip = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("from")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("Expected `skip`"))
+            }
+        })?;
+    }
+    attrs.retain(|attr| !attr.path().is_ident("from"));
+    Ok(skip)
+}
@@ -1,37 +1,105 @@
+use crate::reflect::snake_case;
 use proc_macro2::Span;
-use quote::quote;
-use syn::*;
+use quote::{format_ident, quote};
+use syn::{punctuated::Punctuated, *};
 
 pub fn enum_trait_deref_macro_inner(
-    trai: TypeTraitObject,
-    input: DeriveInput,
+    traits: Punctuated<TypeTraitObject, Token![,]>,
+    mut input: DeriveInput,
     with_mut: bool,
 ) -> syn::Result<proc_macro2::TokenStream> {
-    let Data::Enum(data_enum) = &input.data else {
+    let macro_name = format!("#[EnumTraitDeref{}]", if with_mut { "Mut" } else { "" });
+    let Data::Enum(data_enum) = &mut input.data else {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            format!("{} macro only supports enums", macro_name),
+        ));
+    };
+    // Deref can only target the first trait, every trait gets accessor methods
+    let Some(deref_trait) = traits.first() else {
         return Err(syn::Error::new(
             Span::call_site(),
-            format!(
-                "#[EnumTraitDeref{}] macro only supports enums",
-                if with_mut { "Mut" } else { "" }
-            ),
+            format!("{} macro requires at least one trait", macro_name),
         ));
     };
 
-    let variants = data_enum
+    let targets = data_enum
+        .variants
+        .iter_mut()
+        .map(|variant| variant_field(variant, "deref", &macro_name))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let Data::Enum(data_enum) = &input.data else {
+        unreachable!()
+    };
+    let patterns = data_enum
         .variants
         .iter()
-        .map(|variant| &variant.ident)
+        .zip(&targets)
+        .map(|(variant, (member, _))| variant_pattern(variant, member, &format_ident!("v")))
         .collect::<Vec<_>>();
+    let payloads = targets.iter().map(|(_, ty)| ty).collect::<Vec<_>>();
 
     let ident = &input.ident;
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let object_lifetime = object_lifetime(&input.generics);
+    let deref_trait = with_lifetime(deref_trait, object_lifetime.as_ref());
+    let where_clause = generic_where_clause(
+        &input.generics,
+        object_lifetime.as_ref(),
+        &payloads,
+        [&deref_trait],
+    );
+    let traits = traits
+        .iter()
+        .map(|trai| with_lifetime(trai, object_lifetime.as_ref()))
+        .collect::<Vec<_>>();
+    let accessors_where_clause = generic_where_clause(
+        &input.generics,
+        object_lifetime.as_ref(),
+        &payloads,
+        &traits,
+    );
+
+    let accessors = traits
+        .iter()
+        .map(|trai| {
+            let name = trait_name(trai)?;
+            let as_ref = format_ident!("as_{}", name);
+            let as_ref_doc = format!("Gets the value of the variant as `dyn {}`.", quote! {#trai});
+            let as_mut = with_mut.then(|| {
+                let as_mut = format_ident!("as_{}_mut", name);
+                let as_mut_doc = format!(
+                    "Gets the value of the variant as mutable `dyn {}`.",
+                    quote! {#trai}
+                );
+                quote! {
+                    #[doc = #as_mut_doc]
+                    pub fn #as_mut(&mut self) -> &mut (dyn #trai) {
+                        match self {
+                            #(#patterns => v as &mut (dyn #trai),)*
+                        }
+                    }
+                }
+            });
+            Ok(quote! {
+                #[doc = #as_ref_doc]
+                pub fn #as_ref(&self) -> &(dyn #trai) {
+                    match self {
+                        #(#patterns => v as &(dyn #trai),)*
+                    }
+                }
+
+                #as_mut
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
 
     let mut_impl = if with_mut {
         quote! {
             impl #impl_generics ::core::ops::DerefMut for #ident #ty_generics #where_clause {
                 fn deref_mut(&mut self) -> &mut Self::Target {
                     match self {
-                        #(Self::#variants(v) => v as &mut Self::Target,)*
+                        #(#patterns => v as &mut Self::Target,)*
                     }
                 }
             }
@@ -44,16 +112,153 @@ pub fn enum_trait_deref_macro_inner(
         #input
 
         impl #impl_generics ::core::ops::Deref for #ident #ty_generics #where_clause {
-            type Target = dyn #trai;
+            type Target = dyn #deref_trait;
 
             fn deref(&self) -> &Self::Target {
                 match self {
-                    #(Self::#variants(v) => v as &Self::Target,)*
+                    #(#patterns => v as &Self::Target,)*
                 }
             }
         }
 
         #mut_impl
+
+        impl #impl_generics #ident #ty_generics #accessors_where_clause {
+            #(#accessors)*
+        }
+    })
+}
+
+/// Pattern which binds the field of the variant, e.g. `Self::A(v)` or `Self::B { inner: v, .. }`.
+pub fn variant_pattern(
+    variant: &Variant,
+    member: &Member,
+    binding: &Ident,
+) -> proc_macro2::TokenStream {
+    let ident = &variant.ident;
+    match &variant.fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => quote! {Self::#ident(#binding)},
+        _ => quote! {Self::#ident { #member: #binding, .. }},
+    }
+}
+
+/// Finds the field which implements the traits, and removes `#[deref(field = name)]` (or the attribute
+/// named `attr_name`) from the variant. Variants with a single field don't need the attribute.
+pub fn variant_field(
+    variant: &mut Variant,
+    attr_name: &str,
+    macro_name: &str,
+) -> syn::Result<(Member, Type)> {
+    let mut member = None;
+    for attr in variant
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident(attr_name))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("field") {
+                member = Some(meta.value()?.parse::<Member>()?);
+                Ok(())
+            } else {
+                Err(meta.error("Expected `field = name`"))
+            }
+        })?;
+    }
+    variant
+        .attrs
+        .retain(|attr| !attr.path().is_ident(attr_name));
+
+    let member = match member {
+        Some(member) => member,
+        None if variant.fields.len() == 1 => variant.fields.members().next().unwrap(),
+        None => {
+            return Err(syn::Error::new_spanned(
+                &variant.ident,
+                format!(
+                    "{} macro requires variants with a single field, or #[{}(field = name)]",
+                    macro_name, attr_name
+                ),
+            ))
+        }
+    };
+    let field = variant
+        .fields
+        .iter()
+        .zip(variant.fields.members())
+        .find(|(_, other)| *other == member)
+        .map(|(field, _)| field)
+        .ok_or_else(|| syn::Error::new_spanned(&member, "Unknown field"))?;
+    Ok((member, field.ty.clone()))
+}
+
+/// Lifetime of the trait objects of generic enums: the first lifetime parameter, or `'static` if the enum
+/// only has type parameters. Non-generic enums keep the default object lifetime.
+fn object_lifetime(generics: &Generics) -> Option<Lifetime> {
+    if let Some(param) = generics.lifetimes().next() {
+        Some(param.lifetime.clone())
+    } else if generics.type_params().next().is_some() {
+        Some(Lifetime::new("'static", Span::call_site()))
+    } else {
+        None
+    }
+}
+
+/// Adds the object lifetime to the trait, unless it already has an explicit one.
+fn with_lifetime(trai: &TypeTraitObject, lifetime: Option<&Lifetime>) -> TypeTraitObject {
+    let mut trai = trai.clone();
+    let has_lifetime = trai
+        .bounds
+        .iter()
+        .any(|bound| matches!(bound, TypeParamBound::Lifetime(_)));
+    if let (Some(lifetime), false) = (lifetime, has_lifetime) {
+        trai.bounds.push(TypeParamBound::Lifetime(lifetime.clone()));
+    }
+    trai
+}
+
+/// Extends the where clause of a generic enum: the type parameters must outlive the trait objects, and the
+/// payloads must implement the traits.
+fn generic_where_clause<'a>(
+    generics: &Generics,
+    lifetime: Option<&Lifetime>,
+    payloads: &[&Type],
+    traits: impl IntoIterator<Item = &'a TypeTraitObject>,
+) -> Option<WhereClause> {
+    let Some(lifetime) = lifetime else {
+        return generics.where_clause.clone();
+    };
+    let mut where_clause = generics
+        .where_clause
+        .clone()
+        .unwrap_or_else(|| WhereClause {
+            where_token: Default::default(),
+            predicates: Punctuated::new(),
+        });
+    for param in generics.type_params() {
+        let param = &param.ident;
+        where_clause
+            .predicates
+            .push(parse_quote! {#param: #lifetime});
+    }
+    for trai in traits {
+        let bounds = &trai.bounds;
+        for payload in payloads {
+            where_clause
+                .predicates
+                .push(parse_quote! {#payload: #bounds});
+        }
     }
-    .into())
+    Some(where_clause)
+}
+
+/// Name of the trait in the accessor methods, e.g. `as_my_trait()` for `MyTrait + Send`.
+fn trait_name(trai: &TypeTraitObject) -> syn::Result<String> {
+    trai.bounds
+        .iter()
+        .find_map(|bound| match bound {
+            TypeParamBound::Trait(bound) => bound.path.segments.last(),
+            _ => None,
+        })
+        .map(|segment| snake_case(&segment.ident.to_string()))
+        .ok_or_else(|| syn::Error::new_spanned(trai, "Expected a trait"))
 }
@@ -1,59 +1,437 @@
+use crate::reflect::{enum_repr, integer_bits, ReflectEnumArgs};
 use proc_macro2::Span;
 use quote::quote;
+use std::collections::HashMap;
 use syn::*;
 
-pub fn flag_enum_macro_inner(mut input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
-    if let Data::Enum(data_enum) = &mut input.data {
-        patch_flag_discriminants(data_enum)?;
+pub fn flag_enum_macro_inner(
+    mut args: ReflectEnumArgs,
+    mut input: DeriveInput,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut repr = enum_repr(&input);
+    let has_repr = input.attrs.iter().any(|attr| attr.path().is_ident("repr"));
+    let groups = take_groups(&mut input.attrs)?;
+    let composites = if let Data::Enum(data_enum) = &mut input.data {
+        if args.storage.is_none() && data_enum.variants.len() > 128 {
+            args.storage = Some(Ident::new("big", Span::call_site()));
+        }
+        if args.is_big() {
+            if let Some(group) = groups.first() {
+                return Err(syn::Error::new(
+                    group.ident.span(),
+                    "#[FlagEnum] does not support groups with big storage",
+                ));
+            }
+            validate_big_flags(data_enum)?;
+            return Ok(flag_enum_impls(args, input, vec![]));
+        }
+
+        let composites = patch_flag_discriminants(data_enum, groups)?;
+        let highest = data_enum
+            .variants
+            .iter()
+            .map(|variant| flag_bit(variant) + 1)
+            .max()
+            .unwrap_or(0);
+        if !has_repr && highest > 0 {
+            repr = smallest_repr(highest).to_string();
+            let repr = Ident::new(&repr, Span::call_site());
+            input.attrs.push(parse_quote! {#[repr(#repr)]});
+        }
+        validate_flag_bits(highest, "repr", &repr)?;
+        if let Some(storage) = &args.storage {
+            validate_flag_bits(highest, "storage", &storage.to_string())?;
+        }
+        composites
     } else {
         return Err(syn::Error::new(
             Span::call_site(),
             "#[FlagEnum] macro only supports enums",
         ));
-    }
+    };
+
+    Ok(flag_enum_impls(args, input, composites))
+}
 
+fn flag_enum_impls(
+    args: ReflectEnumArgs,
+    input: DeriveInput,
+    composites: Vec<Composite>,
+) -> proc_macro2::TokenStream {
     let ident = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let storage = args.storage.map(|storage| quote! {storage = #storage,});
+    let rename_all = args
+        .rename_all
+        .map(|rename_all| quote! {rename_all = #rename_all,});
+    let display = args.display.then(|| quote! {display,});
+    let reflect_args = quote! {(#storage #rename_all #display)};
+    let bitflags_impl = args.bitflags.map(|bitflags| {
+        quote! {
+            impl #impl_generics From<#bitflags> for adar::prelude::Flags<#ident #ty_generics> #where_clause {
+                fn from(flags: #bitflags) -> Self {
+                    adar::prelude::Flags::from_bitflags(flags)
+                }
+            }
 
-    Ok(quote! {
+            impl #impl_generics From<adar::prelude::Flags<#ident #ty_generics>> for #bitflags #where_clause {
+                fn from(flags: adar::prelude::Flags<#ident #ty_generics>) -> Self {
+                    flags.into_bitflags()
+                }
+            }
+        }
+    });
+
+    let composite_consts = composites.iter().map(|composite| {
+        let Composite {
+            attrs,
+            ident,
+            value,
+        } = composite;
+        let value = LitInt::new(&value.to_string(), ident.span());
+        quote! {
+            #(#attrs)*
+            #[allow(non_upper_case_globals)]
+            pub const #ident: adar::prelude::Flags<Self> =
+                adar::prelude::Flags::<Self>::__from_raw_unchecked(#value);
+        }
+    });
+    let composites_impl = if composites.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                #(#composite_consts)*
+            }
+        }
+    };
+
+    let ops = [
+        (quote!(BitOr), quote!(bitor)),
+        (quote!(BitAnd), quote!(bitand)),
+        (quote!(BitXor), quote!(bitxor)),
+        (quote!(Sub), quote!(sub)),
+    ]
+    .into_iter()
+    .map(|(op, method)| {
+        quote! {
+            impl #impl_generics ::core::ops::#op for #ident #ty_generics #where_clause
+            where
+                Self: adar::prelude::ReflectEnum
+            {
+                type Output = adar::prelude::Flags<Self>;
+
+                #[inline(always)]
+                fn #method(self, rhs: Self) -> Self::Output {
+                    ::core::ops::#op::#method(adar::prelude::Flags::from(self), rhs)
+                }
+            }
+
+            impl #impl_generics ::core::ops::#op<adar::prelude::Flags<Self>> for #ident #ty_generics #where_clause
+            where
+                Self: adar::prelude::ReflectEnum
+            {
+                type Output = adar::prelude::Flags<Self>;
+
+                #[inline(always)]
+                fn #method(self, rhs: adar::prelude::Flags<Self>) -> Self::Output {
+                    ::core::ops::#op::#method(adar::prelude::Flags::from(self), rhs)
+                }
+            }
+        }
+    });
+
+    quote! {
         #[derive(Copy, Clone)]
-        #[ReflectEnum]
+        #[ReflectEnum #reflect_args]
         #input
 
-        impl #impl_generics std::ops::BitOr for #ident #ty_generics #where_clause
+        #composites_impl
+
+        #bitflags_impl
+
+        #(#ops)*
+
+        impl #impl_generics ::core::ops::Not for #ident #ty_generics #where_clause
         where
             Self: adar::prelude::ReflectEnum
         {
             type Output = adar::prelude::Flags<Self>;
 
-            fn bitor(self, rhs: Self) -> Self::Output {
-                Flags::empty() | self | rhs
+            #[inline(always)]
+            fn not(self) -> Self::Output {
+                !adar::prelude::Flags::from(self)
             }
         }
     }
-    .into())
 }
 
-fn patch_flag_discriminants(data_enum: &mut DataEnum) -> syn::Result<()> {
-    let mut value = 1;
+/// Variant which isn't a single flag, e.g. `ReadWrite = Read | Write`, or a [`Group`]. The variants are removed
+/// from the enum, both are exposed as `Flags` constants instead.
+struct Composite {
+    attrs: Vec<Attribute>,
+    ident: Ident,
+    value: u128,
+}
+
+/// Named group of flags, declared with `#[group(AllReads = Read | ReadMeta)]` on the enum.
+struct Group {
+    attrs: Vec<Attribute>,
+    ident: Ident,
+    expr: Expr,
+}
+
+impl parse::Parse for Group {
+    fn parse(input: parse::ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+        Ok(Self {
+            attrs,
+            ident,
+            expr: input.parse()?,
+        })
+    }
+}
+
+/// Removes the `#[group(...)]` attributes from the enum.
+fn take_groups(attrs: &mut Vec<Attribute>) -> syn::Result<Vec<Group>> {
+    let mut groups = vec![];
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("group")) {
+        groups.extend(
+            attr.parse_args_with(punctuated::Punctuated::<Group, Token![,]>::parse_terminated)?,
+        );
+    }
+    attrs.retain(|attr| !attr.path().is_ident("group"));
+    Ok(groups)
+}
+
+/// Assigns a bit to every flag. Single bit constants are kept, the flags without a value get the lowest
+/// free bits in order. Every other variant is removed and returned as a composite.
+fn patch_flag_discriminants(
+    data_enum: &mut DataEnum,
+    groups: Vec<Group>,
+) -> syn::Result<Vec<Composite>> {
+    let mut values = HashMap::new();
+    let mut used = 0u128;
 
-    for variant in &mut data_enum.variants {
+    for variant in &data_enum.variants {
         if !matches!(variant.fields, Fields::Unit) {
             return Err(syn::Error::new(
                 Span::call_site(),
                 "#[FlagEnum] macro only supports unit enums",
             ));
         }
+        if let Some(value) = single_bit_literal(variant)? {
+            if let Some((owner, _)) = values.iter().find(|(_, &other)| other == value) {
+                return Err(syn::Error::new_spanned(
+                    &variant.discriminant.as_ref().unwrap().1,
+                    format!(
+                        "#[FlagEnum] bit {} of {} is already used by {}, use `{} = {}` for an alias",
+                        value.trailing_zeros(),
+                        variant.ident,
+                        owner,
+                        variant.ident,
+                        owner
+                    ),
+                ));
+            }
+            used |= value;
+            values.insert(variant.ident.to_string(), value);
+        }
+    }
 
-        variant.discriminant = Some((
-            Token![=](Span::call_site()),
-            Expr::Lit(ExprLit {
-                attrs: vec![],
-                lit: Lit::Int(LitInt::new(&value.to_string(), Span::call_site())),
-            }),
-        ));
+    for variant in &data_enum.variants {
+        if variant.discriminant.is_none() {
+            let value = 1u128.checked_shl((!used).trailing_zeros()).ok_or_else(|| {
+                syn::Error::new(variant.ident.span(), "#[FlagEnum] has too many flags")
+            })?;
+            used |= value;
+            values.insert(variant.ident.to_string(), value);
+        }
+    }
 
-        value *= 2;
+    let mut composites = vec![];
+    for mut variant in std::mem::take(&mut data_enum.variants) {
+        if let Some(&value) = values.get(&variant.ident.to_string()) {
+            variant.discriminant = Some((
+                Token![=](Span::call_site()),
+                Expr::Lit(ExprLit {
+                    attrs: vec![],
+                    lit: Lit::Int(LitInt::new(&value.to_string(), Span::call_site())),
+                }),
+            ));
+            data_enum.variants.push(variant);
+            continue;
+        }
+
+        let (_, expr) = variant.discriminant.take().unwrap();
+        let value = eval_composite(&variant.ident, &expr, &values, used)?;
+        composites.push(Composite {
+            attrs: variant.attrs,
+            ident: variant.ident,
+            value,
+        });
+    }
+    // Groups can refer to the composite variants and the previous groups
+    for composite in &composites {
+        values.insert(composite.ident.to_string(), composite.value);
+    }
+    for group in groups {
+        let value = eval_composite(&group.ident, &group.expr, &values, used)?;
+        values.insert(group.ident.to_string(), value);
+        composites.push(Composite {
+            attrs: group.attrs,
+            ident: group.ident,
+            value,
+        });
+    }
+    Ok(composites)
+}
+
+/// Flags stored in a `BitArray` are indexed by their discriminants, which must be left implicit.
+fn validate_big_flags(data_enum: &DataEnum) -> syn::Result<()> {
+    for variant in &data_enum.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "#[FlagEnum] macro only supports unit enums",
+            ));
+        }
+        if let Some((_, expr)) = &variant.discriminant {
+            return Err(syn::Error::new_spanned(
+                expr,
+                "#[FlagEnum] does not support explicit values with big storage",
+            ));
+        }
     }
     Ok(())
 }
+
+/// Gets the value of a variant like `Read = 0b001` or `Read = 1 << 0`, which declares a flag.
+fn single_bit_literal(variant: &Variant) -> syn::Result<Option<u128>> {
+    match &variant.discriminant {
+        Some((_, expr)) if is_constant(expr) => {
+            let value = eval_flag_expr(expr, &HashMap::new())?;
+            Ok((value.count_ones() == 1).then_some(value))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Checks if the expression doesn't refer to other flags.
+fn is_constant(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(_) => true,
+        Expr::Binary(ExprBinary { left, right, .. }) => is_constant(left) && is_constant(right),
+        Expr::Paren(ExprParen { expr, .. }) | Expr::Group(ExprGroup { expr, .. }) => {
+            is_constant(expr)
+        }
+        _ => false,
+    }
+}
+
+/// Evaluates a composite and checks that it only consists of the `used` bits.
+fn eval_composite(
+    ident: &Ident,
+    expr: &Expr,
+    values: &HashMap<String, u128>,
+    used: u128,
+) -> syn::Result<u128> {
+    let value = eval_flag_expr(expr, values)?;
+    if value == 0 {
+        return Err(syn::Error::new_spanned(
+            expr,
+            format!("#[FlagEnum] {} must have at least one bit set", ident),
+        ));
+    }
+    if value & !used != 0 {
+        return Err(syn::Error::new_spanned(
+            expr,
+            format!(
+                "#[FlagEnum] {} sets bits which do not belong to any flag ({:#b}), \
+                 declare single bit flags for them first",
+                ident,
+                value & !used
+            ),
+        ));
+    }
+    Ok(value)
+}
+
+/// Evaluates the value of a composite, which may combine literals and other flags with `|`.
+fn eval_flag_expr(expr: &Expr, values: &HashMap<String, u128>) -> syn::Result<u128> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => lit.base10_parse(),
+        Expr::Path(path) => {
+            let name = path
+                .path
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string());
+            name.and_then(|name| values.get(&name).copied())
+                .ok_or_else(|| syn::Error::new_spanned(path, "#[FlagEnum] unknown flag"))
+        }
+        Expr::Binary(ExprBinary {
+            left,
+            op: BinOp::BitOr(_),
+            right,
+            ..
+        }) => Ok(eval_flag_expr(left, values)? | eval_flag_expr(right, values)?),
+        Expr::Binary(ExprBinary {
+            left,
+            op: BinOp::Shl(_),
+            right,
+            ..
+        }) => {
+            let left = eval_flag_expr(left, values)?;
+            let shift = eval_flag_expr(right, values)?;
+            u32::try_from(shift)
+                .ok()
+                .and_then(|shift| left.checked_shl(shift))
+                .ok_or_else(|| syn::Error::new_spanned(expr, "#[FlagEnum] value is too large"))
+        }
+        Expr::Paren(ExprParen { expr, .. }) | Expr::Group(ExprGroup { expr, .. }) => {
+            eval_flag_expr(expr, values)
+        }
+        _ => Err(syn::Error::new_spanned(
+            expr,
+            "#[FlagEnum] values must be integer literals or flags combined with `|` and `<<`",
+        )),
+    }
+}
+
+/// Index of the bit of a flag patched by [`patch_flag_discriminants`].
+fn flag_bit(variant: &Variant) -> usize {
+    single_bit_literal(variant)
+        .ok()
+        .flatten()
+        .map_or(0, |value| value.trailing_zeros() as usize)
+}
+
+/// Smallest unsigned integer type which can hold the bits, used when the enum has no `#[repr]`.
+fn smallest_repr(bits: usize) -> &'static str {
+    match bits {
+        0..=8 => "u8",
+        9..=16 => "u16",
+        17..=32 => "u32",
+        33..=64 => "u64",
+        _ => "u128",
+    }
+}
+
+/// Checks that every flag fits into the integer type.
+fn validate_flag_bits(needed: usize, kind: &str, typ: &str) -> syn::Result<()> {
+    match integer_bits(typ) {
+        Some(bits) if needed > bits => Err(syn::Error::new(
+            Span::call_site(),
+            format!(
+                "#[FlagEnum] needs {} bits, but the {} {} can only hold {}",
+                needed, kind, typ, bits
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
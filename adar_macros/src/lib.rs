@@ -1,20 +1,24 @@
+mod enum_delegate;
+mod enum_from;
 mod enum_trait_deref;
 mod flags;
 mod reflect;
 mod state_machine;
+use enum_delegate::*;
+use enum_from::*;
 use enum_trait_deref::*;
 use flags::*;
 use proc_macro::TokenStream;
 use reflect::*;
 use state_machine::*;
-use syn::{parse::Nothing, parse_macro_input, DeriveInput, TypeTraitObject};
+use syn::{parse_macro_input, punctuated::Punctuated, DeriveInput, Item, Token, TypeTraitObject};
 
 #[allow(non_snake_case)]
 #[proc_macro_attribute]
 pub fn FlagEnum(attr: TokenStream, input: TokenStream) -> TokenStream {
-    parse_macro_input!(attr as Nothing);
+    let attr = parse_macro_input!(attr as ReflectEnumArgs);
     let input = parse_macro_input!(input as DeriveInput);
-    flag_enum_macro_inner(input)
+    flag_enum_macro_inner(attr, input)
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
@@ -22,9 +26,19 @@ pub fn FlagEnum(attr: TokenStream, input: TokenStream) -> TokenStream {
 #[allow(non_snake_case)]
 #[proc_macro_attribute]
 pub fn ReflectEnum(attr: TokenStream, input: TokenStream) -> TokenStream {
-    parse_macro_input!(attr as Nothing);
+    let attr = parse_macro_input!(attr as ReflectEnumArgs);
     let input = parse_macro_input!(input as DeriveInput);
-    reflect_enum_macro_inner(input)
+    reflect_enum_macro_inner(attr, input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+#[allow(non_snake_case)]
+#[proc_macro_attribute]
+pub fn ReflectStruct(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let attr = proc_macro2::TokenStream::from(attr);
+    let input = parse_macro_input!(input as DeriveInput);
+    reflect_struct_macro_inner(attr, input)
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
@@ -32,9 +46,10 @@ pub fn ReflectEnum(attr: TokenStream, input: TokenStream) -> TokenStream {
 #[allow(non_snake_case)]
 #[proc_macro_attribute]
 pub fn EnumTraitDeref(attr: TokenStream, input: TokenStream) -> TokenStream {
-    let attr = parse_macro_input!(attr as TypeTraitObject);
+    let attr =
+        parse_macro_input!(attr with Punctuated::<TypeTraitObject, Token![,]>::parse_terminated);
     let input = parse_macro_input!(input as DeriveInput);
-    enum_trait_deref_macro_inner(attr.into(), input, false)
+    enum_trait_deref_macro_inner(attr, input, false)
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
@@ -42,13 +57,63 @@ pub fn EnumTraitDeref(attr: TokenStream, input: TokenStream) -> TokenStream {
 #[allow(non_snake_case)]
 #[proc_macro_attribute]
 pub fn EnumTraitDerefMut(attr: TokenStream, input: TokenStream) -> TokenStream {
-    let attr = parse_macro_input!(attr as TypeTraitObject);
+    let attr =
+        parse_macro_input!(attr with Punctuated::<TypeTraitObject, Token![,]>::parse_terminated);
+    let input = parse_macro_input!(input as DeriveInput);
+    enum_trait_deref_macro_inner(attr, input, true)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+#[allow(non_snake_case)]
+#[proc_macro_attribute]
+pub fn EnumDelegate(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let attr = proc_macro2::TokenStream::from(attr);
+    let input = parse_macro_input!(input as Item);
+    enum_delegate_macro_inner(attr, input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+#[allow(non_snake_case)]
+#[proc_macro_attribute]
+pub fn EnumFrom(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let attr = proc_macro2::TokenStream::from(attr);
     let input = parse_macro_input!(input as DeriveInput);
-    enum_trait_deref_macro_inner(attr.into(), input, true)
+    enum_from_macro_inner(attr, input)
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
 
+/// Generates the states of a state machine from the variants of an enum. See `adar::state_machine`.
+///
+/// # Per-state arguments
+/// Every state receives the machine's `args` type, unless the variant asks for a view into it:
+/// - `#[args(T)]` converts with `From<&mut Args> for &mut T`.
+/// - `#[try_args(T)]` converts with `TryFrom<&mut Args> for &mut T`. The conversion error is converted into the
+///   `error` type of the machine and passed to `Machine::on_error()` from `State::try_on_enter()` and `State::try_on_update()`.
+///   The other callbacks receive `None` when the conversion fails.
+///
+/// The state receives a mutable reference into the machine's args, so its changes are seen by the caller.
+///
+/// ```ignore
+/// struct Input {
+///     login: LoginArgs,
+/// }
+///
+/// impl<'a> From<&'a mut Input> for &'a mut LoginArgs {
+///     fn from(input: &'a mut Input) -> Self {
+///         &mut input.login
+///     }
+/// }
+///
+/// #[StateEnum(args = Input)]
+/// enum Session {
+///     #[args(LoginArgs)]
+///     Login,
+///     Active,
+/// }
+/// ```
 #[allow(non_snake_case)]
 #[proc_macro_attribute]
 pub fn StateEnum(attr: TokenStream, input: TokenStream) -> TokenStream {
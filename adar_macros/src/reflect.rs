@@ -1,44 +1,164 @@
 use proc_macro2::Span;
-use quote::quote;
-use syn::*;
+use quote::{quote, ToTokens};
+use syn::{parse::*, *};
 
-pub fn reflect_enum_macro_inner(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
-    let Data::Enum(data_enum) = &input.data else {
+pub fn reflect_enum_macro_inner(
+    args: ReflectEnumArgs,
+    mut input: DeriveInput,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let Data::Enum(data_enum) = &mut input.data else {
         return Err(syn::Error::new(
             Span::call_site(),
             "#[ReflectEnum] macro only supports enums",
         ));
     };
+    let variant_attrs = data_enum
+        .variants
+        .iter_mut()
+        .map(|variant| ReflectAttrs::take(&mut variant.attrs))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let Data::Enum(data_enum) = &input.data else {
+        unreachable!()
+    };
+
+    if let Some(bitflags) = &args.bitflags {
+        return Err(syn::Error::new_spanned(
+            bitflags,
+            "bitflags is only supported by #[FlagEnum]",
+        ));
+    }
 
     let ident = &input.ident;
-    let variants = data_enum
+    let names = data_enum
+        .variants
+        .iter()
+        .zip(&variant_attrs)
+        .map(|(variant, attrs)| {
+            attrs.rename.as_ref().map_or_else(
+                || rename_all(&variant.ident.to_string(), args.rename_all.as_ref()),
+                LitStr::value,
+            )
+        })
+        .collect::<Vec<_>>();
+    // The skipped variants are not reflected, except by `name()`
+    let reflected = || {
+        data_enum
+            .variants
+            .iter()
+            .zip(&names)
+            .zip(&variant_attrs)
+            .filter(|(_, attrs)| !attrs.skip)
+            .map(|((variant, name), attrs)| (variant, name, attrs))
+    };
+
+    // Discriminants are counted from the last explicit one, which is evaluated in the repr of the enum
+    let discriminant_repr = discriminant_repr(&input);
+    let mut explicit = None;
+    let mut offset = 0usize;
+    let discriminants = data_enum
         .variants
         .iter()
         .map(|variant| {
-            let name_str = &variant.ident.to_string();
+            if let Some((_, expr)) = &variant.discriminant {
+                explicit = Some(expr);
+                offset = 0;
+            } else {
+                offset += 1;
+            }
+            match explicit {
+                Some(expr) if offset == 0 => {
+                    quote! {{ const D: #discriminant_repr = #expr; D } as i128}
+                }
+                Some(expr) => {
+                    quote! {({ const D: #discriminant_repr = #expr; D } + #offset as #discriminant_repr) as i128}
+                }
+                None => {
+                    let value = offset as i128 - 1;
+                    quote! {#value}
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let variants = reflected()
+        .zip(
+            discriminants
+                .iter()
+                .zip(&variant_attrs)
+                .filter(|(_, attrs)| !attrs.skip)
+                .map(|(discriminant, _)| discriminant),
+        )
+        .map(|((variant, name_str, attrs), discriminant)| {
             let variant_ident = &variant.ident;
-            if matches!(variant.fields, Fields::Unit) {
+            let variant = if matches!(variant.fields, Fields::Unit) {
                 quote! {
-                    EnumVariant::new(#name_str, Some(#ident::#variant_ident))
+                    adar::prelude::EnumVariant::new(#name_str, Some(#ident::#variant_ident))
                 }
             } else {
+                let fields = variant.fields.iter().enumerate().map(|(i, field)| {
+                    let name = field
+                        .ident
+                        .as_ref()
+                        .map_or_else(|| i.to_string(), |ident| ident.to_string());
+                    // `Self` is not available in the const
+                    let ty = replace_self(field.ty.to_token_stream(), ident);
+                    quote! {
+                        adar::prelude::FieldInfo::new(#name, ::core::any::TypeId::of::<#ty>())
+                    }
+                });
                 quote! {
-                    EnumVariant::new(#name_str, None)
+                    adar::prelude::EnumVariant::new(#name_str, None).with_fields(&[#(#fields),*])
                 }
-            }
+            };
+            let doc = attrs.doc.as_ref().map(|doc| quote! {.with_doc(#doc)});
+            let metadata = (!attrs.metadata.is_empty()).then(|| {
+                let metadata = attrs.metadata.iter().map(|(key, value)| {
+                    let key = key.to_string();
+                    quote! {(#key, #value)}
+                });
+                quote! {.with_metadata(&[#(#metadata),*])}
+            });
+            quote! {#variant.with_discriminant(#discriminant) #doc #metadata}
         })
         .collect::<Vec<_>>();
 
     let variants2 = data_enum
         .variants
         .iter()
-        .map(|variant| {
+        .zip(&names)
+        .map(|(variant, name)| {
             let ident = &variant.ident;
-            let ident_str = ident.to_string();
-            quote! {Self::#ident{..} => #ident_str}
+            quote! {Self::#ident{..} => #name}
         })
         .collect::<Vec<_>>();
 
+    let count = variants.len();
+    let mut positions = 0usize..;
+    let index_arms = data_enum
+        .variants
+        .iter()
+        .zip(&variant_attrs)
+        .map(|(variant, attrs)| {
+            let ident = &variant.ident;
+            let index = if attrs.skip {
+                quote! {None}
+            } else {
+                let index = positions.next().unwrap();
+                quote! {Some(#index)}
+            };
+            quote! {Self::#ident{..} => #index}
+        })
+        .collect::<Vec<_>>();
+    let index_impl = if data_enum.variants.is_empty() {
+        quote! {None}
+    } else {
+        quote! {
+            match self {
+                #(#index_arms),*
+            }
+        }
+    };
+
     let name_impl = if variants2.is_empty() {
         quote! {""}
     } else {
@@ -49,15 +169,64 @@ pub fn reflect_enum_macro_inner(input: DeriveInput) -> syn::Result<proc_macro2::
         }
     };
 
-    let count = variants.len();
-    let repr = parse_str::<Type>(&enum_repr(&input))?;
+    let from_name_arms = reflected()
+        .filter(|(variant, _, _)| matches!(variant.fields, Fields::Unit))
+        .map(|(variant, name, _)| {
+            let ident = &variant.ident;
+            quote! {#name => Some(Self::#ident),}
+        })
+        .collect::<Vec<_>>();
+
+    let big = args.is_big();
+    let storage_override = args.storage.is_some() && !big;
+    let repr = match args.storage {
+        _ if big => {
+            let words = data_enum.variants.len().div_ceil(64).max(1);
+            parse_quote! {adar::enums::BitArray<#words>}
+        }
+        Some(storage) => parse_quote! {#storage},
+        None => parse_str::<Type>(&enum_repr(&input))?,
+    };
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
-    let into_repr_impl = if data_enum
+    let all_unit = data_enum
         .variants
         .iter()
-        .all(|v| matches!(v.fields, Fields::Unit))
-    {
+        .all(|v| matches!(v.fields, Fields::Unit));
+
+    // Note: `self as #repr` truncates silently, so the discriminants are checked against a narrower storage
+    let storage_check = if all_unit && storage_override && input.generics.params.is_empty() {
+        let checks = data_enum.variants.iter().map(|variant| {
+            let variant_ident = &variant.ident;
+            let message = format!(
+                "The discriminant of {ident}::{variant_ident} does not fit in the storage type {}",
+                repr.to_token_stream()
+            );
+            quote! {
+                assert!(
+                    #ident::#variant_ident as i128 == (#ident::#variant_ident as #repr) as i128,
+                    #message
+                );
+            }
+        });
+        quote! {
+            const _: () = {
+                #(#checks)*
+            };
+        }
+    } else {
+        quote! {}
+    };
+
+    let into_repr_impl = if all_unit && big {
+        quote! {
+            impl #impl_generics Into<#repr> for #ident #ty_generics #where_clause {
+                fn into(self) -> #repr {
+                    adar::enums::BitArray::with_bit(self as usize)
+                }
+            }
+        }
+    } else if all_unit {
         quote! {
             impl #impl_generics Into<#repr> for #ident #ty_generics #where_clause {
                 fn into(self) -> #repr {
@@ -69,17 +238,124 @@ pub fn reflect_enum_macro_inner(input: DeriveInput) -> syn::Result<proc_macro2::
         quote! {}
     };
 
+    // Note: Evaluated as a constant, so `Flags::full()` does not have to combine the variants at each call
+    let all_bits_impl = if all_unit && input.generics.params.is_empty() {
+        let variant_idents = reflected().map(|(variant, _, _)| &variant.ident);
+        let all_bits = if big {
+            let words = data_enum.variants.len().div_ceil(64).max(1);
+            quote! {{
+                let mut words = [0u64; #words];
+                #(words[#ident::#variant_idents as usize / 64] |= 1 << (#ident::#variant_idents as usize % 64);)*
+                adar::enums::BitArray::from_words(words)
+            }}
+        } else {
+            quote! {0 #(| #ident::#variant_idents as #repr)*}
+        };
+        quote! {
+            fn all_bits() -> #repr {
+                const ALL_BITS: #repr = #all_bits;
+                ALL_BITS
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let try_from_repr_impl = if all_unit && !big {
+        let checks = reflected().map(|(variant, _, _)| {
+            let variant_ident = &variant.ident;
+            quote! {
+                if value == Self::#variant_ident as #repr {
+                    return Ok(Self::#variant_ident);
+                }
+            }
+        });
+        quote! {
+            impl #impl_generics TryFrom<#repr> for #ident #ty_generics #where_clause {
+                type Error = adar::prelude::UnknownDiscriminant<#repr>;
+
+                fn try_from(value: #repr) -> Result<Self, adar::prelude::UnknownDiscriminant<#repr>> {
+                    #(#checks)*
+                    Err(adar::prelude::UnknownDiscriminant(value))
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let display_impl = if args.display {
+        quote! {
+            impl #impl_generics ::core::fmt::Display for #ident #ty_generics #where_clause {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.pad(adar::prelude::ReflectEnum::name(self))
+                }
+            }
+
+            impl #impl_generics ::core::str::FromStr for #ident #ty_generics #where_clause {
+                type Err = adar::prelude::UnknownVariant;
+
+                fn from_str(s: &str) -> Result<Self, adar::prelude::UnknownVariant> {
+                    // Note: Inlined, because ReflectEnum::from_name requires Clone
+                    let value: Option<Self> = match s {
+                        #(#from_name_arms)*
+                        _ => None,
+                    };
+                    value.ok_or(adar::prelude::UnknownVariant)
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // Note: The expansion depends on the features of adar, so it's delegated to a declarative macro
+    let archive_impl =
+        if all_unit && !big && !data_enum.variants.is_empty() && input.generics.params.is_empty() {
+            let variant_idents = data_enum.variants.iter().map(|variant| &variant.ident);
+            quote! {
+                adar::__archive_unit_enum!(#ident, #repr, [#(#variant_idents),*]);
+            }
+        } else {
+            quote! {}
+        };
+
+    let clap_impl = if all_unit && input.generics.params.is_empty() {
+        let variant_idents = reflected().map(|(variant, _, _)| &variant.ident);
+        quote! {
+            adar::__clap_value_enum!(#ident, [#(#variant_idents),*]);
+        }
+    } else {
+        quote! {}
+    };
+
     Ok(quote! {
         #input
 
+        #storage_check
+
         #into_repr_impl
 
+        #try_from_repr_impl
+
+        #display_impl
+
+        #archive_impl
+
+        #clap_impl
+
+        impl #impl_generics adar::prelude::ReflectEnumConsts for #ident #ty_generics #where_clause {
+            const COUNT: usize = #count;
+            const VARIANTS: &'static [adar::prelude::EnumVariant<Self>] = &[#(#variants),*];
+        }
+
         impl #impl_generics adar::prelude::ReflectEnum for #ident #ty_generics #where_clause {
             type Type = #repr;
-            fn variants() -> &'static [adar::prelude::EnumVariant<#ident>] {
-                const VARIANTS : &[adar::prelude::EnumVariant<#ident>] = &[#(#variants),*];
-                VARIANTS
+
+            fn variants() -> &'static [adar::prelude::EnumVariant<Self>] {
+                <Self as adar::prelude::ReflectEnumConsts>::VARIANTS
             }
+
             fn count() -> usize {
                 #count
             }
@@ -87,24 +363,359 @@ pub fn reflect_enum_macro_inner(input: DeriveInput) -> syn::Result<proc_macro2::
             fn name(&self) -> &'static str {
                 #name_impl
             }
+
+            fn index(&self) -> Option<usize> {
+                #index_impl
+            }
+
+            fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    #(#from_name_arms)*
+                    _ => None,
+                }
+            }
+
+            #all_bits_impl
         }
+    })
+}
+
+pub fn reflect_struct_macro_inner(
+    args: proc_macro2::TokenStream,
+    mut input: DeriveInput,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if !args.is_empty() {
+        return Err(syn::Error::new_spanned(
+            args,
+            "#[ReflectStruct] macro takes no arguments",
+        ));
     }
-    .into())
+    let Data::Struct(data_struct) = &mut input.data else {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "#[ReflectStruct] macro only supports structs",
+        ));
+    };
+    let field_attrs = data_struct
+        .fields
+        .iter_mut()
+        .map(|field| ReflectAttrs::take(&mut field.attrs))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let Data::Struct(data_struct) = &input.data else {
+        unreachable!()
+    };
+
+    let reflected = data_struct
+        .fields
+        .iter()
+        .enumerate()
+        .zip(&field_attrs)
+        .filter(|(_, attrs)| !attrs.skip)
+        .map(|((i, field), attrs)| {
+            let member = field
+                .ident
+                .clone()
+                .map_or_else(|| Member::Unnamed(Index::from(i)), Member::Named);
+            (field, member, attrs)
+        })
+        .collect::<Vec<_>>();
+
+    let fields = reflected.iter().map(|(field, member, attrs)| {
+        let name = attrs.rename.as_ref().map_or_else(
+            || match member {
+                Member::Named(ident) => ident.to_string(),
+                Member::Unnamed(index) => index.index.to_string(),
+            },
+            LitStr::value,
+        );
+        let ty = &field.ty;
+        let doc = attrs.doc.as_ref().map(|doc| quote! {.with_doc(#doc)});
+        let metadata = (!attrs.metadata.is_empty()).then(|| {
+            let metadata = attrs.metadata.iter().map(|(key, value)| {
+                let key = key.to_string();
+                quote! {(#key, #value)}
+            });
+            quote! {.with_metadata(&[#(#metadata),*])}
+        });
+        quote! {
+            adar::prelude::FieldInfo::new(#name, ::core::any::TypeId::of::<#ty>()) #doc #metadata
+        }
+    });
+    let members = reflected
+        .iter()
+        .map(|(_, member, _)| member)
+        .collect::<Vec<_>>();
+    let indices = 0..members.len();
+    let indices2 = indices.clone();
+    let count = members.len();
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        #input
+
+        impl #impl_generics adar::prelude::ReflectStruct for #ident #ty_generics #where_clause {
+            const COUNT: usize = #count;
+            const FIELDS: &'static [adar::prelude::FieldInfo] = &[#(#fields),*];
+
+            fn field_at(&self, index: usize) -> Option<&dyn ::core::any::Any> {
+                match index {
+                    #(#indices => Some(&self.#members),)*
+                    _ => None,
+                }
+            }
+
+            fn field_at_mut(&mut self, index: usize) -> Option<&mut dyn ::core::any::Any> {
+                match index {
+                    #(#indices2 => Some(&mut self.#members),)*
+                    _ => None,
+                }
+            }
+        }
+    })
+}
+
+/// Replaces `Self` with the name of the type in the tokens.
+fn replace_self(tokens: proc_macro2::TokenStream, ident: &Ident) -> proc_macro2::TokenStream {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            proc_macro2::TokenTree::Ident(i) if i == "Self" => {
+                proc_macro2::TokenTree::Ident(ident.clone())
+            }
+            proc_macro2::TokenTree::Group(group) => {
+                let mut replaced =
+                    proc_macro2::Group::new(group.delimiter(), replace_self(group.stream(), ident));
+                replaced.set_span(group.span());
+                proc_macro2::TokenTree::Group(replaced)
+            }
+            token => token,
+        })
+        .collect()
+}
+
+/// Reflected attributes of a variant or a field: the doc comment and
+/// `#[reflect(skip, rename = "name", key = "value")]`.
+#[derive(Default)]
+struct ReflectAttrs {
+    doc: Option<String>,
+    metadata: Vec<(Ident, LitStr)>,
+    /// `#[reflect(skip)]`
+    skip: bool,
+    /// `#[reflect(rename = "name")]`
+    rename: Option<LitStr>,
+}
+
+impl ReflectAttrs {
+    /// Collects the attributes and removes `#[reflect(...)]`, which is not a real attribute.
+    fn take(attrs: &mut Vec<Attribute>) -> syn::Result<Self> {
+        let mut result = Self::default();
+        let mut doc = vec![];
+        for attr in attrs.iter() {
+            if attr.path().is_ident("doc") {
+                if let Meta::NameValue(MetaNameValue {
+                    value:
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Str(line),
+                            ..
+                        }),
+                    ..
+                }) = &attr.meta
+                {
+                    let line = line.value();
+                    doc.push(line.strip_prefix(' ').unwrap_or(&line).to_string());
+                }
+            } else if attr.path().is_ident("reflect") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("skip") {
+                        result.skip = true;
+                        return Ok(());
+                    }
+                    if meta.path.is_ident("rename") {
+                        result.rename = Some(meta.value()?.parse()?);
+                        return Ok(());
+                    }
+                    let key = meta.path.require_ident()?.clone();
+                    if result.metadata.iter().any(|(k, _)| *k == key) {
+                        return Err(meta.error(format!("Duplicate key: {}", key)));
+                    }
+                    result.metadata.push((key, meta.value()?.parse()?));
+                    Ok(())
+                })?;
+            }
+        }
+        let doc = doc.join("\n");
+        if !doc.trim().is_empty() {
+            result.doc = Some(doc.trim().to_string());
+        }
+        attrs.retain(|attr| !attr.path().is_ident("reflect"));
+        Ok(result)
+    }
+}
+
+/// Case conventions of `#[ReflectEnum(rename_all = "...")]`, the same as serde's.
+const RENAME_RULES: &[&str] = &[
+    "lowercase",
+    "UPPERCASE",
+    "PascalCase",
+    "camelCase",
+    "snake_case",
+    "SCREAMING_SNAKE_CASE",
+    "kebab-case",
+    "SCREAMING-KEBAB-CASE",
+];
+
+/// Converts the PascalCase name of a variant to the case convention in [`RENAME_RULES`].
+fn rename_all(name: &str, rule: Option<&LitStr>) -> String {
+    let snake_case = || snake_case(name);
+    match rule.map(LitStr::value).as_deref() {
+        Some("lowercase") => name.to_ascii_lowercase(),
+        Some("UPPERCASE") => name.to_ascii_uppercase(),
+        Some("camelCase") => {
+            let mut chars = name.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_ascii_lowercase().to_string() + chars.as_str()
+            })
+        }
+        Some("snake_case") => snake_case(),
+        Some("SCREAMING_SNAKE_CASE") => snake_case().to_ascii_uppercase(),
+        Some("kebab-case") => snake_case().replace('_', "-"),
+        Some("SCREAMING-KEBAB-CASE") => snake_case().to_ascii_uppercase().replace('_', "-"),
+        _ => name.to_string(),
+    }
+}
+
+/// Converts a PascalCase name to snake_case.
+pub fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.char_indices() {
+        if c.is_uppercase() && i != 0 {
+            snake.push('_');
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+    snake
 }
 
 pub fn enum_repr(input: &DeriveInput) -> String {
     const DEFAULT_REPR: &str = "u32";
     for attr in &input.attrs {
         if attr.path().is_ident("repr") {
-            if let Ok(meta) = attr.parse_args() {
-                if let syn::Meta::Path(path) = meta {
-                    return path
-                        .get_ident()
-                        .map(|i| i.to_string())
-                        .unwrap_or(DEFAULT_REPR.into());
-                }
+            if let Ok(syn::Meta::Path(path)) = attr.parse_args() {
+                return path
+                    .get_ident()
+                    .map(|i| i.to_string())
+                    .unwrap_or(DEFAULT_REPR.into());
             }
         }
     }
     DEFAULT_REPR.into()
 }
+
+/// Integer type of the discriminants, from `#[repr(...)]` (which may list other hints, e.g. `C`),
+/// or `isize` like the compiler.
+fn discriminant_repr(input: &DeriveInput) -> Ident {
+    input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+        .filter_map(|attr| {
+            attr.parse_args_with(punctuated::Punctuated::<Ident, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .find(|ident| {
+            integer_bits(&ident.to_string()).is_some() || ident == "usize" || ident == "isize"
+        })
+        .unwrap_or_else(|| Ident::new("isize", Span::call_site()))
+}
+
+/// Number of bits which can hold a positive discriminant in the integer type.
+pub fn integer_bits(typ: &str) -> Option<usize> {
+    Some(match typ {
+        "u8" => 8,
+        "u16" => 16,
+        "u32" => 32,
+        "u64" => 64,
+        "u128" => 128,
+        "i8" => 7,
+        "i16" => 15,
+        "i32" => 31,
+        "i64" => 63,
+        "i128" => 127,
+        _ => return None,
+    })
+}
+
+/// Arguments of `#[ReflectEnum(storage = u64, rename_all = "snake_case", display)]` and
+/// `#[FlagEnum(storage = u64, bitflags = MyBitflags, rename_all = "snake_case")]`.
+#[derive(Default)]
+pub struct ReflectEnumArgs {
+    /// Overrides [`enum_repr`] as the reflected type. `big` selects `adar::enums::BitArray`.
+    pub storage: Option<Ident>,
+    /// Type generated by `bitflags!`, which gets `From` conversions. Only supported by `#[FlagEnum]`.
+    pub bitflags: Option<Path>,
+    /// Case convention of the reflected names, one of [`RENAME_RULES`].
+    pub rename_all: Option<LitStr>,
+    /// Implements `Display` and `FromStr` with the reflected names.
+    pub display: bool,
+}
+
+impl ReflectEnumArgs {
+    /// Checks if the flags are stored in a `BitArray` instead of an integer.
+    pub fn is_big(&self) -> bool {
+        self.storage
+            .as_ref()
+            .is_some_and(|storage| storage == "big")
+    }
+}
+
+impl Parse for ReflectEnumArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut result = Self::default();
+        while !input.is_empty() {
+            let ident: Ident = input.parse()?;
+            if ident == "display" {
+                result.display = true;
+                if !input.is_empty() {
+                    input.parse::<Token![,]>()?;
+                }
+                continue;
+            }
+            input.parse::<Token![=]>()?;
+            if ident == "storage" {
+                let storage: Ident = input.parse()?;
+                if !matches!(
+                    storage.to_string().as_str(),
+                    "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "big"
+                ) {
+                    return Err(syn::Error::new(
+                        storage.span(),
+                        "Storage must be an unsigned integer type or big",
+                    ));
+                }
+                result.storage = Some(storage);
+            } else if ident == "bitflags" {
+                result.bitflags = Some(input.parse()?);
+            } else if ident == "rename_all" {
+                let rule: LitStr = input.parse()?;
+                if !RENAME_RULES.contains(&rule.value().as_str()) {
+                    return Err(syn::Error::new(
+                        rule.span(),
+                        format!("rename_all must be one of: {}", RENAME_RULES.join(", ")),
+                    ));
+                }
+                result.rename_all = Some(rule);
+            } else {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!("Invalid identifier: {}", ident),
+                ));
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(result)
+    }
+}
@@ -1,6 +1,7 @@
 use proc_macro2::{Span, TokenStream};
-use quote::quote;
-use syn::{parse::*, *};
+use quote::{format_ident, quote, quote_spanned};
+use std::collections::{HashMap, VecDeque};
+use syn::{parse::*, punctuated::Punctuated, *};
 
 pub fn state_enum_macro_inner(
     args: StateMachineArgs,
@@ -29,32 +30,172 @@ pub fn state_enum_macro_inner(
                 typ: ctx_type,
                 wher: ctx_where,
             },
+        event:
+            ComplexType {
+                generics: event_gen,
+                typ: event_type,
+                wher: event_where,
+            },
+        error:
+            ComplexType {
+                generics: error_gen,
+                typ: error_type,
+                wher: error_where,
+            },
+        serde,
+        initial,
+        unreachable,
     } = args;
 
-    let combined_gen = combine_generics(args_gen, ctx_gen);
-    let combined_where = combine_where(args_where, ctx_where);
+    let combined_gen = combine_generics(
+        combine_generics(combine_generics(args_gen, ctx_gen), event_gen),
+        error_gen,
+    );
+    let combined_where = combine_where(
+        combine_where(combine_where(args_where, ctx_where), event_where),
+        error_where,
+    );
 
     let args_type = args_type.map(|v| quote! {#v}).unwrap_or(quote! {()});
     let ctx_type = ctx_type.map(|v| quote! {#v}).unwrap_or(quote! {()});
+    let event_type = event_type.map(|v| quote! {#v}).unwrap_or(quote! {()});
+    let error_type = error_type
+        .map(|v| quote! {#v})
+        .unwrap_or(quote! {std::convert::Infallible});
+
+    if serde {
+        input.attrs.push(parse_quote! {
+            #[derive(adar::serde::Serialize, adar::serde::Deserialize)]
+        });
+        input.attrs.push(parse_quote! {
+            #[serde(crate = "adar::serde")]
+        });
+    }
 
     let mut derive = quote! {};
     for attr in &input.attrs {
         if attr.path().is_ident("derive") {
             if let Meta::List(list) = &attr.meta {
                 let tokens = &list.tokens;
-                derive = quote! {#[derive(#tokens)]};
-                break;
+                derive.extend(quote! {#[derive(#tokens)]});
             }
         }
     }
+    if serde {
+        derive.extend(quote! {#[serde(crate = "adar::serde")]});
+    }
 
-    let mut end_state = quote! {};
+    // `#[state_attr(...)]` on the enum is forwarded to every state struct
+    let state_attrs = forwarded_attrs(&input.attrs, "state_attr")?;
+    input
+        .attrs
+        .retain(|attr| !attr.path().is_ident("state_attr"));
+    // `#[cfg(...)]` of a variant is forwarded to the state struct and to everything generated for it
+    let cfgs = data_enum
+        .variants
+        .iter()
+        .map(|variant| (variant.ident.to_string(), cfg_attrs(variant)))
+        .collect::<HashMap<_, _>>();
+
+    let mut end_variants = vec![];
+    let mut end_cfgs = vec![];
+    let mut end_output = None;
+    let mut history_variants = vec![];
+    let mut timeout_arms = vec![];
+    let mut on_timeout_arms = vec![];
     let mut variants = vec![];
     let mut enum_variants = vec![];
     let mut variant_structs = vec![];
+    let mut update_arms = vec![];
+    let mut transition_table = TransitionTable::default();
+    let mut state_infos = vec![];
+    let mut field_arms = vec![];
+    let mut arg_converts = vec![];
+    let mut try_arg_converts = vec![];
+    let mut enter_actions = vec![];
+    let mut leave_actions = vec![];
+    let mut variant_cfgs = vec![];
     for variant in &data_enum.variants {
         let variant_ident = &variant.ident;
-        if variant_ident == "EndState" {
+        let cfg = &cfgs[&variant_ident.to_string()];
+        transition_table.add_state(variant)?;
+        // States with their own args receive a view into the machine's args, converted with `From<&mut Args>`
+        // or `TryFrom<&mut Args>`. Conversion errors are returned from the fallible callbacks.
+        let (variant_args_type, arg_convert, try_arg_convert) = match variant
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("args") || attr.path().is_ident("try_args"))
+        {
+            Some(attr) if attr.path().is_ident("args") => {
+                let typ: Type = attr.parse_args()?;
+                let convert = quote! {
+                    let args = args.map(<&mut #typ as From<&mut #args_type>>::from);
+                };
+                (quote! {#typ}, convert.clone(), convert)
+            }
+            Some(attr) => {
+                let typ: Type = attr.parse_args()?;
+                let convert = quote! {
+                    // The infallible callbacks can't report the error, the state receives no args instead
+                    let args = args.and_then(|args| <&mut #typ as TryFrom<&mut #args_type>>::try_from(args).ok());
+                };
+                let try_convert = quote! {
+                    let args = args.map(<&mut #typ as TryFrom<&mut #args_type>>::try_from).transpose()?;
+                };
+                (quote! {#typ}, convert, try_convert)
+            }
+            None => (args_type.clone(), quote! {}, quote! {}),
+        };
+        let (field_names, field_members): (Vec<_>, Vec<_>) = variant
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| match &field.ident {
+                Some(ident) => (ident.to_string(), Member::Named(ident.clone())),
+                None => (index.to_string(), Member::Unnamed(index.into())),
+            })
+            .unzip();
+        field_arms.push(quote! {
+            #cfg
+            Self::#variant_ident(_s) => vec![#(adar::prelude::StateField {
+                name: #field_names,
+                value: &_s.#field_members,
+            }),*]
+        });
+        let has_attr = |name| variant.attrs.iter().any(|attr| attr.path().is_ident(name));
+        let info_name = variant_ident.to_string();
+        let has_data = !variant.fields.is_empty();
+        let is_end = variant_ident == "EndState" || has_attr("end");
+        let has_history = has_attr("history");
+        state_infos.push(quote! {
+            adar::prelude::StateInfo {
+                name: #info_name,
+                has_data: #has_data,
+                is_end: #is_end,
+                has_history: #has_history,
+            }
+        });
+        if variant_ident == "EndState" && !variant.fields.is_empty() {
+            // EndState with a payload is generated like the other states, but without behavior
+            let Fields::Unnamed(fields) = &variant.fields else {
+                return Err(syn::Error::new(
+                    variant_ident.span(),
+                    "EndState payload must be a single unnamed field: EndState(Output)",
+                ));
+            };
+            let [field] = fields.unnamed.iter().collect::<Vec<_>>()[..] else {
+                return Err(syn::Error::new(
+                    variant_ident.span(),
+                    "EndState payload must be a single unnamed field: EndState(Output)",
+                ));
+            };
+            end_output = Some(field.ty.clone());
+            end_variants.push(variant_ident);
+            end_cfgs.push(cfg);
+            variant_structs.push(quote! {
+                impl #combined_gen adar::prelude::State #combined_gen for #variant_ident #combined_where {}
+            });
+        } else if variant_ident == "EndState" {
             enum_variants.push(quote! {
                 #variant_ident(adar::prelude::EndState)
             });
@@ -64,44 +205,232 @@ pub fn state_enum_macro_inner(
                         #ident::EndState (adar::prelude::EndState)
                     }
                 }
-            });
-            end_state = quote! {
-                impl adar::prelude::HasEndState for #ident {
-                    fn is_finished(&self) -> bool {
-                        matches!(self, #ident::EndState(_))
+
+                impl adar::prelude::StateVariant<#ident> for adar::prelude::EndState {
+                    fn downcast(state: &#ident) -> Option<&Self> {
+                        match state {
+                            #ident::EndState(s) => Some(s),
+                            #[allow(unreachable_patterns)]
+                            _ => None,
+                        }
+                    }
+
+                    fn downcast_mut(state: &mut #ident) -> Option<&mut Self> {
+                        match state {
+                            #ident::EndState(s) => Some(s),
+                            #[allow(unreachable_patterns)]
+                            _ => None,
+                        }
                     }
                 }
-            };
+            });
+            end_variants.push(variant_ident);
+            end_cfgs.push(cfg);
             continue;
         }
 
+        if variant
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("history"))
+        {
+            history_variants.push(quote! {
+                #cfg
+                Self::#variant_ident(_) => true
+            });
+        }
+
+        if variant.attrs.iter().any(|attr| attr.path().is_ident("end")) {
+            end_variants.push(variant_ident);
+            end_cfgs.push(cfg);
+        }
+
+        match variant
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("timeout"))
+        {
+            Some(attr) => {
+                let Timeout { nanos, to } = attr.parse_args()?;
+                timeout_arms.push(quote! {
+                    #cfg
+                    Self::#variant_ident(_) => Some(std::time::Duration::from_nanos(#nanos))
+                });
+                on_timeout_arms.push(quote! {
+                    #cfg
+                    Self::#variant_ident(_) => Some((#to).into())
+                });
+            }
+            None => {
+                timeout_arms.push(quote! {
+                    #cfg
+                    Self::#variant_ident(s) => <#variant_ident as adar::prelude::State #combined_gen>::timeout(s)
+                });
+                on_timeout_arms.push(quote! {
+                    #cfg
+                    Self::#variant_ident(s) => <#variant_ident as adar::prelude::State #combined_gen>::on_timeout(s, context)
+                });
+            }
+        }
+
+        match variant
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("regions"))
+        {
+            Some(attr) => {
+                let RegionsArgs { then } = match &attr.meta {
+                    Meta::Path(_) => RegionsArgs { then: None },
+                    _ => attr.parse_args()?,
+                };
+                let members: Vec<Member> = match &variant.fields {
+                    Fields::Named(fields) => fields
+                        .named
+                        .iter()
+                        .filter_map(|field| field.ident.clone().map(Member::Named))
+                        .collect(),
+                    Fields::Unnamed(fields) => (0..fields.unnamed.len())
+                        .map(|index| Member::Unnamed(index.into()))
+                        .collect(),
+                    Fields::Unit => {
+                        return Err(syn::Error::new_spanned(
+                            attr,
+                            "#[regions] requires a state with fields",
+                        ))
+                    }
+                };
+                variant_structs.push(quote! {
+                    #cfg
+                    impl adar::prelude::Regions for #variant_ident {
+                        fn update_regions(&mut self) {
+                            #(adar::prelude::Region::update_region(&mut self.#members);)*
+                        }
+
+                        fn regions_finished(&self) -> bool {
+                            true #(&& adar::prelude::Region::is_region_finished(&self.#members))*
+                        }
+                    }
+                });
+                let then = match then {
+                    Some(then) => quote! {
+                        Ok(None) if adar::prelude::Regions::regions_finished(s) => {
+                            Ok(Some(adar::prelude::Transition::To((#then).into())))
+                        }
+                    },
+                    None => quote! {},
+                };
+                update_arms.push(quote! {
+                    #cfg
+                    Self::#variant_ident(s) => {
+                        #try_arg_convert
+                        adar::prelude::Regions::update_regions(s);
+                        match #variant_ident::try_on_update(s, args, context) {
+                            #then
+                            result => result,
+                        }
+                    }
+                });
+            }
+            None => update_arms.push(quote! {
+                #cfg
+                Self::#variant_ident(s) => {
+                    #try_arg_convert
+                    #variant_ident::try_on_update(s, args, context)
+                }
+            }),
+        }
+
+        // Entry and exit actions declared in the macro run around the callbacks of the variant
+        let action = |name: &str| -> syn::Result<TokenStream> {
+            variant
+                .attrs
+                .iter()
+                .find(|attr| attr.path().is_ident(name))
+                .map(|attr| {
+                    let action = &attr.meta.require_list()?.tokens;
+                    Ok(quote! {adar::prelude::run_action(context, #action);})
+                })
+                .unwrap_or_else(|| Ok(quote! {}))
+        };
+        enter_actions.push(action("on_enter")?);
+        leave_actions.push(action("on_leave")?);
+
         variants.push(quote! {
             #variant_ident
         });
+        arg_converts.push(arg_convert);
+        try_arg_converts.push(try_arg_convert);
+        variant_cfgs.push(cfg);
 
         enum_variants.push(quote! {
             #variant_ident(#variant_ident)
         });
 
         let meta = quote! {
+            #cfg
             impl #combined_gen adar::prelude::StateTypes #combined_gen for #variant_ident #combined_where {
                 type States = #ident;
-                type Args = #args_type;
+                type Args = #variant_args_type;
                 type Context = #ctx_type;
             }
 
+            #cfg
+            impl #combined_gen adar::prelude::HasEvent #combined_gen for #variant_ident #combined_where {
+                type Event = #event_type;
+            }
+
+            #cfg
+            impl #combined_gen adar::prelude::HasError #combined_gen for #variant_ident #combined_where {
+                type Error = #error_type;
+            }
+
+            #cfg
             impl Into<#ident> for #variant_ident {
                 fn into(self) -> #ident {
                     #ident::#variant_ident (self)
                 }
             }
+
+            #cfg
+            impl adar::prelude::StateVariant<#ident> for #variant_ident {
+                fn downcast(state: &#ident) -> Option<&Self> {
+                    match state {
+                        #ident::#variant_ident(s) => Some(s),
+                        #[allow(unreachable_patterns)]
+                        _ => None,
+                    }
+                }
+
+                fn downcast_mut(state: &mut #ident) -> Option<&mut Self> {
+                    match state {
+                        #ident::#variant_ident(s) => Some(s),
+                        #[allow(unreachable_patterns)]
+                        _ => None,
+                    }
+                }
+            }
         };
 
+        // Single field states share the layout (and the niches) of their field
+        let repr = (variant.fields.len() == 1).then(|| quote! {#[repr(transparent)]});
+        let docs = variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"));
+        let variant_attrs = forwarded_attrs(&variant.attrs, "state_attr")?;
+        let derive = quote! {
+            #cfg
+            #(#docs)*
+            #derive
+            #state_attrs
+            #variant_attrs
+        };
         match &variant.fields {
             Fields::Named(fields) => {
                 let fields_named = fields.named.iter();
                 variant_structs.push(quote! {
                     #derive
+                    #repr
                     #visibility struct #variant_ident{
                         #(#fields_named),*,
                     }
@@ -119,6 +448,7 @@ pub fn state_enum_macro_inner(
                 let fields_unnamed = fields.unnamed.iter();
                 variant_structs.push(quote! {
                     #derive
+                    #repr
                     #visibility struct #variant_ident(#(#fields_unnamed),*,);
                     #meta
                 });
@@ -126,8 +456,219 @@ pub fn state_enum_macro_inner(
         }
     }
 
+    let has_history_impl = if history_variants.is_empty() {
+        quote! {false}
+    } else {
+        quote! {
+            match self {
+                #(#history_variants,)*
+                #[allow(unreachable_patterns)]
+                _ => false,
+            }
+        }
+    };
+
+    let finished_state = end_output.map(|output| {
+        quote! {
+            impl adar::prelude::FinishedState for #ident {
+                type Output = #output;
+
+                fn output(&self) -> Option<&Self::Output> {
+                    match self {
+                        Self::EndState(s) => Some(&s.0),
+                        #[allow(unreachable_patterns)]
+                        _ => None,
+                    }
+                }
+
+                fn into_output(self) -> Option<Self::Output> {
+                    match self {
+                        Self::EndState(s) => Some(s.0),
+                        #[allow(unreachable_patterns)]
+                        _ => None,
+                    }
+                }
+            }
+        }
+    });
+
+    let end_state = if end_variants.is_empty() {
+        quote! {}
+    } else {
+        let outcome = format_ident!("{}Outcome", ident);
+        let doc = format!(
+            "Terminal states of [`{}`]. See [`adar::prelude::HasOutcome::outcome()`].",
+            ident
+        );
+        quote! {
+            #[doc = #doc]
+            #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+            #visibility enum #outcome {
+                #(#end_cfgs #end_variants),*
+            }
+
+            impl adar::prelude::HasEndState for #ident {
+                fn is_finished(&self) -> bool {
+                    adar::prelude::HasOutcome::outcome(self).is_some()
+                }
+            }
+
+            impl adar::prelude::HasOutcome for #ident {
+                type Outcome = #outcome;
+
+                fn outcome(&self) -> Option<Self::Outcome> {
+                    match self {
+                        #(#end_cfgs Self::#end_variants(_) => Some(#outcome::#end_variants)),*,
+                        #[allow(unreachable_patterns)]
+                        _ => None,
+                    }
+                }
+            }
+        }
+    };
+
+    let state_name_impl = if input.attrs.iter().any(|attr| {
+        attr.path()
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "ReflectEnum")
+    }) {
+        quote! {<Self as adar::prelude::ReflectEnum>::name(self)}
+    } else {
+        let names = data_enum
+            .variants
+            .iter()
+            .map(|variant| variant.ident.to_string());
+        let variant_idents = data_enum.variants.iter().map(|variant| &variant.ident);
+        let variant_cfgs = data_enum
+            .variants
+            .iter()
+            .map(|variant| &cfgs[&variant.ident.to_string()]);
+        quote! {
+            match self {
+                #(#variant_cfgs Self::#variant_idents(_) => #names),*
+            }
+        }
+    };
+
+    let unreachable_states = transition_table.check(initial.as_ref(), unreachable)?;
+
+    let transition_edges = transition_table.declared.then(|| {
+        let edge_ident = format_ident!("{}Edge", ident);
+        let mut edges: Vec<(&Ident, &Ident)> = vec![];
+        for (state, state_edges) in &transition_table.states {
+            for Edge { target, .. } in state_edges {
+                if !edges.contains(&(state, target)) {
+                    edges.push((state, target));
+                }
+            }
+        }
+        let (from, to): (Vec<_>, Vec<_>) = edges.into_iter().unzip();
+        let edge_variants = from
+            .iter()
+            .zip(&to)
+            .map(|(from, to)| format_ident!("{}To{}", from, to))
+            .collect::<Vec<_>>();
+        let edge_cfgs = from
+            .iter()
+            .zip(&to)
+            .map(|(from, to)| {
+                let from = cfgs.get(&from.to_string());
+                let to = cfgs.get(&to.to_string());
+                quote! {#from #to}
+            })
+            .collect::<Vec<_>>();
+        let doc = format!(
+            "Declared transitions of [`{}`]. See [`adar::prelude::TransitionEdges::edge()`].",
+            ident
+        );
+        quote! {
+            #[doc = #doc]
+            #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+            #visibility enum #edge_ident {
+                #(#edge_cfgs #edge_variants),*
+            }
+
+            impl adar::prelude::TransitionEdges for #ident {
+                type Edge = #edge_ident;
+
+                fn edge(&self, to: &Self) -> Option<Self::Edge> {
+                    match (self, to) {
+                        #(#edge_cfgs (Self::#from(_), Self::#to(_)) => Some(#edge_ident::#edge_variants),)*
+                        #[allow(unreachable_patterns)]
+                        _ => None,
+                    }
+                }
+            }
+        }
+    });
+
+    let topology = {
+        let name = ident.to_string();
+        let initial = match initial.as_ref().and_then(state_name) {
+            Some(initial) => {
+                let initial = initial.to_string();
+                quote! {Some(#initial)}
+            }
+            None => quote! {None},
+        };
+        let transitions = transition_table.states.iter().flat_map(|(state, edges)| {
+            let from = state.to_string();
+            edges.iter().map(move |Edge { target, timeout }| {
+                let to = target.to_string();
+                quote! {
+                    adar::prelude::TransitionInfo {
+                        from: #from,
+                        to: #to,
+                        timeout: #timeout,
+                    }
+                }
+            })
+        });
+        let doc = format!(
+            "Static description of the states and the declared transitions of [`{}`]. See [`adar::prelude::Topology`].",
+            ident
+        );
+        quote! {
+            impl #ident {
+                #[doc = #doc]
+                #visibility const fn topology() -> &'static adar::prelude::Topology {
+                    const TOPOLOGY: adar::prelude::Topology = adar::prelude::Topology {
+                        name: #name,
+                        initial: #initial,
+                        states: &[#(#state_infos),*],
+                        transitions: &[#(#transitions),*],
+                    };
+                    &TOPOLOGY
+                }
+            }
+        }
+    };
+
+    let initial_state = initial.map(|initial| {
+        quote! {
+            impl adar::prelude::InitialState for #ident {
+                fn initial() -> Self {
+                    (#initial).into()
+                }
+            }
+        }
+    });
+
     // Patch the enum
     for variant in &mut data_enum.variants {
+        variant.attrs.retain(|attr| {
+            !attr.path().is_ident("history")
+                && !attr.path().is_ident("end")
+                && !attr.path().is_ident("timeout")
+                && !attr.path().is_ident("transitions")
+                && !attr.path().is_ident("regions")
+                && !attr.path().is_ident("args")
+                && !attr.path().is_ident("try_args")
+                && !attr.path().is_ident("on_enter")
+                && !attr.path().is_ident("on_leave")
+                && !attr.path().is_ident("state_attr")
+        });
         let variant_name = &variant.ident;
         let variant_ty = Ident::new(&variant_name.to_string(), variant_name.span());
         variant.fields = Fields::Unnamed(syn::FieldsUnnamed {
@@ -160,33 +701,420 @@ pub fn state_enum_macro_inner(
             type Context = #ctx_type;
         }
 
+        impl #combined_gen adar::prelude::HasEvent #combined_gen for #ident #combined_where {
+            type Event = #event_type;
+        }
+
+        impl #combined_gen adar::prelude::HasError #combined_gen for #ident #combined_where {
+            type Error = #error_type;
+        }
+
         impl #combined_gen adar::prelude::State #combined_gen for #ident #combined_where
         {
             fn on_enter(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) {
                 match self {
-                    #(Self::#variants(s)=> #variants::on_enter(s, args, context)),*,
+                    #(#variant_cfgs Self::#variants(s)=> {
+                        #enter_actions
+                        #arg_converts
+                        #variants::on_enter(s, args, context)
+                    }),*,
                     _=>(),
                 }
             }
 
+            fn enter_state(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) -> Option<Self::States>
+            where
+                Self: adar::prelude::Machine #combined_gen,
+            {
+                match <Self as adar::prelude::State #combined_gen>::try_on_enter(self, args, context) {
+                    Ok(()) => None,
+                    Err(error) => <Self as adar::prelude::Machine #combined_gen>::on_error(self, error, context),
+                }
+            }
+
+            fn try_on_enter(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) -> Result<(), <Self as adar::prelude::HasError #combined_gen>::Error> {
+                match self {
+                    #(#variant_cfgs Self::#variants(s)=> {
+                        #enter_actions
+                        #try_arg_converts
+                        #variants::try_on_enter(s, args, context)
+                    }),*,
+                    _=>Ok(()),
+                }
+            }
+
             fn on_update(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) -> Option<Self::States> {
                 match self {
-                    #(Self::#variants(s)=> #variants::on_update(s, args, context)),*,
+                    #(#variant_cfgs Self::#variants(s)=> {
+                        #arg_converts
+                        #variants::on_update(s, args, context)
+                    }),*,
                     _=>None,
                 }
             }
 
             fn on_leave(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) {
                 match self {
-                    #(Self::#variants(s)=> #variants::on_leave(s, args, context)),*,
+                    #(#variant_cfgs Self::#variants(s)=> {
+                        #arg_converts
+                        #variants::on_leave(s, args, context);
+                        #leave_actions
+                    }),*,
                     _=>(),
                 }
             }
+
+            fn on_update_transition(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) -> Option<adar::prelude::Transition<Self::States>> {
+                match self {
+                    #(#variant_cfgs Self::#variants(s)=> {
+                        #arg_converts
+                        #variants::on_update_transition(s, args, context)
+                    }),*,
+                    _=>None,
+                }
+            }
+
+            fn update_state(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) -> Option<adar::prelude::Transition<Self::States>>
+            where
+                Self: adar::prelude::Machine #combined_gen,
+            {
+                match <Self as adar::prelude::State #combined_gen>::try_on_update(self, args, context) {
+                    Ok(transition) => transition,
+                    Err(error) => <Self as adar::prelude::Machine #combined_gen>::on_error(self, error, context)
+                        .map(adar::prelude::Transition::To),
+                }
+            }
+
+            fn try_on_update(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) -> Result<Option<adar::prelude::Transition<Self::States>>, <Self as adar::prelude::HasError #combined_gen>::Error> {
+                match self {
+                    #(#update_arms),*,
+                    _=>Ok(None),
+                }
+            }
+
+            fn on_pause(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) {
+                match self {
+                    #(#variant_cfgs Self::#variants(s)=> {
+                        #arg_converts
+                        #variants::on_pause(s, args, context)
+                    }),*,
+                    _=>(),
+                }
+            }
+
+            fn on_resume(&mut self, args: Option<&mut Self::Args>, context: &mut Self::Context) {
+                match self {
+                    #(#variant_cfgs Self::#variants(s)=> {
+                        #arg_converts
+                        #variants::on_resume(s, args, context)
+                    }),*,
+                    _=>(),
+                }
+            }
+
+            fn has_history(&self) -> bool {
+                #has_history_impl
+            }
+
+            fn state_name(&self) -> &'static str {
+                #state_name_impl
+            }
+
+            fn timeout(&self) -> Option<std::time::Duration> {
+                match self {
+                    #(#timeout_arms),*,
+                    _=>None,
+                }
+            }
+
+            fn on_timeout(&mut self, context: &mut Self::Context) -> Option<Self::States> {
+                match self {
+                    #(#on_timeout_arms),*,
+                    _=>None,
+                }
+            }
+
+            fn on_event(&mut self, event: <Self as adar::prelude::HasEvent #combined_gen>::Event, context: &mut Self::Context) -> Option<Self::States> {
+                match self {
+                    #(#variant_cfgs Self::#variants(s)=> #variants::on_event(s, event, context)),*,
+                    _=>None,
+                }
+            }
         }
 
         #end_state
+
+        impl adar::prelude::StateFields for #ident {
+            fn fields(&self) -> Vec<adar::prelude::StateField<'_>> {
+                match self {
+                    #(#field_arms),*,
+                    #[allow(unreachable_patterns)]
+                    _ => vec![],
+                }
+            }
+        }
+
+        #finished_state
+
+        #initial_state
+
+        #unreachable_states
+
+        #transition_edges
+
+        #topology
+    })
+}
+
+/// Unwraps the attributes listed in `#[name(...)]` attributes, e.g. `#[state_attr(cfg(test))]` into `#[cfg(test)]`.
+fn forwarded_attrs(attrs: &[Attribute], name: &str) -> syn::Result<TokenStream> {
+    let mut forwarded = quote! {};
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident(name)) {
+        let metas = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        forwarded.extend(metas.iter().map(|meta| quote! {#[#meta]}));
     }
-    .into())
+    Ok(forwarded)
+}
+
+/// Collects the `#[cfg(...)]` attributes of a variant.
+fn cfg_attrs(variant: &Variant) -> TokenStream {
+    let cfgs = variant
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"));
+    quote! {#(#cfgs)*}
+}
+
+/// How unreachable states of a declared transition table are reported.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unreachable {
+    Allow,
+    #[default]
+    Warn,
+    Deny,
+}
+
+/// Transition table declared by the `#[transitions(...)]` and `#[timeout(...)]` variant attributes.
+#[derive(Default)]
+struct TransitionTable {
+    /// Whether any variant declares its transitions with `#[transitions(...)]`.
+    declared: bool,
+    /// States in declaration order with their transitions.
+    states: Vec<(Ident, Vec<Edge>)>,
+}
+
+/// Transition declared by a `#[transitions(...)]` or `#[timeout(...)]` variant attribute.
+struct Edge {
+    target: Ident,
+    timeout: bool,
+}
+
+impl TransitionTable {
+    fn add_state(&mut self, variant: &Variant) -> syn::Result<()> {
+        let mut targets = vec![];
+        for attr in &variant.attrs {
+            if attr.path().is_ident("transitions") {
+                self.declared = true;
+                targets.extend(
+                    attr.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated)?
+                        .into_iter()
+                        .map(|target| Edge {
+                            target,
+                            timeout: false,
+                        }),
+                );
+            } else if attr.path().is_ident("timeout") {
+                let Timeout { to, .. } = attr.parse_args()?;
+                targets.extend(state_name(&to).map(|target| Edge {
+                    target,
+                    timeout: true,
+                }));
+            } else if attr.path().is_ident("regions") && !matches!(attr.meta, Meta::Path(_)) {
+                let RegionsArgs { then } = attr.parse_args()?;
+                targets.extend(then.as_ref().and_then(state_name).map(|target| Edge {
+                    target,
+                    timeout: false,
+                }));
+            }
+        }
+        self.states.push((variant.ident.clone(), targets));
+        Ok(())
+    }
+
+    /// Validates the targets of the transitions and reports the states which can't be reached from the initial state.
+    /// Only performed if the transitions are declared.
+    fn check(&self, initial: Option<&Expr>, unreachable: Unreachable) -> syn::Result<TokenStream> {
+        if !self.declared {
+            return Ok(quote! {});
+        }
+
+        let indices: HashMap<_, _> = self
+            .states
+            .iter()
+            .enumerate()
+            .map(|(index, (state, _))| (state, index))
+            .collect();
+        for Edge { target, .. } in self.states.iter().flat_map(|(_, targets)| targets) {
+            if !indices.contains_key(target) {
+                return Err(syn::Error::new(
+                    target.span(),
+                    format!("Transition to unknown state: {}", target),
+                ));
+            }
+        }
+
+        let Some(initial) = initial.and_then(state_name) else {
+            return Ok(quote! {});
+        };
+        let Some(&initial) = indices.get(&initial) else {
+            return Ok(quote! {});
+        };
+        if unreachable == Unreachable::Allow {
+            return Ok(quote! {});
+        }
+
+        let mut reachable = vec![false; self.states.len()];
+        let mut queue = VecDeque::from([initial]);
+        reachable[initial] = true;
+        while let Some(index) = queue.pop_front() {
+            for Edge { target, .. } in &self.states[index].1 {
+                let target = indices[target];
+                if !reachable[target] {
+                    reachable[target] = true;
+                    queue.push_back(target);
+                }
+            }
+        }
+
+        let mut result = quote! {};
+        for ((state, _), reachable) in self.states.iter().zip(reachable) {
+            if reachable {
+                continue;
+            }
+            let message = format!(
+                "State {} is unreachable: it's neither the initial state nor the target of a transition from a reachable state",
+                state
+            );
+            result.extend(match unreachable {
+                Unreachable::Deny => syn::Error::new(state.span(), message).to_compile_error(),
+                _ => {
+                    // Stable proc macros can't emit warnings, so the warning is raised through a deprecated item.
+                    let warning = format_ident!("unreachable_state_{}", state);
+                    quote_spanned! {state.span()=>
+                        const _: () = {
+                            #[deprecated(note = #message)]
+                            #[allow(non_camel_case_types)]
+                            struct #warning;
+                            let _ = #warning;
+                        };
+                    }
+                }
+            });
+        }
+        Ok(result)
+    }
+}
+
+/// Extracts the name of the state from expressions like `State`, `State { .. }`, `State(..)` or `Enum::State(State)`.
+fn state_name(expr: &Expr) -> Option<Ident> {
+    let path = match expr {
+        Expr::Path(expr) => &expr.path,
+        Expr::Struct(expr) => &expr.path,
+        Expr::Call(expr) => match &*expr.func {
+            Expr::Path(func) => &func.path,
+            _ => return None,
+        },
+        Expr::Paren(expr) => return state_name(&expr.expr),
+        _ => return None,
+    };
+    path.segments.last().map(|segment| segment.ident.clone())
+}
+
+/// Arguments of the `#[timeout(duration = "2s", to = State)]` variant attribute.
+struct Timeout {
+    nanos: u64,
+    to: Expr,
+}
+
+impl Parse for Timeout {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut nanos = None;
+        let mut to = None;
+        while !input.is_empty() {
+            let ident: syn::Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            if ident == "duration" {
+                let literal: LitStr = input.parse()?;
+                nanos = Some(parse_duration(&literal.value()).ok_or_else(|| {
+                    syn::Error::new(
+                        literal.span(),
+                        "Invalid duration (expected e.g. \"2s\", \"1.5s\" or \"500ms\")",
+                    )
+                })?);
+            } else if ident == "to" {
+                to = Some(input.parse()?);
+            } else {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!("Invalid identifier: {}", ident),
+                ));
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        match (nanos, to) {
+            (Some(nanos), Some(to)) => Ok(Self { nanos, to }),
+            _ => Err(syn::Error::new(
+                input.span(),
+                "#[timeout] requires both duration and to",
+            )),
+        }
+    }
+}
+
+/// Arguments of the `#[regions(then = State)]` variant attribute.
+struct RegionsArgs {
+    then: Option<Expr>,
+}
+
+impl Parse for RegionsArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut then = None;
+        while !input.is_empty() {
+            let ident: syn::Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            if ident == "then" {
+                then = Some(input.parse()?);
+            } else {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!("Invalid identifier: {}", ident),
+                ));
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(Self { then })
+    }
+}
+
+/// Parses durations like "2s", "1.5s", "500ms", "100us", "10ns", "1m" and "1h" into nanoseconds.
+fn parse_duration(duration: &str) -> Option<u64> {
+    let split = duration.find(|c: char| c.is_ascii_alphabetic())?;
+    let (value, unit) = duration.split_at(split);
+    let value: f64 = value.trim().parse().ok()?;
+    let unit_nanos = match unit {
+        "ns" => 1.0,
+        "us" => 1e3,
+        "ms" => 1e6,
+        "s" => 1e9,
+        "m" => 60e9,
+        "h" => 3600e9,
+        _ => return None,
+    };
+    (value >= 0.0).then(|| (value * unit_nanos).round() as u64)
 }
 
 #[derive(Default, Debug)]
@@ -200,6 +1128,11 @@ pub struct ComplexType {
 pub struct StateMachineArgs {
     pub args: ComplexType,
     pub context: ComplexType,
+    pub event: ComplexType,
+    pub error: ComplexType,
+    pub serde: bool,
+    pub initial: Option<Expr>,
+    pub unreachable: Unreachable,
 }
 
 impl Parse for StateMachineArgs {
@@ -211,20 +1144,43 @@ impl Parse for StateMachineArgs {
                 input.parse::<Token![,]>()?;
             }
             let ident: syn::Ident = input.parse()?;
+            first = false;
+            if ident == "serde" {
+                result.serde = true;
+                continue;
+            }
             input.parse::<Token![=]>()?;
 
             if ident == "args" {
                 result.args = Self::parse_type(&input)?;
             } else if ident == "context" {
                 result.context = Self::parse_type(&input)?;
+            } else if ident == "event" {
+                result.event = Self::parse_type(&input)?;
+            } else if ident == "error" {
+                result.error = Self::parse_type(&input)?;
+            } else if ident == "initial" {
+                result.initial = Some(input.parse()?);
+            } else if ident == "unreachable" {
+                let level: syn::Ident = input.parse()?;
+                result.unreachable = if level == "allow" {
+                    Unreachable::Allow
+                } else if level == "warn" {
+                    Unreachable::Warn
+                } else if level == "deny" {
+                    Unreachable::Deny
+                } else {
+                    return Err(syn::Error::new(
+                        level.span(),
+                        "Invalid level (expected allow, warn or deny)",
+                    ));
+                };
             } else {
                 return Err(syn::Error::new(
                     Span::call_site(),
                     format!("Invalid identifier: {}", ident),
                 ));
             }
-
-            first = false;
         }
 
         Ok(result)
@@ -6,19 +6,20 @@ fn main() {
     // Original website setup
     let menu = Registry::<MenuItem>::new();
     let styles = Registry::<StyleSheet>::new();
-    let mut website_store = vec![];
-
-    website_store.push(menu.register(MenuItem("Home")).as_generic());
-    website_store.push(menu.register(MenuItem("About")).as_generic());
-    website_store.push(styles.register(StyleSheet("website.css")).as_generic());
+    let _website_store = [
+        menu.register(MenuItem("Home")).as_generic(),
+        menu.register(MenuItem("About")).as_generic(),
+        styles.register(StyleSheet("website.css")).as_generic(),
+    ];
 
     print_state("Original website", &menu, &styles);
 
     // Loading an extension which registers new resources
-    let mut extension_store = vec![];
-    extension_store.push(menu.register(MenuItem("Weather")).as_generic());
-    extension_store.push(menu.register(MenuItem("News")).as_generic());
-    extension_store.push(styles.register(StyleSheet("extension.css")).as_generic());
+    let extension_store = [
+        menu.register(MenuItem("Weather")).as_generic(),
+        menu.register(MenuItem("News")).as_generic(),
+        styles.register(StyleSheet("extension.css")).as_generic(),
+    ];
 
     print_state("After extension is loaded", &menu, &styles);
 
@@ -6,10 +6,10 @@ fn main() {
         println!("{:?}, {:?}, {}", event, entry, value)
     });
 
-    let foo = registry.register("foo");
-    let bar = registry.register("bar");
-    drop(foo);
-    let baz = registry.register("baz");
-    drop(bar);
-    drop(baz);
+    let foo_entry = registry.register("foo");
+    let bar_entry = registry.register("bar");
+    drop(foo_entry);
+    let baz_entry = registry.register("baz");
+    drop(bar_entry);
+    drop(baz_entry);
 }
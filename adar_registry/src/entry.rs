@@ -1,9 +1,10 @@
-use crate::registry::RegistryInterface;
+use crate::registry::{Registry, RegistryInterface};
 use std::{
     marker::PhantomData,
     mem::MaybeUninit,
     ops::Deref,
     sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak},
+    time::{Duration, Instant},
 };
 
 /// Entry index type
@@ -54,7 +55,7 @@ where
     /// lock can be acquired!
     /// # Return
     /// [`None`] if the [`crate::registry::Registry`] no longer exists.
-    pub fn write(&self) -> Option<EntryWriteGuard<T>> {
+    pub fn write(&self) -> Option<EntryWriteGuard<'_, T>> {
         let registry = self.iface.upgrade()?;
         let ptr = self.iface.as_ptr();
         // Note: The acquired pointer will be valid as long as a strong reference is alive.
@@ -73,7 +74,7 @@ where
     /// lock can be acquired!
     /// # Return
     /// [`None`] if the [`crate::registry::Registry`] no longer exists.
-    pub fn read(&self) -> Option<EntryReadGuard<T>> {
+    pub fn read(&self) -> Option<EntryReadGuard<'_, T>> {
         let registry = self.iface.upgrade()?;
         let ptr = self.iface.as_ptr();
         // Note: The acquired pointer will be valid as long as a strong reference is alive.
@@ -93,8 +94,79 @@ where
         self.id
     }
 
+    /// Returns the remaining lease of a TTL registration (see [`crate::registry::Registry::register_with_ttl()`]).
+    /// An expired element which has not been purged yet returns [`Duration::ZERO`].
+    ///
+    /// # Return
+    /// [`None`] if the element has no TTL, it has been removed, or the [`crate::registry::Registry`] no longer exists.
+    pub fn remaining(&self) -> Option<Duration> {
+        let registry = self.iface.upgrade()?;
+        let deadline = registry.read().unwrap().deadline(self.id)?;
+        Some(deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Extends the lease of a TTL registration, the element expires `ttl` from now.
+    /// See [`crate::registry::Registry::register_with_ttl()`].
+    ///
+    /// # Return
+    /// false if the element has no TTL, it has been removed, or the [`crate::registry::Registry`] no longer exists.
+    pub fn renew(&self, ttl: Duration) -> bool {
+        let Some(registry) = self.iface.upgrade() else {
+            return false;
+        };
+        let result = registry
+            .write()
+            .unwrap()
+            .set_deadline(self.id, Instant::now() + ttl);
+        result
+    }
+
+    /// Moves the element into another [`crate::registry::Registry`] atomically. Both registries are locked
+    /// during the move, in a consistent order so that concurrent moves can't deadlock. \
+    /// The move callback of the source (see [`crate::registry::Registry::set_move_callback()`]) and the register callback
+    /// of the target (see [`crate::registry::Registry::set_register_callback()`]) are called. Moving into the registry
+    /// that holds the element returns the entry unchanged.
+    ///
+    /// # Return
+    /// [`None`] if the source registry no longer exists, or the element was already removed from it
+    /// (e.g. purged by [`crate::registry::Registry::purge_expired()`] or removed by [`crate::registry::RegistryWriteGuard::retain()`]).
+    ///
+    /// # Example
+    /// ```
+    /// use adar_registry::prelude::*;
+    ///
+    /// let staging = Registry::<&str>::new();
+    /// let live = Registry::<&str>::new();
+    /// let entry = staging.register("item");
+    /// let entry = entry.move_to(&live).unwrap();
+    /// assert!(staging.is_empty());
+    /// assert_eq!(*entry.read().unwrap(), "item");
+    /// ```
+    pub fn move_to(self, target: &Registry<T>) -> Option<Entry<T>>
+    where
+        T: 'static,
+    {
+        let source = self.iface.upgrade()?;
+        if target.is_same(&source) {
+            return Some(self);
+        }
+        // Note: The id is gone from the source after the move and ids are never reused,
+        // so dropping the old entry afterwards removes nothing.
+        target.move_from(&source, self.id)
+    }
+
+    /// Converts the entry into a clonable [`SharedEntry`]. The element is removed from the registry when the last clone is dropped.
+    pub fn into_shared(self) -> SharedEntry<T> {
+        SharedEntry {
+            entry: Arc::new(self),
+        }
+    }
+
     /// Leaks the entry. \
     /// ⚠️ In production environments you should never use this method. It's only meant for quick prototyping or debugging.
+    ///
+    /// # Safety
+    /// The registered element will never be removed from the registry.
     pub unsafe fn leak(self) {
         std::mem::forget(self);
     }
@@ -111,6 +183,82 @@ impl<T> Drop for Entry<T> {
     }
 }
 
+/// Shared ownership of a registration. Unlike [`Entry`], it can be cloned,
+/// and the element is removed from the registry when the last clone is dropped. See [`Entry::into_shared()`].
+///
+/// # Example
+/// ```
+/// use adar_registry::prelude::*;
+///
+/// let registry = Registry::<u32>::new();
+/// let entry = registry.register(1).into_shared();
+/// let clone = entry.clone();
+/// *clone.write().unwrap().get_mut() = 2;
+/// assert_eq!(*entry.read().unwrap(), 2);
+///
+/// drop(entry);
+/// assert_eq!(registry.len(), 1);
+/// drop(clone);
+/// assert!(registry.is_empty());
+/// ```
+pub struct SharedEntry<T = ()> {
+    entry: Arc<Entry<T>>,
+}
+
+// Note: Derive macro is not used here in order to make the implementation independent from T
+impl<T> Clone for SharedEntry<T> {
+    fn clone(&self) -> Self {
+        Self {
+            entry: self.entry.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for SharedEntry<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "E{:?}", self.entry.id)
+    }
+}
+
+impl<T> From<Entry<T>> for SharedEntry<T>
+where
+    T: Send + Sync,
+{
+    fn from(entry: Entry<T>) -> Self {
+        entry.into_shared()
+    }
+}
+
+impl<T> SharedEntry<T>
+where
+    T: Send + Sync,
+{
+    /// Grants mutable access to the entry. See [`Entry::write()`].
+    pub fn write(&self) -> Option<EntryWriteGuard<'_, T>> {
+        self.entry.write()
+    }
+
+    /// Grants shared read access to the entry. See [`Entry::read()`].
+    pub fn read(&self) -> Option<EntryReadGuard<'_, T>> {
+        self.entry.read()
+    }
+
+    /// Gets the underlying id of the entry.
+    pub fn get_id(&self) -> EntryId {
+        self.entry.id
+    }
+
+    /// Returns the number of clones sharing the entry.
+    pub fn share_count(&self) -> usize {
+        Arc::strong_count(&self.entry)
+    }
+
+    /// Converts back into an [`Entry`] if this is the only clone, otherwise returns itself.
+    pub fn try_into_entry(self) -> Result<Entry<T>, Self> {
+        Arc::try_unwrap(self.entry).map_err(|entry| Self { entry })
+    }
+}
+
 /// Holds a write guard to the entry. See [`Entry::write()`].
 pub struct EntryWriteGuard<'a, T> {
     _registry: Arc<RwLock<dyn RegistryInterface>>,
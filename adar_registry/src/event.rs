@@ -1,4 +1,5 @@
 use super::{entry::Entry, registry::Registry};
+use std::marker::PhantomData;
 
 pub trait EventObserver<Args>: Send + Sync {
     fn notify(&self, args: &Args);
@@ -21,6 +22,93 @@ where
     }
 }
 
+/// Adapters for [`EventObserver`]s. Implemented for every observer, new adapters are added here instead of
+/// [`EventObserver`] so implementors are not affected.
+///
+/// # Example
+/// ```
+/// use adar_registry::prelude::*;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+///
+/// let event = Event::<i32>::new();
+/// let count = Arc::new(AtomicUsize::new(0));
+/// let count2 = count.clone();
+/// let observer = move |len: &usize| {
+///     count2.fetch_add(*len, Ordering::Relaxed);
+/// };
+/// let _entry = event.register_observer(
+///     observer
+///         .map(|value: &i32| value.unsigned_abs() as usize)
+///         .filter(|value: &i32| *value < 0),
+/// );
+/// event.dispatch(3);
+/// event.dispatch(-5);
+/// assert_eq!(count.load(Ordering::Relaxed), 5);
+/// ```
+pub trait EventObserverExt<Args>: EventObserver<Args> + Sized {
+    /// Only notifies the observer about the events for which `predicate` returns true.
+    fn filter<F>(self, predicate: F) -> Filter<Self, F>
+    where
+        F: Fn(&Args) -> bool + Send + Sync,
+    {
+        Filter {
+            observer: self,
+            predicate,
+        }
+    }
+
+    /// Creates an observer of `From` events, which are converted by `f` before notifying this observer.
+    fn map<From, F>(self, f: F) -> Map<Self, F, Args>
+    where
+        F: Fn(&From) -> Args + Send + Sync,
+    {
+        Map {
+            observer: self,
+            f,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<O, Args> EventObserverExt<Args> for O where O: EventObserver<Args> {}
+
+/// Observer returned by [`EventObserverExt::filter()`].
+#[derive(Clone, Debug)]
+pub struct Filter<O, F> {
+    observer: O,
+    predicate: F,
+}
+
+impl<O, F, Args> EventObserver<Args> for Filter<O, F>
+where
+    O: EventObserver<Args>,
+    F: Fn(&Args) -> bool + Send + Sync,
+{
+    fn notify(&self, args: &Args) {
+        if (self.predicate)(args) {
+            self.observer.notify(args);
+        }
+    }
+}
+
+/// Observer returned by [`EventObserverExt::map()`].
+pub struct Map<O, F, Args> {
+    observer: O,
+    f: F,
+    phantom: PhantomData<fn(Args)>,
+}
+
+impl<O, F, From, Args> EventObserver<From> for Map<O, F, Args>
+where
+    O: EventObserver<Args>,
+    F: Fn(&From) -> Args + Send + Sync,
+{
+    fn notify(&self, args: &From) {
+        self.observer.notify(&(self.f)(args));
+    }
+}
+
 impl<Args> Default for Event<Args>
 where
     Args: Send + Sync + 'static,
@@ -47,9 +135,9 @@ where
         self.observers.register(Box::new(observer)).as_generic()
     }
 
-    pub fn dispatch(&self, mut args: Args) {
+    pub fn dispatch(&self, args: Args) {
         for (_, observer) in self.observers.read().iter() {
-            (**observer).notify(&mut args);
+            (**observer).notify(&args);
         }
     }
 }
@@ -65,4 +153,24 @@ mod tests {
         is_send_sync::<Event<i32>>();
         is_clone::<Event<i32>>();
     }
+
+    #[test]
+    fn test_observer_adapters() {
+        use std::sync::{Arc, Mutex};
+
+        let event = Event::<(u32, &'static str)>::new();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received2 = received.clone();
+        let observer = move |name: &&'static str| received2.lock().unwrap().push(*name);
+        let _entry = event.register_observer(
+            observer
+                .map(|(_, name): &(u32, &'static str)| *name)
+                .filter(|(code, _): &(u32, &'static str)| *code >= 400),
+        );
+
+        event.dispatch((200, "ok"));
+        event.dispatch((404, "not found"));
+        event.dispatch((500, "error"));
+        assert_eq!(*received.lock().unwrap(), vec!["not found", "error"]);
+    }
 }
@@ -2,14 +2,26 @@
 
 pub mod entry;
 pub mod event;
+#[cfg(feature = "log")]
+pub mod log_observer;
 pub mod registry;
 pub mod registry_map;
+#[cfg(feature = "shuffle")]
+pub mod shuffle;
 pub mod traced_registry;
+#[cfg(feature = "tracing")]
+pub mod tracing_observer;
 
 pub mod prelude {
     pub use crate::entry::*;
     pub use crate::event::*;
+    #[cfg(feature = "log")]
+    pub use crate::log_observer::*;
     pub use crate::registry::*;
     pub use crate::registry_map::*;
+    #[cfg(feature = "shuffle")]
+    pub use crate::shuffle::{set_shuffle_seed, shuffle_seed};
     pub use crate::traced_registry::*;
+    #[cfg(feature = "tracing")]
+    pub use crate::tracing_observer::*;
 }
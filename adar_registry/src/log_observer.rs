@@ -0,0 +1,117 @@
+use crate::event::EventObserver;
+use std::fmt::Debug;
+
+/// Observer which logs every dispatched event with [`Debug`] formatting through the [`log`] crate.
+///
+/// # Example
+/// ```
+/// use adar_registry::prelude::*;
+///
+/// let event = Event::<(u32, &'static str)>::new();
+/// let _observer = event.register_observer(LogObserver::new(log::Level::Info).named("request"));
+/// event.dispatch((200, "/index.html")); // request: (200, "/index.html")
+/// ```
+#[derive(Clone, Debug)]
+pub struct LogObserver {
+    level: log::Level,
+    target: &'static str,
+    name: Option<&'static str>,
+}
+
+impl LogObserver {
+    /// Creates an observer which logs at `level` with the target of this crate.
+    pub fn new(level: log::Level) -> Self {
+        Self {
+            level,
+            target: module_path!(),
+            name: None,
+        }
+    }
+
+    /// Sets the target of the log records.
+    pub fn with_target(mut self, target: &'static str) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Sets the name which prefixes the logged events.
+    pub fn named(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+}
+
+impl<Args> EventObserver<Args> for LogObserver
+where
+    Args: Debug,
+{
+    fn notify(&self, args: &Args) {
+        match self.name {
+            Some(name) => log::log!(target: self.target, self.level, "{}: {:?}", name, args),
+            None => log::log!(target: self.target, self.level, "{:?}", args),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{event::Event, traced_registry::TracedRegistry};
+    use std::sync::Mutex;
+
+    static RECORDS: Mutex<Vec<(log::Level, String, String)>> = Mutex::new(Vec::new());
+
+    struct TestLogger;
+
+    impl log::Log for TestLogger {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            RECORDS.lock().unwrap().push((
+                record.level(),
+                record.target().to_string(),
+                record.args().to_string(),
+            ));
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn test_log_observer() {
+        log::set_logger(&TestLogger).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+
+        let event = Event::<(u32, bool)>::new();
+        let _observer = event.register_observer(LogObserver::new(log::Level::Warn).named("event"));
+        event.dispatch((1, true));
+
+        let registry = TracedRegistry::<&'static str>::new();
+        let _observer =
+            registry.register_observer(LogObserver::new(log::Level::Debug).with_target("registry"));
+        drop(registry.register("foo"));
+
+        assert_eq!(
+            *RECORDS.lock().unwrap(),
+            [
+                (
+                    log::Level::Warn,
+                    module_path!().trim_end_matches("::tests").to_string(),
+                    "event: (1, true)".to_string()
+                ),
+                (
+                    log::Level::Debug,
+                    "registry".to_string(),
+                    "(Register, 0, \"foo\")".to_string()
+                ),
+                (
+                    log::Level::Debug,
+                    "registry".to_string(),
+                    "(UnRegister, 0, \"foo\")".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -1,9 +1,13 @@
 use super::entry::{Entry, EntryId};
+#[cfg(feature = "shuffle")]
+use super::shuffle;
 use std::{
     any::Any,
-    collections::BTreeMap,
+    collections::{btree_map, BTreeMap},
     fmt::Debug,
+    iter::FusedIterator,
     sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak},
+    time::{Duration, Instant},
 };
 
 /// [`Registry`] is a container whose registered elements' lifetimes are controlled by the non-copyable [`Entry`] object.
@@ -54,8 +58,12 @@ where
         Registry {
             inner: Arc::new(RwLock::new(Inner {
                 map: BTreeMap::new(),
+                deadlines: BTreeMap::new(),
                 next_id: 0,
                 remove_callback: None,
+                register_callback: None,
+                move_callback: None,
+                len_observer: None,
             })),
         }
     }
@@ -66,12 +74,91 @@ where
     /// [`Entry`] which controls the lifetime of the registered element.
     #[must_use = "Entry will be immediately revoked if not used"]
     pub fn register(&self, value: T) -> Entry<T> {
+        let entry_id = self.inner.write().unwrap().insert(value);
+        self.entry(entry_id)
+    }
+
+    /// Registers an element which expires after `ttl`. Expired elements are removed by
+    /// [`Registry::purge_expired()`], the holder of the [`Entry`] can query and extend its lease
+    /// with [`Entry::remaining()`] and [`Entry::renew()`].
+    ///
+    /// # Returns
+    /// [`Entry`] which controls the lifetime of the registered element.
+    ///
+    /// # Example
+    /// ```
+    /// use adar_registry::prelude::*;
+    /// use std::time::Duration;
+    ///
+    /// let sessions = Registry::<&str>::new();
+    /// let session = sessions.register_with_ttl("alice", Duration::ZERO);
+    /// assert_eq!(session.remaining(), Some(Duration::ZERO));
+    ///
+    /// // Heartbeat
+    /// assert!(session.renew(Duration::from_secs(60)));
+    /// assert_eq!(sessions.purge_expired(), 0);
+    /// assert_eq!(*session.read().unwrap(), "alice");
+    /// ```
+    #[must_use = "Entry will be immediately revoked if not used"]
+    pub fn register_with_ttl(&self, value: T, ttl: Duration) -> Entry<T> {
+        let mut lock = self.inner.write().unwrap();
+        let entry_id = lock.insert(value);
+        lock.deadlines.insert(entry_id, Instant::now() + ttl);
+        drop(lock);
+        self.entry(entry_id)
+    }
+
+    /// Removes the elements whose TTL has expired (see [`Registry::register_with_ttl()`]).
+    /// The remove callback is called for each removed element (see [`Registry::set_remove_callback()`]).
+    /// The entries of removed elements stay valid, but they no longer grant access to the element.
+    ///
+    /// # Returns
+    /// The number of removed elements.
+    pub fn purge_expired(&self) -> usize {
         let mut lock = self.inner.write().unwrap();
+        let now = Instant::now();
+        let expired = lock
+            .deadlines
+            .iter()
+            .filter_map(|(entry_id, deadline)| (*deadline <= now).then_some(*entry_id))
+            .collect::<Vec<_>>();
+        for entry_id in &expired {
+            lock.remove(*entry_id);
+        }
+        expired.len()
+    }
 
-        let entry_id = lock.next_id;
-        lock.map.insert(entry_id, value);
-        lock.next_id += 1;
+    /// Moves the element of an entry from `source` into this registry. Both locks are acquired in address order,
+    /// so concurrent moves between the same registries can't deadlock. See [`Entry::move_to()`].
+    pub(crate) fn move_from(
+        &self,
+        source: &Arc<RwLock<dyn RegistryInterface>>,
+        entry_id: EntryId,
+    ) -> Option<Entry<T>>
+    where
+        T: 'static,
+    {
+        let target = Arc::as_ptr(&self.inner) as *const ();
+        let (mut source_lock, mut target_lock) = if (Arc::as_ptr(source) as *const ()) < target {
+            let source_lock = source.write().unwrap();
+            (source_lock, self.inner.write().unwrap())
+        } else {
+            let target_lock = self.inner.write().unwrap();
+            (source.write().unwrap(), target_lock)
+        };
+        let value = source_lock
+            .take(entry_id)?
+            .downcast::<T>()
+            .expect("Failed to downcast Entry");
+        let entry_id = target_lock.insert(*value);
+        Some(self.entry(entry_id))
+    }
+
+    pub(crate) fn is_same(&self, iface: &Arc<RwLock<dyn RegistryInterface>>) -> bool {
+        std::ptr::addr_eq(Arc::as_ptr(&self.inner), Arc::as_ptr(iface))
+    }
 
+    fn entry(&self, entry_id: EntryId) -> Entry<T> {
         Entry::<T>::new(
             Arc::downgrade(&self.inner) as Weak<RwLock<dyn RegistryInterface>>,
             entry_id,
@@ -79,14 +166,14 @@ where
     }
 
     /// Creates a [`RegistryReadGuard`] which can be used to read the contents of the registry.
-    pub fn read(&self) -> RegistryReadGuard<T> {
+    pub fn read(&self) -> RegistryReadGuard<'_, T> {
         RegistryReadGuard::<T> {
             guard: self.inner.read().unwrap(),
         }
     }
 
     /// Creates a [`RegistryWriteGuard`] which can be used to write the contents of the registry.
-    pub fn write(&self) -> RegistryWriteGuard<T> {
+    pub fn write(&self) -> RegistryWriteGuard<'_, T> {
         RegistryWriteGuard::<T> {
             guard: self.inner.write().unwrap(),
         }
@@ -110,16 +197,150 @@ where
     {
         self.inner.write().unwrap().remove_callback = Some(Box::new(callback))
     }
+
+    /// Sets a register callback for the registry. It's also called when an element is moved
+    /// into the registry (see [`Entry::move_to()`]). \
+    /// Note: If you call this multiple times. It will override the previous callback.
+    pub fn set_register_callback<C>(&self, callback: C)
+    where
+        C: Fn(EntryId, &T) + Send + Sync + 'static,
+    {
+        self.inner.write().unwrap().register_callback = Some(Box::new(callback))
+    }
+
+    /// Sets a callback which is called instead of the remove callback when an element is moved
+    /// to another registry, since the value is not dropped (see [`Entry::move_to()`]). \
+    /// Note: If you call this multiple times. It will override the previous callback.
+    pub fn set_move_callback<C>(&self, callback: C)
+    where
+        C: Fn(EntryId, &T) + Send + Sync + 'static,
+    {
+        self.inner.write().unwrap().move_callback = Some(Box::new(callback))
+    }
+
+    /// Sets a callback which is called when the length of the registry crosses the [`Watermarks`].
+    /// Useful to detect leaked entries before the memory usage becomes a problem. \
+    /// The callback is called with the registry locked, so it must not access the registry.
+    /// If the length is already above the high watermark, the callback is called immediately. \
+    /// Note: If you call this multiple times. It will override the previous callback.
+    ///
+    /// # Example
+    /// ```
+    /// use adar_registry::prelude::*;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let registry = Registry::<u32>::new();
+    /// let events = Arc::new(Mutex::new(vec![]));
+    /// registry.observe_len(Watermarks::of_capacity(10, 80, 50), {
+    ///     let events = events.clone();
+    ///     move |event| events.lock().unwrap().push(event)
+    /// });
+    ///
+    /// let mut entries: Vec<_> = (0..8).map(|i| registry.register(i)).collect();
+    /// entries.truncate(5);
+    /// assert_eq!(
+    ///     *events.lock().unwrap(),
+    ///     [WatermarkEvent::Exceeded { len: 8 }, WatermarkEvent::Recovered { len: 5 }]
+    /// );
+    /// ```
+    pub fn observe_len<C>(&self, watermarks: Watermarks, callback: C)
+    where
+        C: Fn(WatermarkEvent) + Send + Sync + 'static,
+    {
+        let mut lock = self.inner.write().unwrap();
+        lock.len_observer = Some(LenObserver {
+            watermarks,
+            exceeded: false,
+            callback: Box::new(callback),
+        });
+        lock.observe_len();
+    }
 }
 
+/// Length thresholds of a [`Registry`] with hysteresis. See [`Registry::observe_len()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Watermarks {
+    /// [`WatermarkEvent::Exceeded`] is emitted when the length reaches this value.
+    pub high: usize,
+    /// [`WatermarkEvent::Recovered`] is emitted when the length drops to this value after it was exceeded.
+    pub low: usize,
+}
+
+impl Watermarks {
+    /// Creates watermarks from absolute lengths. `low` is clamped to `high`.
+    pub fn new(high: usize, low: usize) -> Self {
+        Self {
+            high,
+            low: low.min(high),
+        }
+    }
+
+    /// Creates watermarks from percentages of a soft capacity. E.g. `of_capacity(1000, 80, 60)`.
+    pub fn of_capacity(capacity: usize, high_percent: usize, low_percent: usize) -> Self {
+        Self::new(capacity * high_percent / 100, capacity * low_percent / 100)
+    }
+}
+
+/// Emitted when the length of a [`Registry`] crosses its [`Watermarks`]. See [`Registry::observe_len()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WatermarkEvent {
+    /// The length reached the high watermark.
+    Exceeded { len: usize },
+    /// The length dropped to the low watermark after it was exceeded.
+    Recovered { len: usize },
+}
+
+struct LenObserver {
+    watermarks: Watermarks,
+    exceeded: bool,
+    callback: Box<dyn Fn(WatermarkEvent) + Send + Sync>,
+}
+
+type ValueCallback<T> = Box<dyn Fn(EntryId, &T) + Send + Sync>;
+
 #[derive(Default)]
 struct Inner<T>
 where
     T: Send + Sync,
 {
     map: BTreeMap<EntryId, T>,
+    deadlines: BTreeMap<EntryId, Instant>,
     next_id: EntryId,
     remove_callback: Option<Box<dyn Fn(EntryId, T) + Send + Sync>>,
+    register_callback: Option<ValueCallback<T>>,
+    move_callback: Option<ValueCallback<T>>,
+    len_observer: Option<LenObserver>,
+}
+
+impl<T> Inner<T>
+where
+    T: Send + Sync,
+{
+    fn insert(&mut self, value: T) -> EntryId {
+        let entry_id = self.next_id;
+        if let Some(callback) = &self.register_callback {
+            callback(entry_id, &value);
+        }
+        self.map.insert(entry_id, value);
+        self.next_id += 1;
+        self.observe_len();
+        entry_id
+    }
+
+    fn observe_len(&mut self) {
+        let Some(observer) = &mut self.len_observer else {
+            return;
+        };
+        let len = self.map.len();
+        if !observer.exceeded && len >= observer.watermarks.high {
+            observer.exceeded = true;
+            (observer.callback)(WatermarkEvent::Exceeded { len });
+        } else if observer.exceeded && len <= observer.watermarks.low {
+            observer.exceeded = false;
+            (observer.callback)(WatermarkEvent::Recovered { len });
+        }
+    }
 }
 
 impl<T: 'static> RegistryInterface for Inner<T>
@@ -141,10 +362,33 @@ where
         }
     }
     fn remove(&mut self, entry_id: EntryId) {
+        self.deadlines.remove(&entry_id);
         if let Some(value) = self.map.remove(&entry_id) {
             if let Some(callback) = &self.remove_callback {
                 callback(entry_id, value);
             }
+            self.observe_len();
+        }
+    }
+    fn take(&mut self, entry_id: EntryId) -> Option<Box<dyn Any>> {
+        self.deadlines.remove(&entry_id);
+        let value = self.map.remove(&entry_id)?;
+        if let Some(callback) = &self.move_callback {
+            callback(entry_id, &value);
+        }
+        self.observe_len();
+        Some(Box::new(value))
+    }
+    fn deadline(&self, entry_id: EntryId) -> Option<Instant> {
+        self.deadlines.get(&entry_id).copied()
+    }
+    fn set_deadline(&mut self, entry_id: EntryId, deadline: Instant) -> bool {
+        match self.deadlines.get_mut(&entry_id) {
+            Some(current) => {
+                *current = deadline;
+                true
+            }
+            None => false,
         }
     }
 }
@@ -161,9 +405,9 @@ impl<'a, T> RegistryReadGuard<'a, T>
 where
     T: Send + Sync,
 {
-    /// Acquires an iterator over the registry.
-    pub fn iter(&'a self) -> std::collections::btree_map::Iter<'a, EntryId, T> {
-        self.guard.map.iter()
+    /// Acquires an iterator over the registry. See [`RegistryIter`] for the iteration order.
+    pub fn iter(&'a self) -> RegistryIter<'a, T> {
+        RegistryIter::new(self.guard.map.iter())
     }
 
     /// Acquires a reference to an element from the registry.
@@ -184,14 +428,14 @@ impl<'a, T> RegistryWriteGuard<'a, T>
 where
     T: Send + Sync,
 {
-    /// Acquires an iterator over the registry.
-    pub fn iter(&'a self) -> std::collections::btree_map::Iter<'a, EntryId, T> {
-        self.guard.map.iter()
+    /// Acquires an iterator over the registry. See [`RegistryIter`] for the iteration order.
+    pub fn iter(&'a self) -> RegistryIter<'a, T> {
+        RegistryIter::new(self.guard.map.iter())
     }
 
-    /// Acquires a mutable iterator to the registry.
-    pub fn iter_mut(&mut self) -> std::collections::btree_map::IterMut<'_, EntryId, T> {
-        self.guard.map.iter_mut()
+    /// Acquires a mutable iterator to the registry. See [`RegistryIter`] for the iteration order.
+    pub fn iter_mut(&mut self) -> RegistryIterMut<'_, T> {
+        RegistryIterMut::new(self.guard.map.iter_mut())
     }
 
     /// Acquires a reference to an element from the registry.
@@ -203,12 +447,168 @@ where
     pub fn get_mut(&'a mut self, key: EntryId) -> Option<&'a mut T> {
         self.guard.map.get_mut(&key)
     }
+
+    /// Removes the elements for which `f` returns false, without waiting for their [`Entry`] to be dropped.
+    /// The remove callback is called for each removed element (see [`Registry::set_remove_callback()`]).
+    /// The entries of removed elements stay valid, but they no longer grant access to the element.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(EntryId, &mut T) -> bool,
+    {
+        let removed = self
+            .guard
+            .map
+            .iter_mut()
+            .filter_map(|(entry_id, value)| (!f(*entry_id, value)).then_some(*entry_id))
+            .collect::<Vec<_>>();
+        for entry_id in removed {
+            self.guard.remove(entry_id);
+        }
+    }
 }
 
+/// Iterator over the elements of a registry in the order of registration,
+/// or in a shuffled order if shuffling is enabled with the `shuffle` feature (see `adar_registry::shuffle`).
+/// Like the iterator of a `BTreeMap`, it can be reversed and it knows its length. See [`RegistryReadGuard::iter()`].
+pub struct RegistryIter<'a, T> {
+    inner: Order<btree_map::Iter<'a, EntryId, T>, (&'a EntryId, &'a T)>,
+}
+
+/// Mutable iterator over the elements of a registry. See [`RegistryWriteGuard::iter_mut()`].
+pub struct RegistryIterMut<'a, T> {
+    inner: Order<btree_map::IterMut<'a, EntryId, T>, (&'a EntryId, &'a mut T)>,
+}
+
+#[derive(Clone)]
+enum Order<I, Item> {
+    Registration(I),
+    #[cfg_attr(not(feature = "shuffle"), allow(dead_code))]
+    Shuffled(std::vec::IntoIter<Item>),
+}
+
+impl<I> Order<I, I::Item>
+where
+    I: Iterator,
+{
+    fn new(iter: I) -> Self {
+        #[cfg(feature = "shuffle")]
+        if shuffle::is_enabled() {
+            let mut items = iter.collect::<Vec<_>>();
+            shuffle::shuffle(&mut items);
+            return Order::Shuffled(items.into_iter());
+        }
+        Order::Registration(iter)
+    }
+
+    fn next(&mut self) -> Option<I::Item> {
+        match self {
+            Order::Registration(iter) => iter.next(),
+            Order::Shuffled(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Order::Registration(iter) => iter.size_hint(),
+            Order::Shuffled(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl<I> Order<I, I::Item>
+where
+    I: DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<I::Item> {
+        match self {
+            Order::Registration(iter) => iter.next_back(),
+            Order::Shuffled(iter) => iter.next_back(),
+        }
+    }
+}
+
+impl<T> Clone for RegistryIter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, T> RegistryIter<'a, T> {
+    fn new(iter: btree_map::Iter<'a, EntryId, T>) -> Self {
+        Self {
+            inner: Order::new(iter),
+        }
+    }
+}
+
+impl<'a, T> Iterator for RegistryIter<'a, T> {
+    type Item = (&'a EntryId, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for RegistryIter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for RegistryIter<'_, T> {}
+
+impl<T> FusedIterator for RegistryIter<'_, T> {}
+
+impl<'a, T> RegistryIterMut<'a, T> {
+    fn new(iter: btree_map::IterMut<'a, EntryId, T>) -> Self {
+        Self {
+            inner: Order::new(iter),
+        }
+    }
+}
+
+impl<'a, T> Iterator for RegistryIterMut<'a, T> {
+    type Item = (&'a EntryId, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for RegistryIterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for RegistryIterMut<'_, T> {}
+
+impl<T> FusedIterator for RegistryIterMut<'_, T> {}
+
 pub(crate) trait RegistryInterface: Send + Sync {
     fn get(&self, entry_id: EntryId) -> Option<&dyn Any>;
     fn get_mut(&mut self, entry_id: EntryId) -> Option<&mut dyn Any>;
     fn remove(&mut self, entry_id: EntryId);
+    /// Removes the element without calling the remove callback. See [`Entry::move_to()`].
+    fn take(&mut self, entry_id: EntryId) -> Option<Box<dyn Any>>;
+    /// Returns the expiry of a TTL element. See [`Registry::register_with_ttl()`].
+    fn deadline(&self, _entry_id: EntryId) -> Option<Instant> {
+        None
+    }
+    /// Sets the expiry of a TTL element, returns false if the element has no TTL.
+    fn set_deadline(&mut self, _entry_id: EntryId, _deadline: Instant) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
@@ -301,6 +701,14 @@ mod tests {
             assert_eq!(iter.next(), Some((&0, &11)));
             assert_eq!(iter.next(), Some((&1, &22)));
             assert_eq!(iter.next(), None);
+
+            let iter = guard.iter();
+            assert_eq!(iter.len(), 2);
+            assert_eq!(
+                iter.clone().rev().collect::<Vec<_>>(),
+                [(&1, &22), (&0, &11)]
+            );
+            assert_eq!(iter.map(|(_, v)| *v).collect::<Vec<_>>(), [11, 22]);
         }
 
         let e3 = r.register(33);
@@ -355,6 +763,24 @@ mod tests {
         assert_eq!(*entries[2].read().unwrap().get(), 66);
         assert_eq!(*entries[3].read().unwrap().get(), 88);
     }
+
+    #[test]
+    fn test_registry_retain() {
+        let r = Registry::<i32>::new();
+        let removed = Arc::new(std::sync::Mutex::new(vec![]));
+        let removed2 = removed.clone();
+        r.set_remove_callback(move |_, value| removed2.lock().unwrap().push(value));
+        let entries = [r.register(1), r.register(2), r.register(3), r.register(4)];
+
+        r.write().retain(|_, value| *value % 2 == 0);
+        assert_eq!(r.len(), 2);
+        assert_eq!(*removed.lock().unwrap(), [1, 3]);
+        assert_eq!(*entries[1].read().unwrap().get(), 2);
+
+        drop(entries);
+        assert!(r.is_empty());
+        assert_eq!(*removed.lock().unwrap(), [1, 3, 2, 4]);
+    }
     #[test]
     fn test_attributes() {
         fn is_send_sync<T: Send + Sync>() {}
@@ -378,6 +804,28 @@ mod tests {
         assert_eq!(r.len(), 0);
     }
     #[test]
+    fn test_shared_entry() {
+        let r = Registry::<i32>::new();
+        let e1 = r.register(11).into_shared();
+        let e2 = e1.clone();
+        assert_eq!(e1.get_id(), e2.get_id());
+        assert_eq!(e2.share_count(), 2);
+        *e2.write().unwrap().get_mut() = 22;
+        assert_eq!(*e1.read().unwrap(), 22);
+
+        let Err(e1) = e1.try_into_entry() else {
+            panic!("Entry is still shared");
+        };
+        drop(e2);
+        assert_eq!(r.len(), 1);
+        let Ok(entry) = e1.try_into_entry() else {
+            panic!("Entry is no longer shared");
+        };
+        assert_eq!(*entry.read().unwrap(), 22);
+        drop(entry);
+        assert_eq!(r.len(), 0);
+    }
+    #[test]
     #[should_panic(expected = "Failed to downcast Entry")]
     fn test_generic_entry_read() {
         let r = Registry::<i32>::new();
@@ -407,4 +855,152 @@ mod tests {
         drop(r);
         assert!(entry.write().is_none());
     }
+
+    #[test]
+    fn test_observe_len() {
+        let r = Registry::<i32>::new();
+        let events = Arc::new(RwLock::new(vec![]));
+        let mut entries = vec![r.register(1), r.register(2), r.register(3)];
+        r.observe_len(Watermarks::new(3, 1), {
+            let events = events.clone();
+            move |event| events.write().unwrap().push(event)
+        });
+        assert_eq!(
+            *events.read().unwrap(),
+            [WatermarkEvent::Exceeded { len: 3 }]
+        );
+
+        entries.pop();
+        entries.push(r.register(4));
+        entries.push(r.register(5));
+        entries.truncate(2);
+        assert_eq!(events.read().unwrap().len(), 1);
+
+        entries.pop();
+        entries.push(r.register(6));
+        entries.push(r.register(7));
+        assert_eq!(
+            *events.read().unwrap(),
+            [
+                WatermarkEvent::Exceeded { len: 3 },
+                WatermarkEvent::Recovered { len: 1 },
+                WatermarkEvent::Exceeded { len: 3 },
+            ]
+        );
+        assert_eq!(
+            Watermarks::of_capacity(1000, 80, 90),
+            Watermarks::new(800, 800)
+        );
+    }
+
+    #[test]
+    fn test_move_to() {
+        let staging = Registry::<String>::new();
+        let live = Registry::<String>::new();
+        let events = Arc::new(RwLock::new(vec![]));
+        staging.set_remove_callback({
+            let events = events.clone();
+            move |_, value| events.write().unwrap().push(format!("removed {}", value))
+        });
+        staging.set_move_callback({
+            let events = events.clone();
+            move |_, value| events.write().unwrap().push(format!("moved {}", value))
+        });
+        live.set_register_callback({
+            let events = events.clone();
+            move |_, value| {
+                events
+                    .write()
+                    .unwrap()
+                    .push(format!("registered {}", value))
+            }
+        });
+
+        let _other = live.register("other".to_string());
+        let entry = staging.register("item".to_string());
+        let entry = entry.move_to(&live).unwrap();
+        assert!(staging.is_empty());
+        assert_eq!(live.len(), 2);
+        assert_eq!(*entry.read().unwrap(), "item");
+        assert_eq!(entry.get_id(), 1);
+
+        let entry = entry.move_to(&live).unwrap();
+        assert_eq!(entry.get_id(), 1);
+        drop(entry);
+        assert_eq!(live.len(), 1);
+        assert_eq!(
+            *events.read().unwrap(),
+            ["registered other", "moved item", "registered item"]
+        );
+
+        let map = crate::registry_map::RegistryMap::<u32, String>::new();
+        let entry = map.register(7, "mapped".to_string()).unwrap();
+        let entry = entry.move_to(&staging).unwrap();
+        assert!(map.read().get(&7).is_none());
+        assert_eq!(*entry.read().unwrap(), "mapped");
+
+        let orphan = Registry::<String>::new();
+        let entry = orphan.register("orphan".to_string());
+        drop(orphan);
+        assert!(entry.move_to(&live).is_none());
+
+        let entry = staging.register("retained".to_string());
+        staging.write().retain(|_, _| false);
+        assert!(entry.move_to(&live).is_none());
+        assert_eq!(live.len(), 1);
+    }
+
+    #[test]
+    fn test_ttl() {
+        let r = Registry::<i32>::new();
+        let removed = Arc::new(std::sync::Mutex::new(vec![]));
+        r.set_remove_callback({
+            let removed = removed.clone();
+            move |_, value| removed.lock().unwrap().push(value)
+        });
+        let permanent = r.register(1);
+        let expired = r.register_with_ttl(2, Duration::ZERO);
+        let renewed = r.register_with_ttl(3, Duration::ZERO);
+        let alive = r.register_with_ttl(4, Duration::from_secs(3600));
+
+        assert_eq!(permanent.remaining(), None);
+        assert_eq!(expired.remaining(), Some(Duration::ZERO));
+        assert!(alive.remaining().unwrap() > Duration::from_secs(3500));
+        assert!(!permanent.renew(Duration::from_secs(1)));
+        assert!(renewed.renew(Duration::from_secs(3600)));
+
+        assert_eq!(r.purge_expired(), 1);
+        assert_eq!(*removed.lock().unwrap(), [2]);
+        assert_eq!(r.len(), 3);
+        assert_eq!(expired.remaining(), None);
+        assert!(!expired.renew(Duration::from_secs(1)));
+        assert_eq!(*renewed.read().unwrap(), 3);
+
+        // The lease is not carried over to another registry
+        let other = Registry::<i32>::new();
+        let moved = alive.move_to(&other).unwrap();
+        assert_eq!(moved.remaining(), None);
+
+        drop(expired);
+        drop(r);
+        assert_eq!(renewed.remaining(), None);
+    }
+
+    #[test]
+    fn test_concurrent_move_to() {
+        let a = Registry::<u32>::new();
+        let b = Registry::<u32>::new();
+        let threads = [(a.clone(), b.clone()), (b.clone(), a.clone())].map(|(from, to)| {
+            std::thread::spawn(move || {
+                let mut entry = from.register(0);
+                for _ in 0..1000 {
+                    entry = entry.move_to(&to).unwrap();
+                    entry = entry.move_to(&from).unwrap();
+                }
+                entry
+            })
+        });
+        let entries = threads.map(|thread| thread.join().unwrap());
+        assert_eq!(a.len() + b.len(), entries.len());
+    }
 }
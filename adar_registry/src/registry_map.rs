@@ -4,13 +4,15 @@ use super::{
 };
 use std::{
     any::Any,
+    borrow::Borrow,
     cmp::Ord,
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     fmt::{self, Debug},
     sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak},
 };
 
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum RegistryMapError {
     KeyAlreadyExists,
 }
@@ -23,6 +25,24 @@ impl fmt::Display for RegistryMapError {
 
 impl std::error::Error for RegistryMapError {}
 
+/// Error returned by [`RegistryMap::register_all()`]. Contains the keys which were already registered or
+/// occurred multiple times in the same batch.
+#[derive(Debug)]
+pub struct BulkError<K> {
+    pub keys: Vec<K>,
+}
+
+impl<K> fmt::Display for BulkError<K>
+where
+    K: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Keys already exist in registry: {:?}", self.keys)
+    }
+}
+
+impl<K> std::error::Error for BulkError<K> where K: Debug {}
+
 /// [`RegistryMap`] is a map whose registered elements' lifetimes are controlled by the non-copyable [`Entry`] object.
 pub struct RegistryMap<K, T>
 where
@@ -106,15 +126,55 @@ where
         ))
     }
 
+    /// Registers multiple elements in the [`RegistryMap`] atomically. If any of the keys is already registered
+    /// (or occurs multiple times in `pairs`) no elements are registered.
+    ///
+    /// # Returns
+    /// [`Entry`] for each element in the order of `pairs`. If any of the keys conflict, [`BulkError`] is returned
+    /// with the conflicting keys.
+    #[must_use = "Entries will be immediately revoked if not used"]
+    pub fn register_all(
+        &self,
+        pairs: impl IntoIterator<Item = (K, T)>,
+    ) -> Result<Vec<Entry<T>>, BulkError<K>> {
+        let pairs = pairs.into_iter().collect::<Vec<_>>();
+        let mut lock = self.inner.write().unwrap();
+
+        let mut keys = BTreeSet::new();
+        let conflicts = pairs
+            .iter()
+            .filter(|(key, _)| lock.map.contains_key(key) || !keys.insert(key))
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        if !conflicts.is_empty() {
+            return Err(BulkError { keys: conflicts });
+        }
+
+        Ok(pairs
+            .into_iter()
+            .map(|(key, value)| {
+                let entry_id = lock.next_id;
+                lock.map.insert(key.clone(), value);
+                lock.entry_map.insert(entry_id, key);
+                lock.next_id += 1;
+
+                Entry::<T>::new(
+                    Arc::downgrade(&self.inner) as Weak<RwLock<dyn RegistryInterface + 'static>>,
+                    entry_id,
+                )
+            })
+            .collect())
+    }
+
     /// Creates a [`RegistryMapReadGuard`] which can be used to read the contents of the RegistryMap.
-    pub fn read(&self) -> RegistryMapReadGuard<K, T> {
+    pub fn read(&self) -> RegistryMapReadGuard<'_, K, T> {
         RegistryMapReadGuard::<K, T> {
             guard: self.inner.read().unwrap(),
         }
     }
 
     /// Creates a [`RegistryMapWriteGuard`] which can be used to write the contents of the RegistryMap.
-    pub fn write(&self) -> RegistryMapWriteGuard<K, T> {
+    pub fn write(&self) -> RegistryMapWriteGuard<'_, K, T> {
         RegistryMapWriteGuard::<K, T> {
             guard: self.inner.write().unwrap(),
         }
@@ -148,6 +208,7 @@ where
     map: BTreeMap<K, T>,
     entry_map: BTreeMap<EntryId, K>,
     next_id: EntryId,
+    #[allow(clippy::type_complexity)]
     remove_callback: Option<Box<dyn FnMut(EntryId, K, T) + Send + Sync>>,
 }
 
@@ -157,9 +218,7 @@ where
     K: Send + Sync + Ord,
 {
     fn get(&self, entry_id: u32) -> Option<&dyn Any> {
-        let Some(key) = self.entry_map.get(&entry_id) else {
-            return None;
-        };
+        let key = self.entry_map.get(&entry_id)?;
         if let Some(value) = self.map.get(key) {
             Some(value)
         } else {
@@ -167,9 +226,7 @@ where
         }
     }
     fn get_mut(&mut self, entry_id: EntryId) -> Option<&mut dyn Any> {
-        let Some(key) = self.entry_map.get(&entry_id) else {
-            return None;
-        };
+        let key = self.entry_map.get(&entry_id)?;
         if let Some(value) = self.map.get_mut(key) {
             Some(value)
         } else {
@@ -177,16 +234,21 @@ where
         }
     }
     fn remove(&mut self, entry_id: EntryId) {
-        let key = self
-            .entry_map
-            .remove(&entry_id)
-            .expect("Failed to find key for EntryId during removal!");
+        // The element may already be gone, e.g. moved by `Entry::move_to()`
+        let Some(key) = self.entry_map.remove(&entry_id) else {
+            return;
+        };
         if let Some(value) = self.map.remove(&key) {
             if let Some(callback) = &mut self.remove_callback {
                 callback(entry_id, key, value);
             }
         }
     }
+    fn take(&mut self, entry_id: EntryId) -> Option<Box<dyn Any>> {
+        let key = self.entry_map.remove(&entry_id)?;
+        let value = self.map.remove(&key)?;
+        Some(Box::new(value))
+    }
 }
 
 /// Holds a read guard to the RegistryMap. See [`RegistryMap::read()`].
@@ -208,7 +270,12 @@ where
     }
 
     /// Acquires a reference to an element from the RegistryMap.
-    pub fn get(&self, key: &K) -> Option<&T> {
+    /// The key may be any borrowed form of `K` (e.g. `&str` for `String` keys).
+    pub fn get<Q>(&self, key: &Q) -> Option<&T>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
         self.guard.map.get(key)
     }
 }
@@ -237,12 +304,57 @@ where
     }
 
     /// Acquires a reference to an element in the RegistryMap.
-    pub fn get(&self, key: &K) -> Option<&T> {
+    /// The key may be any borrowed form of `K` (e.g. `&str` for `String` keys).
+    pub fn get<Q>(&self, key: &Q) -> Option<&T>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
         self.guard.map.get(key)
     }
 
     /// Acquires a mutable reference to an element in the RegistryMap.
-    pub fn get_mut(&mut self, key: &K) -> Option<&mut T> {
+    /// The key may be any borrowed form of `K` (e.g. `&str` for `String` keys).
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut T>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
         self.guard.map.get_mut(key)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_all() {
+        let r = RegistryMap::<&'static str, i32>::new();
+        let entries = r.register_all([("a", 1), ("b", 2)]).unwrap();
+        assert_eq!(r.len(), 2);
+        assert_eq!(*entries[1].read().unwrap().get(), 2);
+
+        let Err(error) = r.register_all([("c", 3), ("a", 4), ("d", 5), ("d", 6)]) else {
+            panic!("Conflicting keys were registered");
+        };
+        assert_eq!(error.keys, vec!["a", "d"]);
+        assert_eq!(r.len(), 2);
+        assert!(r.read().get(&"c").is_none());
+
+        drop(entries);
+        assert!(r.is_empty());
+    }
+
+    #[test]
+    fn test_borrowed_key() {
+        let r = RegistryMap::<String, i32>::new();
+        let _entry = r.register("a".to_string(), 1).unwrap();
+        assert_eq!(r.read().get("a"), Some(&1));
+        assert_eq!(r.read().get("b"), None);
+
+        *r.write().get_mut("a").unwrap() = 2;
+        assert_eq!(r.write().get("a"), Some(&2));
+        assert_eq!(r.read().get(&"a".to_string()), Some(&2));
+    }
+}
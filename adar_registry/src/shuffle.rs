@@ -0,0 +1,143 @@
+//! Debug mode which shuffles the iteration order of [`Registry`](crate::registry::Registry) and the dispatch order
+//! of [`Event`](crate::event::Event) with a seed, so hidden order dependencies in observers and handlers surface
+//! in tests. Every order is derived from the seed, so a failure can be reproduced by running with the same seed.
+//!
+//! The seed is read from the `ADAR_SHUFFLE_SEED` environment variable for every thread,
+//! or set for the current thread with [`set_shuffle_seed()`]. The seed is logged to stderr when it's applied,
+//! an invalid `ADAR_SHUFFLE_SEED` panics.
+//!
+//! Only available with the `shuffle` feature, which is meant for tests (e.g. as a dev-dependency feature),
+//! so the order of production builds can't be changed through the environment.
+
+use std::{cell::Cell, sync::OnceLock};
+
+/// Name of the environment variable which enables shuffling for every thread.
+pub const SHUFFLE_SEED_VAR: &str = "ADAR_SHUFFLE_SEED";
+
+thread_local! {
+    /// Seed and the number of shuffles performed since it was set.
+    static STATE: Cell<Option<(u64, u64)>> = Cell::new(env_seed().map(|seed| (seed, 0)));
+}
+
+fn env_seed() -> Option<u64> {
+    static SEED: OnceLock<Option<u64>> = OnceLock::new();
+    *SEED.get_or_init(|| {
+        let value = match std::env::var(SHUFFLE_SEED_VAR) {
+            Ok(value) => value,
+            Err(std::env::VarError::NotPresent) => return None,
+            Err(err) => panic!("adar_registry: invalid {SHUFFLE_SEED_VAR}: {err}"),
+        };
+        // Ignoring a malformed seed would silently run the tests without shuffling
+        let seed = value.trim().parse().unwrap_or_else(|err| {
+            panic!("adar_registry: invalid {SHUFFLE_SEED_VAR}={value:?}, expected an unsigned integer: {err}")
+        });
+        eprintln!("adar_registry: shuffling iteration order with {SHUFFLE_SEED_VAR}={seed}");
+        Some(seed)
+    })
+}
+
+/// Enables shuffling with `seed` on the current thread, or disables it with `None`.
+/// Restarts the sequence of orders, so the same seed produces the same orders again.
+pub fn set_shuffle_seed(seed: Option<u64>) {
+    if let Some(seed) = seed {
+        eprintln!("adar_registry: shuffling iteration order with seed {seed}");
+    }
+    STATE.with(|state| state.set(seed.map(|seed| (seed, 0))));
+}
+
+/// Returns the seed used on the current thread, if shuffling is enabled.
+pub fn shuffle_seed() -> Option<u64> {
+    STATE.with(|state| state.get().map(|(seed, _)| seed))
+}
+
+/// Returns `true` if shuffling is enabled on the current thread.
+#[inline]
+pub(crate) fn is_enabled() -> bool {
+    STATE.with(|state| state.get().is_some())
+}
+
+/// Shuffles the items with the next order derived from the seed. Does nothing if shuffling is disabled.
+pub(crate) fn shuffle<T>(items: &mut [T]) {
+    let Some((seed, count)) = STATE.with(|state| state.get()) else {
+        return;
+    };
+    STATE.with(|state| state.set(Some((seed, count.wrapping_add(1)))));
+
+    let mut random = SplitMix64(seed ^ count.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    // Fisher-Yates
+    for i in (1..items.len()).rev() {
+        let j = (random.next() % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
+
+/// Small and fast pseudo random number generator. Not suitable for cryptographic purposes.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{event::Event, registry::Registry};
+    use std::sync::{Arc, Mutex};
+
+    fn orders(registry: &Registry<i32>, count: usize) -> Vec<Vec<i32>> {
+        (0..count)
+            .map(|_| registry.read().iter().map(|(_, v)| *v).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_shuffle_registry() {
+        let registry = Registry::new();
+        let _entries = (0..16).map(|i| registry.register(i)).collect::<Vec<_>>();
+        let sorted = (0..16).collect::<Vec<_>>();
+
+        set_shuffle_seed(Some(42));
+        assert_eq!(shuffle_seed(), Some(42));
+        let shuffled = orders(&registry, 4);
+        assert!(shuffled.iter().any(|order| *order != sorted));
+        assert!(shuffled.iter().all(|order| {
+            let mut order = order.clone();
+            order.sort();
+            order == sorted
+        }));
+
+        set_shuffle_seed(Some(42));
+        assert_eq!(orders(&registry, 4), shuffled);
+
+        set_shuffle_seed(None);
+        assert_eq!(orders(&registry, 1), [sorted]);
+    }
+
+    #[test]
+    fn test_shuffle_event() {
+        let event = Event::<()>::new();
+        let calls = Arc::new(Mutex::new(vec![]));
+        let _observers = (0..16)
+            .map(|i| {
+                let calls = calls.clone();
+                event.register_observer(move |_: &()| calls.lock().unwrap().push(i))
+            })
+            .collect::<Vec<_>>();
+
+        set_shuffle_seed(Some(7));
+        event.dispatch(());
+        let first = std::mem::take(&mut *calls.lock().unwrap());
+        assert_ne!(first, (0..16).collect::<Vec<_>>());
+
+        set_shuffle_seed(Some(7));
+        event.dispatch(());
+        assert_eq!(*calls.lock().unwrap(), first);
+        set_shuffle_seed(None);
+    }
+}
@@ -3,8 +3,15 @@ use crate::{
     event::{Event, EventObserver},
     registry::{Registry, RegistryReadGuard, RegistryWriteGuard},
 };
+use std::{
+    mem,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
 /// Event types emitted by a traced registry.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum TracedRegistryEvent {
     Register,
     UnRegister,
@@ -48,12 +55,20 @@ where
 {
     /// Creates a new traced registry.
     pub fn new() -> Self {
-        let registry = Registry::new();
+        let registry = Registry::<T>::new();
         let event = Event::new();
         let event2 = event.clone();
         registry.set_remove_callback(move |entry_id, value| {
             event2.dispatch((TracedRegistryEvent::UnRegister, entry_id, value));
         });
+        let event2 = event.clone();
+        registry.set_move_callback(move |entry_id, value| {
+            event2.dispatch((TracedRegistryEvent::UnRegister, entry_id, value.clone()));
+        });
+        let event2 = event.clone();
+        registry.set_register_callback(move |entry_id, value| {
+            event2.dispatch((TracedRegistryEvent::Register, entry_id, value.clone()));
+        });
         Self { registry, event }
     }
 
@@ -63,11 +78,13 @@ where
     /// [`Entry`] which controls the lifetime of the registered element.
     #[must_use = "Entry will be immediately revoked if not used"]
     pub fn register(&self, value: T) -> Entry<T> {
-        let entry = self.registry.register(value.clone());
-        self.event
-            .dispatch((TracedRegistryEvent::Register, entry.get_id(), value));
+        self.registry.register(value)
+    }
 
-        entry
+    /// Returns the underlying [`Registry`], e.g. to move entries into it with [`Entry::move_to()`].
+    /// Its callbacks are used by the traced registry and must not be overridden.
+    pub fn registry(&self) -> &Registry<T> {
+        &self.registry
     }
 
     /// Registers an observer to the [`Registry`].
@@ -82,6 +99,12 @@ where
         self.event.register_observer(observer)
     }
 
+    /// Creates a [`TracedRegistryBatcher`] which coalesces the events of the registry
+    /// into a single [`Batch`] notification per `window`.
+    pub fn batched(&self, window: Duration) -> TracedRegistryBatcher<T> {
+        TracedRegistryBatcher::new(self, window)
+    }
+
     /// Returns the number of elements in the registry.
     pub fn len(&self) -> usize {
         self.registry.len()
@@ -103,11 +126,148 @@ where
     }
 }
 
+/// Events coalesced by a [`TracedRegistryBatcher`] in the order they were dispatched.
+#[derive(Clone, Debug)]
+pub struct Batch<Args>(pub Vec<Args>);
+
+/// Observer-facing view of a [`TracedRegistry`] which coalesces the events within a time window
+/// into a single [`Batch`] notification. See [`TracedRegistry::batched()`].
+///
+/// The pending events are flushed when an event arrives after the window has elapsed,
+/// when [`TracedRegistryBatcher::poll()`] or [`TracedRegistryBatcher::flush()`] is called
+/// and when the batcher is dropped.
+pub struct TracedRegistryBatcher<T>
+where
+    T: Send + Sync + Clone + 'static,
+{
+    inner: Arc<BatcherInner<T>>,
+    _entry: Entry,
+}
+
+struct BatcherInner<T>
+where
+    T: Send + Sync + Clone + 'static,
+{
+    window: Duration,
+    pending: Mutex<Pending<T>>,
+    event: Event<Batch<(TracedRegistryEvent, EntryId, T)>>,
+}
+
+struct Pending<T> {
+    events: Vec<(TracedRegistryEvent, EntryId, T)>,
+    since: Option<Instant>,
+}
+
+impl<T> BatcherInner<T>
+where
+    T: Send + Sync + Clone + 'static,
+{
+    fn push(&self, event: (TracedRegistryEvent, EntryId, T)) {
+        let elapsed = {
+            let mut pending = self.pending.lock().unwrap();
+            pending.events.push(event);
+            pending.since.get_or_insert_with(Instant::now).elapsed() >= self.window
+        };
+        if elapsed {
+            self.flush();
+        }
+    }
+
+    fn poll(&self) -> bool {
+        let elapsed = self
+            .pending
+            .lock()
+            .unwrap()
+            .since
+            .is_some_and(|since| since.elapsed() >= self.window);
+        elapsed && self.flush()
+    }
+
+    fn flush(&self) -> bool {
+        let events = {
+            let mut pending = self.pending.lock().unwrap();
+            pending.since = None;
+            mem::take(&mut pending.events)
+        };
+        if events.is_empty() {
+            return false;
+        }
+        self.event.dispatch(Batch(events));
+        true
+    }
+}
+
+impl<T> TracedRegistryBatcher<T>
+where
+    T: Send + Sync + Clone + 'static,
+{
+    /// Creates a batcher observing the registry. See [`TracedRegistry::batched()`].
+    pub fn new(registry: &TracedRegistry<T>, window: Duration) -> Self {
+        let inner = Arc::new(BatcherInner {
+            window,
+            pending: Mutex::new(Pending {
+                events: Vec::new(),
+                since: None,
+            }),
+            event: Event::new(),
+        });
+        let entry = registry.register_observer({
+            let inner = inner.clone();
+            move |event: &(TracedRegistryEvent, EntryId, T)| inner.push(event.clone())
+        });
+        Self {
+            inner,
+            _entry: entry,
+        }
+    }
+
+    /// Registers an observer which receives the coalesced events.
+    ///
+    /// # Returns
+    /// [`Entry`] which controls the lifetime of the observer.
+    #[must_use = "Entry will be immediately revoked if not used"]
+    pub fn register_observer<O>(&self, observer: O) -> Entry
+    where
+        O: EventObserver<Batch<(TracedRegistryEvent, EntryId, T)>> + 'static,
+    {
+        self.inner.event.register_observer(observer)
+    }
+
+    /// Flushes the pending events if the window has elapsed since the first of them arrived.
+    ///
+    /// # Returns
+    /// `true` if a [`Batch`] was dispatched.
+    pub fn poll(&self) -> bool {
+        self.inner.poll()
+    }
+
+    /// Flushes the pending events regardless of the window.
+    ///
+    /// # Returns
+    /// `true` if a [`Batch`] was dispatched.
+    pub fn flush(&self) -> bool {
+        self.inner.flush()
+    }
+
+    /// Returns the number of events waiting to be flushed.
+    pub fn pending(&self) -> usize {
+        self.inner.pending.lock().unwrap().events.len()
+    }
+}
+
+impl<T> Drop for TracedRegistryBatcher<T>
+where
+    T: Send + Sync + Clone + 'static,
+{
+    fn drop(&mut self) {
+        self.inner.flush();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::sync::atomic::{AtomicUsize, Ordering};
-    use std::sync::Arc;
 
     #[test]
     fn test_attributes() {
@@ -161,4 +321,90 @@ mod tests {
         assert_eq!(counter1.load(Ordering::Relaxed), 2);
         assert_eq!(counter2.load(Ordering::Relaxed), 2);
     }
+
+    #[test]
+    fn test_move_between_traced_registries() {
+        let staging = TracedRegistry::new();
+        let live = TracedRegistry::new();
+        let events = Arc::new(Mutex::new(vec![]));
+        let observers = [("staging", &staging), ("live", &live)].map(|(name, registry)| {
+            let events = events.clone();
+            registry.register_observer(
+                move |(event, _, value): &(TracedRegistryEvent, EntryId, i32)| {
+                    events
+                        .lock()
+                        .unwrap()
+                        .push(format!("{} {:?} {}", name, event, value))
+                },
+            )
+        });
+
+        let entry = staging.register(1).move_to(live.registry()).unwrap();
+        drop(entry);
+        drop(observers);
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                "staging Register 1",
+                "staging UnRegister 1",
+                "live Register 1",
+                "live UnRegister 1"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_batched() {
+        let registry = TracedRegistry::new();
+        let batcher = registry.batched(Duration::from_secs(3600));
+        let batches = Arc::new(Mutex::new(vec![]));
+        let _observer = batcher.register_observer({
+            let batches = batches.clone();
+            move |batch: &Batch<(TracedRegistryEvent, EntryId, i32)>| {
+                batches.lock().unwrap().push(
+                    batch
+                        .0
+                        .iter()
+                        .map(|(event, _, value)| {
+                            (matches!(event, TracedRegistryEvent::Register), *value)
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            }
+        });
+
+        let entries = [registry.register(1), registry.register(2)];
+        drop(entries);
+        assert_eq!(batcher.pending(), 4);
+        assert!(!batcher.poll());
+        assert!(batches.lock().unwrap().is_empty());
+
+        assert!(batcher.flush());
+        assert!(!batcher.flush());
+        assert_eq!(
+            *batches.lock().unwrap(),
+            [vec![(true, 1), (true, 2), (false, 1), (false, 2)]]
+        );
+
+        let _entry = registry.register(3);
+        drop(batcher);
+        assert_eq!(batches.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_batched_window() {
+        let registry = TracedRegistry::new();
+        let batcher = registry.batched(Duration::ZERO);
+        let counter = Arc::new(AtomicUsize::new(0));
+        let _observer = batcher.register_observer({
+            let counter = counter.clone();
+            move |batch: &Batch<_>| {
+                counter.fetch_add(batch.0.len(), Ordering::Relaxed);
+            }
+        });
+
+        let _entry = registry.register(TestData { value: 1 });
+        assert_eq!(counter.load(Ordering::Relaxed), 1);
+        assert_eq!(batcher.pending(), 0);
+    }
 }
@@ -0,0 +1,138 @@
+use crate::event::EventObserver;
+use std::fmt::Debug;
+
+/// Observer which emits a [`tracing`] event for every dispatched event.
+/// The event has an `event` field with the name of the observer and an `args` field with the [`Debug`] formatted arguments.
+///
+/// # Example
+/// ```
+/// use adar_registry::prelude::*;
+///
+/// let registry = TracedRegistry::<&'static str>::new();
+/// let _observer = registry.register_observer(TracingObserver::new(tracing::Level::INFO).named("plugins"));
+/// let _entry = registry.register("weather");
+/// ```
+#[derive(Clone, Debug)]
+pub struct TracingObserver {
+    level: tracing::Level,
+    name: &'static str,
+}
+
+impl TracingObserver {
+    /// Creates an observer which emits events at `level`.
+    pub fn new(level: tracing::Level) -> Self {
+        Self { level, name: "" }
+    }
+
+    /// Sets the value of the `event` field.
+    pub fn named(mut self, name: &'static str) -> Self {
+        self.name = name;
+        self
+    }
+}
+
+impl<Args> EventObserver<Args> for TracingObserver
+where
+    Args: Debug,
+{
+    fn notify(&self, args: &Args) {
+        // The level of a tracing event must be a constant
+        match self.level {
+            tracing::Level::TRACE => {
+                tracing::event!(tracing::Level::TRACE, event = self.name, args = ?args)
+            }
+            tracing::Level::DEBUG => {
+                tracing::event!(tracing::Level::DEBUG, event = self.name, args = ?args)
+            }
+            tracing::Level::INFO => {
+                tracing::event!(tracing::Level::INFO, event = self.name, args = ?args)
+            }
+            tracing::Level::WARN => {
+                tracing::event!(tracing::Level::WARN, event = self.name, args = ?args)
+            }
+            tracing::Level::ERROR => {
+                tracing::event!(tracing::Level::ERROR, event = self.name, args = ?args)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::Event;
+    use std::{
+        fmt::Write,
+        sync::{Arc, Mutex},
+    };
+    use tracing::{
+        field::{Field, Visit},
+        span, Metadata, Subscriber,
+    };
+
+    #[derive(Default)]
+    struct TestSubscriber {
+        events: Arc<Mutex<Vec<(tracing::Level, String)>>>,
+    }
+
+    struct TestVisitor(String);
+
+    impl Visit for TestVisitor {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            write!(self.0, "{}={:?};", field.name(), value).unwrap();
+        }
+    }
+
+    impl Subscriber for TestSubscriber {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+            span::Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            let mut visitor = TestVisitor(String::new());
+            event.record(&mut visitor);
+            self.events
+                .lock()
+                .unwrap()
+                .push((*event.metadata().level(), visitor.0));
+        }
+
+        fn enter(&self, _span: &span::Id) {}
+
+        fn exit(&self, _span: &span::Id) {}
+    }
+
+    #[test]
+    fn test_tracing_observer() {
+        let subscriber = TestSubscriber::default();
+        let events = subscriber.events.clone();
+        tracing::subscriber::with_default(subscriber, || {
+            let event = Event::<(u32, bool)>::new();
+            let _info = event.register_observer(TracingObserver::new(tracing::Level::INFO));
+            let _error = event
+                .register_observer(TracingObserver::new(tracing::Level::ERROR).named("failure"));
+            event.dispatch((1, true));
+        });
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                (
+                    tracing::Level::INFO,
+                    "event=\"\";args=(1, true);".to_string()
+                ),
+                (
+                    tracing::Level::ERROR,
+                    "event=\"failure\";args=(1, true);".to_string()
+                ),
+            ]
+        );
+    }
+}